@@ -1,11 +1,14 @@
+use crate::did_resolver;
 use qsb_runtime::{
-    AccountId, AuraConfig, BalancesConfig, GrandpaConfig, RuntimeGenesisConfig, Signature,
-    SudoConfig, SystemConfig, WASM_BINARY,
+    did::{EndpointInput, GenesisDid, KeyRole, KeyType},
+    AccountId, AuraConfig, BalancesConfig, DidConfig, GrandpaConfig, RevocationListConfig, Runtime,
+    RuntimeGenesisConfig, SchemaConfig, Signature, SudoConfig, SystemConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
+use serde_json::Value;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
-use sp_core::{sr25519, Pair, Public};
+use sp_core::{ed25519, sr25519, Pair, Public};
 use sp_runtime::traits::{IdentifyAccount, Verify};
 
 // The URL for the telemetry server.
@@ -36,8 +39,211 @@ pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
     (get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
 }
 
+/// Environment variable naming a JSON file that replaces the built-in genesis state of the
+/// identity pallets. The file may hold `did`, `schema` and `revocationList` sections in the
+/// format of the pallet genesis configs; a missing section leaves the pallet empty. DIDs take
+/// the network segment of the chain spec unless the `did` section names one.
+const IDENTITY_GENESIS_ENV: &str = "QSB_IDENTITY_GENESIS";
+
+/// Membership credential schema registered at genesis by the first founder.
+const MEMBERSHIP_SCHEMA: &str = concat!(
+    r#"{"$schema":"https://json-schema.org/draft/2020-12/schema","#,
+    r#""title":"Membership","type":"object","#,
+    r#""properties":{"member":{"type":"string"},"since":{"type":"string","format":"date"}},"#,
+    r#""required":["member"]}"#,
+);
+
+/// Genesis state of the identity pallets.
+type IdentityGenesis = (DidConfig, SchemaConfig, RevocationListConfig);
+
+/// Ed25519 key a founder's genesis DID is derived from.
+fn founder_key(seed: &str) -> Vec<u8> {
+    get_from_seed::<ed25519::Public>(seed).as_ref().to_vec()
+}
+
+/// Built-in identities: a DID for each founder, a membership schema and an empty status
+/// list, both issued by the first founder. DIDs carry the `network` segment, and identifiers
+/// are derived from `chain_id`.
+fn default_identity_genesis(founders: &[&str], network: &str, chain_id: &str) -> IdentityGenesis {
+    let dids = founders
+        .iter()
+        .map(|seed| GenesisDid {
+            controllers: vec![get_account_id_from_seed::<sr25519::Public>(seed)],
+            keys: vec![(
+                KeyType::Ed25519.name().into(),
+                founder_key(seed),
+                [
+                    KeyRole::Authentication,
+                    KeyRole::AssertionMethod,
+                    KeyRole::CapabilityInvocation,
+                ]
+                .iter()
+                .map(|role| role.name().into())
+                .collect(),
+            )],
+            services: vec![(
+                b"#linked-domain".to_vec(),
+                b"LinkedDomains".to_vec(),
+                EndpointInput::Uri(b"https://quantumblockchains.io".to_vec()),
+            )],
+        })
+        .collect();
+    let issuer = did_resolver::did_string(
+        &did::Pallet::<Runtime>::genesis_did_id(chain_id.as_bytes(), &founder_key(founders[0])),
+        Some(network),
+    )
+    .into_bytes();
+
+    (
+        DidConfig {
+            network: Some(network.as_bytes().to_vec()),
+            allowed_service_types: None,
+            chain_id: chain_id.as_bytes().to_vec(),
+            dids,
+        },
+        SchemaConfig {
+            chain_id: chain_id.as_bytes().to_vec(),
+            schemas: vec![(
+                MEMBERSHIP_SCHEMA.as_bytes().to_vec(),
+                b"urn:qsb:schema:membership".to_vec(),
+                issuer.clone(),
+            )],
+            ..Default::default()
+        },
+        RevocationListConfig {
+            chain_id: chain_id.as_bytes().to_vec(),
+            status_lists: vec![(issuer, b"qsb-genesis-status-list".to_vec(), 8192)],
+            ..Default::default()
+        },
+    )
+}
+
+/// Reads the file named by [`IDENTITY_GENESIS_ENV`], if set, and checks it for a chain whose
+/// DIDs carry the `network` segment and whose identifiers are derived from `chain_id`.
+fn load_identity_genesis(network: &str, chain_id: &str) -> Result<Option<Value>, String> {
+    let Ok(path) = std::env::var(IDENTITY_GENESIS_ENV) else {
+        return Ok(None);
+    };
+    let file = std::fs::File::open(&path)
+        .map_err(|e| format!("Cannot open {IDENTITY_GENESIS_ENV} file {path}: {e}"))?;
+    let value = serde_json::from_reader(std::io::BufReader::new(file))
+        .map_err(|e| format!("Invalid JSON in {IDENTITY_GENESIS_ENV} file {path}: {e}"))?;
+    loaded_identity_genesis(&value, network, chain_id)?;
+    Ok(Some(value))
+}
+
+/// Identity genesis of a loaded file. DIDs take the `network` segment unless the `did`
+/// section names one. Identifiers are always derived from `chain_id`, the id of the chain spec
+/// the file is loaded into, so that a file reused for another chain yields other identifiers.
+fn loaded_identity_genesis(
+    value: &Value,
+    network: &str,
+    chain_id: &str,
+) -> Result<IdentityGenesis, String> {
+    let (mut did, mut schema, mut revocation_list) = parse_identity_genesis(value)?;
+    did.network
+        .get_or_insert_with(|| network.as_bytes().to_vec());
+    did.chain_id = chain_id.as_bytes().to_vec();
+    schema.chain_id = did.chain_id.clone();
+    revocation_list.chain_id = did.chain_id.clone();
+    let genesis = (did, schema, revocation_list);
+    check_issuers(&genesis)?;
+    Ok(genesis)
+}
+
+fn parse_identity_genesis(value: &Value) -> Result<IdentityGenesis, String> {
+    let invalid = |section: &str, e: serde_json::Error| {
+        format!("Invalid `{section}` section in {IDENTITY_GENESIS_ENV} file: {e}")
+    };
+    let did: DidConfig = value
+        .get("did")
+        .map(|section| serde_json::from_value(section.clone()))
+        .transpose()
+        .map_err(|e| invalid("did", e))?
+        .unwrap_or_default();
+    let schema: SchemaConfig = value
+        .get("schema")
+        .map(|section| serde_json::from_value(section.clone()))
+        .transpose()
+        .map_err(|e| invalid("schema", e))?
+        .unwrap_or_default();
+    let revocation_list: RevocationListConfig = value
+        .get("revocationList")
+        .map(|section| serde_json::from_value(section.clone()))
+        .transpose()
+        .map_err(|e| invalid("revocationList", e))?
+        .unwrap_or_default();
+    Ok((did, schema, revocation_list))
+}
+
+/// Checks that schemas and status lists are issued by DIDs of the `did` section holding an
+/// `AssertionMethod` key, as they must be to issue them after launch.
+fn check_issuers((did, schema, revocation_list): &IdentityGenesis) -> Result<(), String> {
+    let network = did
+        .network
+        .as_deref()
+        .map(|network| String::from_utf8_lossy(network).into_owned());
+    let issuers: Vec<String> = did
+        .dids
+        .iter()
+        .filter(|genesis_did| {
+            genesis_did.keys.iter().any(|(_, _, roles)| {
+                roles
+                    .iter()
+                    .any(|role| KeyRole::from_name(role) == Some(KeyRole::AssertionMethod))
+            })
+        })
+        .filter_map(|genesis_did| genesis_did.keys.first())
+        .map(|(_, public_key, _)| {
+            did_resolver::did_string(
+                &did::Pallet::<Runtime>::genesis_did_id(&did.chain_id, public_key),
+                network.as_deref(),
+            )
+        })
+        .collect();
+    let issued = schema
+        .schemas
+        .iter()
+        .map(|(_, _, issuer)| ("schema", issuer))
+        .chain(
+            revocation_list
+                .status_lists
+                .iter()
+                .map(|(issuer, _, _)| ("status list", issuer)),
+        );
+    for (entry, issuer) in issued {
+        if !issuers
+            .iter()
+            .any(|did| did.as_bytes() == issuer.as_slice())
+        {
+            return Err(format!(
+                "Genesis {entry} issuer {} in {IDENTITY_GENESIS_ENV} file is not a genesis DID \
+                 with an AssertionMethod key",
+                String::from_utf8_lossy(issuer)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Genesis state of the identity pallets, from [`IDENTITY_GENESIS_ENV`] when it is set.
+fn identity_genesis(
+    loaded: &Option<Value>,
+    founders: &[&str],
+    network: &str,
+    chain_id: &str,
+) -> IdentityGenesis {
+    match loaded {
+        Some(value) => {
+            loaded_identity_genesis(value, network, chain_id).expect("validated when loaded; qed")
+        }
+        None => default_identity_genesis(founders, network, chain_id),
+    }
+}
+
 pub fn development_config() -> Result<ChainSpec, String> {
     let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+    let loaded_identity = load_identity_genesis("dev", "dev")?;
 
     Ok(ChainSpec::from_genesis(
         // Name
@@ -59,6 +265,7 @@ pub fn development_config() -> Result<ChainSpec, String> {
                     get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
                     get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
                 ],
+                identity_genesis(&loaded_identity, &["Alice"], "dev", "dev"),
                 true,
             )
         },
@@ -78,6 +285,7 @@ pub fn development_config() -> Result<ChainSpec, String> {
 
 pub fn local_testnet_config() -> Result<ChainSpec, String> {
     let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+    let loaded_identity = load_identity_genesis("local", "local_testnet")?;
 
     Ok(ChainSpec::from_genesis(
         // Name
@@ -110,6 +318,12 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
                     get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
                     get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
                 ],
+                identity_genesis(
+                    &loaded_identity,
+                    &["Alice", "Bob"],
+                    "local",
+                    "local_testnet",
+                ),
                 true,
             )
         },
@@ -133,6 +347,7 @@ fn testnet_genesis(
     initial_authorities: Vec<(AuraId, GrandpaId)>,
     root_key: AccountId,
     endowed_accounts: Vec<AccountId>,
+    (did, schema, revocation_list): IdentityGenesis,
     _enable_println: bool,
 ) -> RuntimeGenesisConfig {
     RuntimeGenesisConfig {
@@ -164,5 +379,8 @@ fn testnet_genesis(
             key: Some(root_key),
         },
         transaction_payment: Default::default(),
+        did,
+        schema,
+        revocation_list,
     }
 }
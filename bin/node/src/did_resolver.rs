@@ -0,0 +1,603 @@
+//! DID Core resolution of `did:qsb` identifiers.
+//!
+//! The runtime stores DID documents in a compact SCALE form. This module turns them into
+//! the JSON resolution result defined by W3C DID Core and DID Resolution, which is what
+//! wallets and verifiers consume.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, SecondsFormat, Utc};
+use did::{
+    DidKey, DidTombstone, Endpoint, KeyRole, KeyStatus, KeyType, KeyValidityBound, ServiceEndpoint,
+};
+use qsb_runtime::{BlockNumber, DidChange, DidDetails, Runtime};
+use serde_json::{json, Map, Value};
+
+/// Method prefix of every DID handled by this node.
+pub const DID_METHOD_PREFIX: &str = "did:qsb:";
+/// Prefix of names registered for DIDs in the name registry.
+pub const DID_NAME_PREFIX: &str = "did:qsb:name:";
+
+const RESOLUTION_CONTEXT: &str = "https://w3id.org/did-resolution/v1";
+const DID_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+const MULTIKEY_CONTEXT: &str = "https://w3id.org/security/multikey/v1";
+const JWS_2020_CONTEXT: &str = "https://w3id.org/security/suites/jws-2020/v1";
+const DID_LD_JSON: &str = "application/did+ld+json";
+
+/// Standard DID resolution error codes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ResolutionError {
+    InvalidDid,
+    InvalidDidUrl,
+    NotFound,
+}
+
+impl ResolutionError {
+    fn code(&self) -> &'static str {
+        match self {
+            ResolutionError::InvalidDid => "invalidDid",
+            ResolutionError::InvalidDidUrl => "invalidDidUrl",
+            ResolutionError::NotFound => "notFound",
+        }
+    }
+}
+
+/// Version of a DID document selected by the `versionId` and `versionTime` DID parameters.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VersionSelector {
+    Latest,
+    VersionId(u64),
+    /// Unix time in milliseconds.
+    VersionTime(u64),
+}
+
+/// A DID URL reduced to the parts that affect resolution.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DidUrl {
+    pub did_id: [u8; 32],
+    pub version: VersionSelector,
+}
+
+/// Parses a DID URL such as `did:qsb:<base58>?versionTime=2024-01-01T00:00:00Z` on a chain
+/// whose DIDs carry the `network` segment, if any. Fragments and unknown parameters are
+/// ignored.
+pub fn parse_did_url(input: &str, network: Option<&str>) -> Result<DidUrl, ResolutionError> {
+    let input = input.split_once('#').map_or(input, |(url, _)| url);
+    let (did, query) = input.split_once('?').unwrap_or((input, ""));
+    let did_id = parse_did(did, network)?;
+
+    let mut version = VersionSelector::Latest;
+    for (name, value) in query.split('&').filter_map(|param| param.split_once('=')) {
+        let selected = match name {
+            "versionId" => value
+                .parse()
+                .map(VersionSelector::VersionId)
+                .map_err(|_| ResolutionError::InvalidDidUrl)?,
+            "versionTime" => DateTime::parse_from_rfc3339(value)
+                .ok()
+                .and_then(|time| u64::try_from(time.timestamp_millis()).ok())
+                .map(VersionSelector::VersionTime)
+                .ok_or(ResolutionError::InvalidDidUrl)?,
+            _ => continue,
+        };
+        if version != VersionSelector::Latest {
+            return Err(ResolutionError::InvalidDidUrl);
+        }
+        version = selected;
+    }
+
+    Ok(DidUrl { did_id, version })
+}
+
+/// Splits a `did:qsb:name:<name>` DID URL into the name and the rest of the URL, starting
+/// at its query or fragment.
+pub fn split_name(input: &str) -> Option<(&str, &str)> {
+    let rest = input.strip_prefix(DID_NAME_PREFIX)?;
+    let end = rest.find(|c| c == '?' || c == '#').unwrap_or(rest.len());
+    Some(rest.split_at(end))
+}
+
+/// Parses a `did:qsb:<base58>` DID, or `did:qsb:<network>:<base58>` on a chain whose DIDs
+/// carry a network segment, into its 32-byte identifier. DIDs of other networks are not
+/// found on this chain.
+pub fn parse_did(did: &str, network: Option<&str>) -> Result<[u8; 32], ResolutionError> {
+    let rest = did
+        .strip_prefix(DID_METHOD_PREFIX)
+        .ok_or(ResolutionError::InvalidDid)?;
+    let id = match (rest.split_once(':'), network) {
+        (None, None) => rest,
+        (Some((segment, id)), Some(network)) if segment == network => id,
+        _ => return Err(ResolutionError::NotFound),
+    };
+    bs58::decode(id)
+        .into_vec()
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(ResolutionError::InvalidDid)
+}
+
+/// Canonical DID string for a 32-byte identifier on a chain whose DIDs carry the `network`
+/// segment, if any.
+pub fn did_string(did_id: &[u8; 32], network: Option<&str>) -> String {
+    let id = bs58::encode(did_id).into_string();
+    match network {
+        Some(network) => format!("{}{}:{}", DID_METHOD_PREFIX, network, id),
+        None => format!("{}{}", DID_METHOD_PREFIX, id),
+    }
+}
+
+/// Resolution result for a DID that could not be resolved.
+pub fn error_result(error: ResolutionError) -> Value {
+    json!({
+        "@context": RESOLUTION_CONTEXT,
+        "didDocument": Value::Null,
+        "didDocumentMetadata": {},
+        "didResolutionMetadata": { "error": error.code() },
+    })
+}
+
+/// Point in time keys are checked against: a block number and a Unix time in milliseconds.
+pub type Instant = (BlockNumber, u64);
+
+/// Resolution result for a registered DID. `next` is the change following `details` when
+/// an older version of the document was requested. Keys that are not valid at `at` are left
+/// out of the document.
+pub fn resolution_result(
+    did: &str,
+    details: &DidDetails,
+    next: Option<&DidChange>,
+    at: Instant,
+) -> Value {
+    json!({
+        "@context": RESOLUTION_CONTEXT,
+        "didDocument": did_document(did, details, at),
+        "didDocumentMetadata": document_metadata(details, next),
+        "didResolutionMetadata": { "contentType": DID_LD_JSON },
+    })
+}
+
+/// Resolution result for a deleted DID. Only the identifier of the document is left.
+pub fn tombstone_result(did: &str, tombstone: &DidTombstone) -> Value {
+    let mut metadata = Map::new();
+    metadata.insert(
+        "versionId".into(),
+        Value::String(tombstone.version.to_string()),
+    );
+    metadata.insert("deactivated".into(), Value::Bool(true));
+    if let Some(updated) = format_timestamp(tombstone.deleted) {
+        metadata.insert("updated".into(), Value::String(updated));
+    }
+    json!({
+        "@context": RESOLUTION_CONTEXT,
+        "didDocument": { "@context": [DID_CONTEXT], "id": did },
+        "didDocumentMetadata": Value::Object(metadata),
+        "didResolutionMetadata": { "contentType": DID_LD_JSON },
+    })
+}
+
+fn did_document(did: &str, details: &DidDetails, (block, moment): Instant) -> Value {
+    let mut verification_methods = Vec::new();
+    let mut relationships: [(KeyRole, &str, Vec<Value>); 5] = [
+        (KeyRole::Authentication, "authentication", Vec::new()),
+        (KeyRole::AssertionMethod, "assertionMethod", Vec::new()),
+        (KeyRole::KeyAgreement, "keyAgreement", Vec::new()),
+        (
+            KeyRole::CapabilityInvocation,
+            "capabilityInvocation",
+            Vec::new(),
+        ),
+        (
+            KeyRole::CapabilityDelegation,
+            "capabilityDelegation",
+            Vec::new(),
+        ),
+    ];
+
+    // Relationships refer to the verification methods by id rather than embedding them.
+    // Legacy keys are left out, as no verification method type can be given for them.
+    for key in details.keys.iter() {
+        if key.key_type == KeyType::Legacy
+            || did::Pallet::<Runtime>::key_status_at(key, block, moment) != KeyStatus::Valid
+        {
+            continue;
+        }
+        let key_id = format!("{}{}", did, String::from_utf8_lossy(&key.id));
+        for (role, _, references) in relationships.iter_mut() {
+            if key.roles.contains(role) {
+                references.push(Value::String(key_id.clone()));
+            }
+        }
+        verification_methods.push(verification_method(did, &key_id, key));
+    }
+
+    let mut document = Map::new();
+    document.insert(
+        "@context".into(),
+        json!([DID_CONTEXT, MULTIKEY_CONTEXT, JWS_2020_CONTEXT]),
+    );
+    document.insert("id".into(), Value::String(did.to_string()));
+    document.insert(
+        "verificationMethod".into(),
+        Value::Array(verification_methods),
+    );
+    for (_, name, references) in relationships {
+        if !references.is_empty() {
+            document.insert(name.into(), Value::Array(references));
+        }
+    }
+    if !details.services.is_empty() {
+        let services = details
+            .services
+            .iter()
+            .map(|service| service_entry(did, service))
+            .collect();
+        document.insert("service".into(), Value::Array(services));
+    }
+    Value::Object(document)
+}
+
+fn verification_method(did: &str, key_id: &str, key: &DidKey<Runtime>) -> Value {
+    let mut method = Map::new();
+    method.insert("id".into(), Value::String(key_id.to_string()));
+    method.insert(
+        "type".into(),
+        Value::String(key.key_type.verification_method_type().into()),
+    );
+    // A controller DID lives on the same network as the DID, so it shares its prefix.
+    let controller = match (&key.controller, did.rsplit_once(':')) {
+        (Some(controller), Some((prefix, _))) => {
+            format!("{}:{}", prefix, bs58::encode(controller).into_string())
+        }
+        _ => did.to_string(),
+    };
+    method.insert("controller".into(), Value::String(controller));
+    if let Some(KeyValidityBound::Moment(valid_until)) = key.valid_until {
+        if let Some(expires) = format_timestamp(valid_until) {
+            method.insert("expires".into(), Value::String(expires));
+        }
+    }
+    match multicodec_prefix(key.key_type) {
+        Some(prefix) => {
+            let mut bytes = prefix.to_vec();
+            bytes.extend_from_slice(&key.public_key);
+            method.insert(
+                "publicKeyMultibase".into(),
+                Value::String(format!("z{}", bs58::encode(bytes).into_string())),
+            );
+        }
+        None => {
+            method.insert(
+                "publicKeyJwk".into(),
+                json!({
+                    "kty": "AKP",
+                    "alg": jwk_algorithm(key.key_type),
+                    "pub": URL_SAFE_NO_PAD.encode(key.public_key.as_slice()),
+                }),
+            );
+        }
+    }
+    Value::Object(method)
+}
+
+fn service_entry(did: &str, service: &ServiceEndpoint<Runtime>) -> Value {
+    let id = String::from_utf8_lossy(&service.id);
+    let id = if id.starts_with('#') {
+        format!("{}{}", did, id)
+    } else if id.contains(':') {
+        id.into_owned()
+    } else {
+        format!("{}#{}", did, id)
+    };
+    let uri = |uri: &[u8]| Value::String(String::from_utf8_lossy(uri).into_owned());
+    let endpoint = match &service.endpoint {
+        Endpoint::Uri(endpoint) => uri(endpoint),
+        Endpoint::Set(endpoints) => endpoints.iter().map(|endpoint| uri(endpoint)).collect(),
+        Endpoint::Map(entries) => Value::Object(
+            entries
+                .iter()
+                .map(|(key, endpoint)| (String::from_utf8_lossy(key).into_owned(), uri(endpoint)))
+                .collect(),
+        ),
+    };
+    json!({
+        "id": id,
+        "type": String::from_utf8_lossy(&service.service_type),
+        "serviceEndpoint": endpoint,
+    })
+}
+
+fn document_metadata(details: &DidDetails, next: Option<&DidChange>) -> Value {
+    let mut metadata = Map::new();
+    metadata.insert(
+        "versionId".into(),
+        Value::String(details.version.to_string()),
+    );
+    metadata.insert("deactivated".into(), Value::Bool(details.deactivated));
+    if let Some(created) = format_timestamp(details.created) {
+        metadata.insert("created".into(), Value::String(created));
+    }
+    if let Some(updated) = format_timestamp(details.updated) {
+        metadata.insert("updated".into(), Value::String(updated));
+    }
+    if let Some(next) = next {
+        metadata.insert(
+            "nextVersionId".into(),
+            Value::String(next.version.to_string()),
+        );
+        if let Some(next_update) = format_timestamp(next.timestamp) {
+            metadata.insert("nextUpdate".into(), Value::String(next_update));
+        }
+    }
+    Value::Object(metadata)
+}
+
+/// Multicodec header of keys published as `publicKeyMultibase`.
+fn multicodec_prefix(key_type: KeyType) -> Option<&'static [u8]> {
+    match key_type {
+        KeyType::Ed25519 => Some(&[0xed, 0x01]),
+        KeyType::Sr25519 => Some(&[0xef, 0x01]),
+        KeyType::EcdsaSecp256k1 => Some(&[0xe7, 0x01]),
+        _ => None,
+    }
+}
+
+/// JOSE algorithm name of keys published as `publicKeyJwk`.
+fn jwk_algorithm(key_type: KeyType) -> &'static str {
+    match key_type {
+        KeyType::MlDsa44 => "ML-DSA-44",
+        KeyType::MlDsa65 => "ML-DSA-65",
+        KeyType::MlDsa87 => "ML-DSA-87",
+        KeyType::Falcon512 => "FN-DSA-512",
+        KeyType::SlhDsa128s => "SLH-DSA-SHA2-128s",
+        KeyType::MlKem512 => "ML-KEM-512",
+        KeyType::MlKem768 => "ML-KEM-768",
+        KeyType::MlKem1024 => "ML-KEM-1024",
+        KeyType::Ed25519 => "EdDSA",
+        KeyType::Sr25519 => "Sr25519",
+        KeyType::EcdsaSecp256k1 => "ES256K",
+        KeyType::Legacy => "unknown",
+    }
+}
+
+/// Formats a Unix time in milliseconds as an XML datetime in UTC. Zero means the time was
+/// not recorded and yields `None`.
+fn format_timestamp(millis: u64) -> Option<String> {
+    if millis == 0 {
+        return None;
+    }
+    DateTime::<Utc>::from_timestamp_millis(i64::try_from(millis).ok()?)
+        .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use did::{DidChangeKind, Endpoint};
+    use sp_runtime::BoundedVec;
+
+    const ID: [u8; 32] = [7; 32];
+    /// 2023-11-14T22:13:20Z.
+    const NOW: u64 = 1_700_000_000_000;
+
+    fn key(id: &str, key_type: KeyType, roles: Vec<KeyRole>) -> DidKey<Runtime> {
+        DidKey {
+            id: BoundedVec::truncate_from(id.as_bytes().to_vec()),
+            key_type,
+            public_key: BoundedVec::truncate_from(vec![1; key_type.public_key_len()]),
+            roles: BoundedVec::truncate_from(roles),
+            controller: None,
+            revoked: false,
+            valid_from: None,
+            valid_until: None,
+            expired: false,
+        }
+    }
+
+    fn details() -> DidDetails {
+        let mut revoked = key("#key-2", KeyType::Ed25519, vec![KeyRole::AssertionMethod]);
+        revoked.revoked = true;
+        let mut expired = key("#key-3", KeyType::Ed25519, vec![KeyRole::AssertionMethod]);
+        expired.valid_until = Some(KeyValidityBound::Moment(NOW));
+        DidDetails {
+            version: 2,
+            nonce: 1,
+            deactivated: false,
+            created: NOW,
+            updated: NOW,
+            controllers: BoundedVec::default(),
+            threshold: 1,
+            keys: BoundedVec::truncate_from(vec![
+                key(
+                    "#key-1",
+                    KeyType::Ed25519,
+                    vec![KeyRole::Authentication, KeyRole::CapabilityInvocation],
+                ),
+                revoked,
+                expired,
+                key("#key-4", KeyType::MlDsa44, vec![KeyRole::AssertionMethod]),
+            ]),
+            keys_added: 4,
+            services: BoundedVec::truncate_from(vec![ServiceEndpoint {
+                id: BoundedVec::truncate_from(b"#linked-domain".to_vec()),
+                service_type: BoundedVec::truncate_from(b"LinkedDomains".to_vec()),
+                endpoint: Endpoint::Uri(BoundedVec::truncate_from(b"https://example.com".to_vec())),
+            }]),
+            metadata: BoundedVec::default(),
+            recovery: None,
+            history_len: 3,
+        }
+    }
+
+    #[test]
+    fn dids_parse_on_their_own_network_only() {
+        let plain = did_string(&ID, None);
+        let testnet = did_string(&ID, Some("testnet"));
+        assert!(testnet.starts_with("did:qsb:testnet:"));
+
+        assert_eq!(parse_did(&plain, None), Ok(ID));
+        assert_eq!(parse_did(&testnet, Some("testnet")), Ok(ID));
+        assert_eq!(
+            parse_did(&plain, Some("testnet")),
+            Err(ResolutionError::NotFound)
+        );
+        assert_eq!(parse_did(&testnet, None), Err(ResolutionError::NotFound));
+        assert_eq!(
+            parse_did(&did_string(&ID, Some("mainnet")), Some("testnet")),
+            Err(ResolutionError::NotFound)
+        );
+    }
+
+    #[test]
+    fn malformed_dids_are_invalid() {
+        let short = format!("did:qsb:{}", bs58::encode([7; 31]).into_string());
+        for did in [
+            "did:web:example.com",
+            "did:qsb:",
+            "did:qsb:0OIl",
+            short.as_str(),
+        ] {
+            assert_eq!(
+                parse_did(did, None),
+                Err(ResolutionError::InvalidDid),
+                "{did}"
+            );
+        }
+    }
+
+    #[test]
+    fn did_urls_select_a_version() {
+        let did = did_string(&ID, None);
+        let url = |suffix: &str| parse_did_url(&format!("{did}{suffix}"), None);
+
+        assert_eq!(
+            url("#key-1"),
+            Ok(DidUrl {
+                did_id: ID,
+                version: VersionSelector::Latest
+            })
+        );
+        assert_eq!(
+            url("?versionId=3#key-1").map(|url| url.version),
+            Ok(VersionSelector::VersionId(3))
+        );
+        assert_eq!(
+            url("?versionTime=2023-11-14T22:13:20Z").map(|url| url.version),
+            Ok(VersionSelector::VersionTime(NOW))
+        );
+        // Unknown parameters are ignored.
+        assert_eq!(
+            url("?service=files&versionId=2").map(|url| url.version),
+            Ok(VersionSelector::VersionId(2))
+        );
+    }
+
+    #[test]
+    fn malformed_did_urls_are_invalid() {
+        let did = did_string(&ID, None);
+        for suffix in [
+            "?versionId=latest",
+            "?versionId=-1",
+            "?versionTime=yesterday",
+            "?versionTime=1969-12-31T23:59:59Z",
+            "?versionId=1&versionTime=2023-11-14T22:13:20Z",
+            "?versionId=1&versionId=2",
+        ] {
+            assert_eq!(
+                parse_did_url(&format!("{did}{suffix}"), None),
+                Err(ResolutionError::InvalidDidUrl),
+                "{suffix}"
+            );
+        }
+        assert_eq!(
+            parse_did_url("did:qsb:0OIl?versionId=1", None),
+            Err(ResolutionError::InvalidDid)
+        );
+    }
+
+    #[test]
+    fn names_are_split_from_the_rest_of_the_url() {
+        assert_eq!(
+            split_name("did:qsb:name:alice?versionId=1#key-1"),
+            Some(("alice", "?versionId=1#key-1"))
+        );
+        assert_eq!(split_name("did:qsb:name:alice"), Some(("alice", "")));
+        assert_eq!(split_name(&did_string(&ID, None)), None);
+    }
+
+    #[test]
+    fn resolution_results_follow_did_core() {
+        let did = did_string(&ID, None);
+        let key_id = |n: u32| Value::String(format!("{did}#key-{n}"));
+        let next = DidChange {
+            version: 3,
+            block_number: 20,
+            timestamp: NOW + 60_000,
+            kind: DidChangeKind::SetMetadata,
+            key: None,
+        };
+        let result = resolution_result(&did, &details(), Some(&next), (10, NOW));
+
+        assert_eq!(result["@context"], RESOLUTION_CONTEXT);
+        assert_eq!(
+            result["didResolutionMetadata"],
+            json!({ "contentType": DID_LD_JSON })
+        );
+
+        let document = &result["didDocument"];
+        assert_eq!(document["id"], did.as_str());
+        assert_eq!(document["@context"][0], DID_CONTEXT);
+        // Revoked and expired keys are left out.
+        let methods = document["verificationMethod"].as_array().unwrap();
+        assert_eq!(methods.len(), 2);
+        assert_eq!(methods[0]["id"], key_id(1));
+        assert_eq!(methods[0]["type"], "Ed25519VerificationKey2020");
+        assert_eq!(methods[0]["controller"], did.as_str());
+        assert!(methods[0]["publicKeyMultibase"]
+            .as_str()
+            .unwrap()
+            .starts_with("z6Mk"));
+        assert_eq!(methods[1]["id"], key_id(4));
+        assert_eq!(methods[1]["type"], "JsonWebKey2020");
+        assert_eq!(methods[1]["publicKeyJwk"]["alg"], "ML-DSA-44");
+        assert_eq!(document["authentication"], json!([key_id(1)]));
+        assert_eq!(document["capabilityInvocation"], json!([key_id(1)]));
+        assert_eq!(document["assertionMethod"], json!([key_id(4)]));
+        assert!(document.get("keyAgreement").is_none());
+        assert_eq!(
+            document["service"],
+            json!([{
+                "id": format!("{did}#linked-domain"),
+                "type": "LinkedDomains",
+                "serviceEndpoint": "https://example.com",
+            }])
+        );
+
+        assert_eq!(
+            result["didDocumentMetadata"],
+            json!({
+                "versionId": "2",
+                "deactivated": false,
+                "created": "2023-11-14T22:13:20Z",
+                "updated": "2023-11-14T22:13:20Z",
+                "nextVersionId": "3",
+                "nextUpdate": "2023-11-14T22:14:20Z",
+            })
+        );
+    }
+
+    #[test]
+    fn unresolved_dids_report_an_error_or_their_tombstone() {
+        let result = error_result(ResolutionError::NotFound);
+        assert_eq!(result["didDocument"], Value::Null);
+        assert_eq!(result["didResolutionMetadata"]["error"], "notFound");
+
+        let did = did_string(&ID, None);
+        let tombstone = DidTombstone {
+            version: 4,
+            deleted: NOW,
+        };
+        let result = tombstone_result(&did, &tombstone);
+        assert_eq!(result["didDocument"]["id"], did.as_str());
+        assert_eq!(result["didDocumentMetadata"]["deactivated"], true);
+        assert_eq!(result["didDocumentMetadata"]["versionId"], "4");
+    }
+}
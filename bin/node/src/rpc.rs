@@ -7,20 +7,46 @@
 
 use std::sync::Arc;
 
+use crate::did_resolver::{self, ResolutionError, VersionSelector};
+use did::migrations::v1::migrate_details;
 use did_runtime_api::DidRuntimeApi;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc, RpcModule};
-use qsb_runtime::{opaque::Block, AccountId, Balance, Nonce};
+use qsb_runtime::{
+    opaque::Block, AccountId, Balance, BlockNumber, DidChange, DidDetails, DidProposal, Hash,
+    Nonce, Runtime,
+};
 use sc_transaction_pool_api::TransactionPool;
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_core::Bytes;
 
 pub use sc_rpc_api::DenyUnsafe;
 
 #[rpc(server)]
 pub trait DidApi {
     #[method(name = "did_getByString")]
-    fn did_by_string(&self, did: String) -> RpcResult<Option<did::DidDetails>>;
+    fn did_by_string(&self, did: String) -> RpcResult<Option<DidDetails>>;
+
+    /// Resolves a DID into a W3C DID Core resolution result. The `versionId` and
+    /// `versionTime` DID parameters select a historical version of the document, which is
+    /// read in the state of a past block: only archive nodes resolve every version, and
+    /// versions whose state was pruned resolve as `notFound`.
+    #[method(name = "did_resolve")]
+    fn resolve(&self, did: String) -> RpcResult<serde_json::Value>;
+
+    /// Returns the change log of a DID, ordered by version.
+    #[method(name = "did_getHistory")]
+    fn history(&self, did: String) -> RpcResult<Vec<DidChange>>;
+
+    /// Returns the operations of a DID awaiting controller approval, ordered by id.
+    #[method(name = "did_getPendingProposals")]
+    fn pending_proposals(&self, did: String) -> RpcResult<Vec<DidProposal>>;
+
+    /// Returns the DIDs holding a public key, with the status of the key in each of them.
+    /// Revoked and expired keys are included, with their status.
+    #[method(name = "did_findByPublicKey")]
+    fn find_by_public_key(&self, public_key: Bytes) -> RpcResult<Vec<serde_json::Value>>;
 }
 
 pub struct DidRpc<C> {
@@ -33,17 +59,256 @@ impl<C> DidRpc<C> {
     }
 }
 
+impl<C> DidRpc<C>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + 'static,
+    C::Api: did_runtime_api::DidRuntimeApi<Block, DidDetails, DidChange, DidProposal, AccountId>,
+{
+    /// Version of `DidRuntimeApi` the runtime of block `at` implements, if any.
+    fn api_version(&self, at: Hash) -> Result<Option<u32>, sp_api::ApiError> {
+        self.client.runtime_api().api_version::<dyn DidRuntimeApi<
+            Block,
+            DidDetails,
+            DidChange,
+            DidProposal,
+            AccountId,
+        >>(at)
+    }
+
+    /// Fails unless the runtime of block `at` implements version 2 of `DidRuntimeApi`, which
+    /// added every method but `did_by_string`.
+    fn ensure_api(&self, at: Hash) -> RpcResult<()> {
+        match self.api_version(at).map_err(runtime_error)? {
+            Some(version) if version >= 2 => Ok(()),
+            version => Err(jsonrpsee::core::Error::Custom(format!(
+                "DID runtime API version {:?} at {:?} does not support this query",
+                version, at
+            ))),
+        }
+    }
+
+    /// The document of `did` in the state of block `at`. Runtimes implementing version 1 of
+    /// `DidRuntimeApi` return records in their original layout, which are converted the way
+    /// the storage migration converts them.
+    fn did_at(&self, at: Hash, did: &[u8]) -> Result<Option<DidDetails>, sp_api::ApiError> {
+        let api = self.client.runtime_api();
+        if self.api_version(at)? >= Some(2) {
+            return api.did_by_string(at, did.to_vec());
+        }
+        #[allow(deprecated)]
+        let old = api.did_by_string_before_version_2(at, did.to_vec())?;
+        // The identifier only names the DID in logs of the conversion.
+        let did_id =
+            did_resolver::parse_did(&String::from_utf8_lossy(did), None).unwrap_or_default();
+        Ok(old.map(|old| migrate_details::<Runtime>(&did_id, old)))
+    }
+
+    /// The version of a DID document selected by `version`, with the change that made it and
+    /// the one that followed it. The document is read in the state of the block that made the
+    /// change, which only archive nodes keep: a version whose state was pruned is not found.
+    fn historical_version(
+        &self,
+        did: &[u8],
+        version: VersionSelector,
+    ) -> RpcResult<Option<(DidDetails, DidChange, Option<DidChange>)>> {
+        let api = self.client.runtime_api();
+        let at = self.client.info().best_hash;
+        self.ensure_api(at)?;
+        let history = api.did_history(at, did.to_vec()).map_err(runtime_error)?;
+        let change = match version {
+            VersionSelector::Latest => history.last(),
+            VersionSelector::VersionId(id) => history.iter().find(|change| change.version == id),
+            // The change log is ordered by version, and so by time.
+            VersionSelector::VersionTime(timestamp) => history
+                .iter()
+                .rev()
+                .find(|change| change.timestamp <= timestamp),
+        };
+        let Some(change) = change else {
+            return Ok(None);
+        };
+        let Some(hash) = self
+            .client
+            .hash(change.block_number)
+            .map_err(blockchain_error)?
+        else {
+            return Ok(None);
+        };
+        let details = match self.did_at(hash, did) {
+            Ok(details) => details,
+            // The state of the block was discarded by state pruning.
+            Err(sp_api::ApiError::UnknownBlock(_)) => None,
+            Err(error) => return Err(runtime_error(error)),
+        };
+        let Some(details) = details else {
+            return Ok(None);
+        };
+        // Another change made later in the same block hides the requested version.
+        if matches!(version, VersionSelector::VersionId(id) if id != details.version) {
+            return Ok(None);
+        }
+        let next = history
+            .iter()
+            .find(|change| change.version > details.version)
+            .cloned();
+        Ok(Some((details, change.clone(), next)))
+    }
+
+    /// The last block made no later than `timestamp`, from the block of `change`, made by
+    /// then, up to the block before `next` or the best block. The document is the same in
+    /// all of them.
+    fn block_at_time(
+        &self,
+        change: &DidChange,
+        next: Option<&DidChange>,
+        timestamp: u64,
+    ) -> RpcResult<BlockNumber> {
+        let api = self.client.runtime_api();
+        let mut low = change.block_number;
+        let mut high = next.map_or(self.client.info().best_number, |next| {
+            next.block_number.saturating_sub(1).max(low)
+        });
+        while low < high {
+            let middle = low + (high - low + 1) / 2;
+            let made_by = match self.client.hash(middle).map_err(blockchain_error)? {
+                Some(hash) => {
+                    self.ensure_api(hash)?;
+                    api.did_block_time(hash).map_err(runtime_error)? <= timestamp
+                }
+                None => false,
+            };
+            if made_by {
+                low = middle;
+            } else {
+                high = middle - 1;
+            }
+        }
+        Ok(low)
+    }
+}
+
 impl<C> DidApiServer for DidRpc<C>
 where
     C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + 'static,
-    C::Api: did_runtime_api::DidRuntimeApi<Block>,
+    C::Api: did_runtime_api::DidRuntimeApi<Block, DidDetails, DidChange, DidProposal, AccountId>,
 {
-    fn did_by_string(&self, did: String) -> RpcResult<Option<did::DidDetails>> {
+    fn did_by_string(&self, did: String) -> RpcResult<Option<DidDetails>> {
+        self.did_at(self.client.info().best_hash, did.as_bytes())
+            .map_err(runtime_error)
+    }
+
+    fn resolve(&self, did: String) -> RpcResult<serde_json::Value> {
+        let api = self.client.runtime_api();
+        let info = self.client.info();
+        let at = info.best_hash;
+        self.ensure_api(at)?;
+
+        // A name resolves to the document of the DID it points at.
+        let (did, named) = match did_resolver::split_name(&did) {
+            Some((name, rest)) => {
+                let Some(target) = api
+                    .did_by_name(at, name.as_bytes().to_vec())
+                    .map_err(runtime_error)?
+                else {
+                    return Ok(did_resolver::error_result(ResolutionError::NotFound));
+                };
+                (
+                    format!("{}{}", String::from_utf8_lossy(&target), rest),
+                    true,
+                )
+            }
+            None => (did, false),
+        };
+        let network = api
+            .did_network(at)
+            .map_err(runtime_error)?
+            .map(|network| String::from_utf8_lossy(&network).into_owned());
+        let url = match did_resolver::parse_did_url(&did, network.as_deref()) {
+            Ok(url) => url,
+            Err(error) => return Ok(did_resolver::error_result(error)),
+        };
+        let did = did_resolver::did_string(&url.did_id, network.as_deref());
+        let did_bytes = did.clone().into_bytes();
+
+        // Bounds of keys are checked at the block being resolved and its timestamp: the best
+        // block, the block that made the version selected by `versionId`, or the last block
+        // made by the time selected by `versionTime`, which is checked against instead.
+        let resolved = match url.version {
+            VersionSelector::Latest => {
+                let details = api
+                    .did_by_string(at, did_bytes.clone())
+                    .map_err(runtime_error)?;
+                let now = api.did_block_time(at).map_err(runtime_error)?;
+                details.map(|details| (details, None, (info.best_number, now)))
+            }
+            version => match self.historical_version(&did_bytes, version)? {
+                Some((details, change, next)) => {
+                    let instant = match version {
+                        VersionSelector::VersionTime(timestamp) => (
+                            self.block_at_time(&change, next.as_ref(), timestamp)?,
+                            timestamp,
+                        ),
+                        _ => (change.block_number, change.timestamp),
+                    };
+                    Some((details, next, instant))
+                }
+                None => None,
+            },
+        };
+
+        let Some((details, next, instant)) = resolved else {
+            // A deleted DID resolves as deactivated rather than unknown.
+            return Ok(
+                match api.did_tombstone(at, did_bytes).map_err(runtime_error)? {
+                    Some(tombstone) => did_resolver::tombstone_result(&did, &tombstone),
+                    None => did_resolver::error_result(ResolutionError::NotFound),
+                },
+            );
+        };
+        let mut result = did_resolver::resolution_result(&did, &details, next.as_ref(), instant);
+        if named {
+            result["didDocumentMetadata"]["canonicalId"] = serde_json::Value::String(did);
+        }
+        Ok(result)
+    }
+
+    fn history(&self, did: String) -> RpcResult<Vec<DidChange>> {
         let api = self.client.runtime_api();
         let at = self.client.info().best_hash;
-        api.did_by_string(at, did.into_bytes())
-            .map_err(|e| jsonrpsee::core::Error::Custom(format!("Runtime API error: {:?}", e)))
+        self.ensure_api(at)?;
+        api.did_history(at, did.into_bytes()).map_err(runtime_error)
     }
+
+    fn pending_proposals(&self, did: String) -> RpcResult<Vec<DidProposal>> {
+        let api = self.client.runtime_api();
+        let at = self.client.info().best_hash;
+        self.ensure_api(at)?;
+        api.did_pending_proposals(at, did.into_bytes())
+            .map_err(runtime_error)
+    }
+
+    fn find_by_public_key(&self, public_key: Bytes) -> RpcResult<Vec<serde_json::Value>> {
+        let api = self.client.runtime_api();
+        let at = self.client.info().best_hash;
+        self.ensure_api(at)?;
+        let matches = api
+            .did_by_public_key(at, public_key.to_vec())
+            .map_err(runtime_error)?;
+        Ok(matches
+            .into_iter()
+            .map(|(did, status)| {
+                serde_json::json!({ "did": String::from_utf8_lossy(&did), "status": status })
+            })
+            .collect())
+    }
+}
+
+fn runtime_error(error: sp_api::ApiError) -> jsonrpsee::core::Error {
+    jsonrpsee::core::Error::Custom(format!("Runtime API error: {:?}", error))
+}
+
+fn blockchain_error(error: BlockChainError) -> jsonrpsee::core::Error {
+    jsonrpsee::core::Error::Custom(format!("Blockchain error: {:?}", error))
 }
 
 /// Full client dependencies.
@@ -66,7 +331,7 @@ where
     C: Send + Sync + 'static,
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
-    C::Api: did_runtime_api::DidRuntimeApi<Block>,
+    C::Api: did_runtime_api::DidRuntimeApi<Block, DidDetails, DidChange, DidProposal, AccountId>,
     C::Api: BlockBuilder<Block>,
     P: TransactionPool + 'static,
 {
@@ -19,7 +19,7 @@ use sp_runtime::{
         self, AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, One,
         Verify,
     },
-    transaction_validity::{TransactionSource, TransactionValidity},
+    transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
     ApplyExtrinsicResult, MultiSignature, SaturatedConversion,
 };
 use sp_std::prelude::*;
@@ -44,6 +44,8 @@ pub use frame_support::{
 };
 pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
+#[cfg(feature = "runtime-benchmarks")]
+use pallet_contracts::NoopMigration;
 pub use pallet_timestamp::Call as TimestampCall;
 use pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier};
 #[cfg(any(feature = "std", test))]
@@ -51,6 +53,7 @@ pub use sp_runtime::BuildStorage;
 pub use sp_runtime::{Perbill, Permill};
 
 pub use did;
+pub use name_registry;
 pub use revocation_list;
 pub use schema;
 
@@ -107,12 +110,13 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     // The version of the runtime specification. A full node will not attempt to use its native
     //   runtime in substitute for the on-chain Wasm runtime unless all of `spec_name`,
     //   `spec_version`, and `authoring_version` are the same between Wasm and native.
-    // This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
-    //   the compatible custom types.
-    spec_version: 100,
+    // This value started at 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
+    //   the compatible custom types. It must be bumped for `Migrations` to run on upgrade.
+    spec_version: 101,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 1,
+    // Bumped whenever call indices or signatures change.
+    transaction_version: 2,
     state_version: 1,
 };
 
@@ -171,8 +175,9 @@ parameter_types! {
 // Configure FRAME pallets to include in runtime.
 
 impl frame_system::Config for Runtime {
-    /// The basic call filter to use in dispatchable.
-    type BaseCallFilter = frame_support::traits::Everything;
+    /// The basic call filter to use in dispatchable. The calls of the DID pallet are paused
+    /// while its records are migrated.
+    type BaseCallFilter = did::migrations::v1::PausedWhileMigrating<Runtime>;
     /// The block type for the runtime.
     type Block = Block;
     /// Block & extrinsics weights: base values and limits.
@@ -390,16 +395,75 @@ where
     type OverarchingCall = RuntimeCall;
 }
 
+parameter_types! {
+    /// Role of the DID keys that issue schemas and status lists.
+    pub const IssuerRole: did::KeyRole = did::KeyRole::AssertionMethod;
+}
+
 impl did::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type UnixTime = Timestamp;
+    type Currency = Balances;
+    type DepositPerItem = DepositPerItem;
+    type DepositPerByte = DepositPerByte;
+    type MaxControllers = ConstU32<8>;
+    type MaxKeys = ConstU32<16>;
+    // Large enough for ML-DSA-87 public keys.
+    type MaxKeyLength = ConstU32<2592>;
+    type MaxServices = ConstU32<16>;
+    type MaxUriLength = ConstU32<256>;
+    type MaxServiceEndpoints = ConstU32<4>;
+    type MaxServiceTypes = ConstU32<32>;
+    type ServiceTypeOrigin = frame_system::EnsureRoot<AccountId>;
+    type MaxMetadataEntries = ConstU32<16>;
+    type MaxMetadataLength = ConstU32<256>;
+    type MaxPendingProposals = ConstU32<8>;
+    type ProposalLifetime = ConstU32<{ 7 * DAYS }>;
+    type MaxGuardians = ConstU32<8>;
+    type MinRecoveryDelay = ConstU32<{ 2 * DAYS }>;
+    type UnsignedPriority = ConstU64<{ TransactionPriority::MAX / 2 }>;
+    type MaxLinkedAccounts = ConstU32<16>;
+    type OnDidDeleted = NameRegistry;
+    type WeightInfo = did::weights::SubstrateWeight<Runtime>;
 }
 
 impl schema::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
+    type IssuerOrigin = did::EnsureDid<Runtime, IssuerRole>;
+    type Currency = Balances;
+    type DepositPerItem = DepositPerItem;
+    type DepositPerByte = DepositPerByte;
+    type MaxDidLength = ConstU32<128>;
+    type MaxUriLength = ConstU32<256>;
+    type WeightInfo = schema::weights::SubstrateWeight<Runtime>;
 }
 
 impl revocation_list::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
+    type IssuerOrigin = did::EnsureDid<Runtime, IssuerRole>;
+    type Currency = Balances;
+    type DepositPerItem = DepositPerItem;
+    type DepositPerByte = DepositPerByte;
+    type MaxDidLength = ConstU32<128>;
+    type MaxListNonceLength = ConstU32<64>;
+    // 16 KiB, enough for the recommended minimum of 131,072 entries.
+    type MaxBitmapBytes = ConstU32<{ 16 * 1024 }>;
+    type WeightInfo = revocation_list::weights::SubstrateWeight<Runtime>;
+}
+
+impl name_registry::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type DidVerifier = Did;
+    type Currency = Balances;
+    type DepositPerItem = DepositPerItem;
+    type DepositPerByte = DepositPerByte;
+    type MinNameLength = ConstU32<3>;
+    type MaxNameLength = ConstU32<64>;
+    type MaxDidLength = ConstU32<128>;
+    type ReserveOrigin = frame_system::EnsureRoot<AccountId>;
+    type WeightInfo = name_registry::weights::SubstrateWeight<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -418,6 +482,7 @@ construct_runtime!(
         Did: did,
         Schema: schema,
         RevocationList: revocation_list,
+        NameRegistry: name_registry,
     }
 );
 
@@ -444,6 +509,13 @@ pub type UncheckedExtrinsic =
     generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
+/// Migrations to apply on runtime upgrade.
+pub type Migrations = (
+    did::migrations::v1::MigrateToV1<Runtime>,
+    schema::migrations::v1::MigrateToV1<Runtime>,
+    revocation_list::migrations::v1::MigrateToV1<Runtime>,
+);
+
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
     Runtime,
@@ -451,8 +523,16 @@ pub type Executive = frame_executive::Executive<
     frame_system::ChainContext<Runtime>,
     Runtime,
     AllPalletsWithSystem,
+    Migrations,
 >;
 
+/// DID document as stored by this runtime.
+pub type DidDetails = did::DidDetails<Runtime>;
+/// Entry of the DID change log as stored by this runtime.
+pub type DidChange = did::DidChange<Runtime>;
+/// Pending DID operation as stored by this runtime.
+pub type DidProposal = did::DidProposal<Runtime>;
+
 type EventRecord = frame_system::EventRecord<
     <Runtime as frame_system::Config>::RuntimeEvent,
     <Runtime as frame_system::Config>::Hash,
@@ -470,7 +550,10 @@ mod benches {
         [pallet_balances, Balances]
         [pallet_timestamp, Timestamp]
         [pallet_sudo, Sudo]
-        [pallet_template, TemplateModule]
+        [did, Did]
+        [schema, Schema]
+        [revocation_list, RevocationList]
+        [name_registry, NameRegistry]
     );
 }
 
@@ -598,10 +681,52 @@ impl_runtime_apis! {
         }
     }
 
-    impl did_runtime_api::DidRuntimeApi<Block> for Runtime {
-        fn did_by_string(did: Vec<u8>) -> Option<did::DidDetails> {
+    impl did_runtime_api::DidRuntimeApi<Block, DidDetails, DidChange, DidProposal, AccountId>
+        for Runtime
+    {
+        fn did_by_string(did: Vec<u8>) -> Option<DidDetails> {
             did::Pallet::<Runtime>::get_did(did).ok()
         }
+
+        fn did_network() -> Option<Vec<u8>> {
+            did::Pallet::<Runtime>::get_network()
+        }
+
+        fn did_history(did: Vec<u8>) -> Vec<DidChange> {
+            did::Pallet::<Runtime>::get_did_history(did).unwrap_or_default()
+        }
+
+        fn did_pending_proposals(did: Vec<u8>) -> Vec<DidProposal> {
+            did::Pallet::<Runtime>::get_pending_proposals(did).unwrap_or_default()
+        }
+
+        fn did_key_status(did: Vec<u8>, key: Vec<u8>) -> Option<did::KeyStatus> {
+            did::Pallet::<Runtime>::get_key_status(did, key).ok()
+        }
+
+        fn did_block_time() -> u64 {
+            did::Pallet::<Runtime>::get_block_time()
+        }
+
+        fn did_by_name(name: Vec<u8>) -> Option<Vec<u8>> {
+            name_registry::Pallet::<Runtime>::get_did_by_name(name)
+        }
+
+        fn did_tombstone(did: Vec<u8>) -> Option<did::DidTombstone> {
+            did::Pallet::<Runtime>::get_did_tombstone(did).ok()
+        }
+
+        fn did_by_public_key(public_key: Vec<u8>) -> Vec<(Vec<u8>, did::KeyStatus)> {
+            did::Pallet::<Runtime>::find_dids_by_public_key(public_key)
+        }
+
+        fn did_by_account(account: AccountId) -> Option<Vec<u8>> {
+            did::Pallet::<Runtime>::get_did_of_account(account)
+        }
+
+        fn did_linked_accounts(did: Vec<u8>) -> Vec<AccountId> {
+            did::Pallet::<Runtime>::get_linked_accounts(did).unwrap_or_default()
+        }
     }
 
 
@@ -1,12 +1,62 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use codec::Codec;
+use did::{migrations::v1::OldDidDetails, DidTombstone, KeyStatus};
 use sp_api::decl_runtime_apis;
 use sp_std::vec::Vec;
 
-use did::DidDetails;
-
 decl_runtime_apis! {
-    pub trait DidRuntimeApi {
-        fn did_by_string(did: Vec<u8>) -> Option<DidDetails>;
+    /// Queries of the DID pallet. `Details`, `Change` and `Proposal` are the
+    /// `did::DidDetails`, `did::DidChange` and `did::DidProposal` types of the runtime, whose
+    /// bounds depend on its configuration.
+    ///
+    /// Version 1 only has `did_by_string`, which returns records in their original layout.
+    /// Clients check the version a runtime implements before calling other methods.
+    #[api_version(2)]
+    pub trait DidRuntimeApi<Details, Change, Proposal, AccountId>
+    where
+        Details: Codec,
+        Change: Codec,
+        Proposal: Codec,
+        AccountId: Codec,
+    {
+        #[changed_in(2)]
+        fn did_by_string(did: Vec<u8>) -> Option<OldDidDetails>;
+
+        fn did_by_string(did: Vec<u8>) -> Option<Details>;
+
+        /// Network segment of the DIDs of the chain, as in `did:qsb:<network>:<id>`.
+        fn did_network() -> Option<Vec<u8>>;
+
+        /// The change log of the DID, ordered by version. A past version of the document is
+        /// read with `did_by_string` at the block that made the change.
+        fn did_history(did: Vec<u8>) -> Vec<Change>;
+
+        /// Operations of the DID awaiting controller approval.
+        fn did_pending_proposals(did: Vec<u8>) -> Vec<Proposal>;
+
+        /// Whether a key of the DID, given by its id or public key, is valid in the block the
+        /// call is made at. Its status at a past time is read in the block made at that time.
+        fn did_key_status(did: Vec<u8>, key: Vec<u8>) -> Option<KeyStatus>;
+
+        /// Unix time in milliseconds of the block the call is made at, which time bounds of
+        /// keys are checked against.
+        fn did_block_time() -> u64;
+
+        /// The DID a `did:qsb:name:` name points at. The name may be given without the prefix.
+        fn did_by_name(name: Vec<u8>) -> Option<Vec<u8>>;
+
+        /// What remains of the DID if it was deleted.
+        fn did_tombstone(did: Vec<u8>) -> Option<DidTombstone>;
+
+        /// DIDs holding the public key, with its status in each of them. Revoked and expired
+        /// keys are included.
+        fn did_by_public_key(public_key: Vec<u8>) -> Vec<(Vec<u8>, KeyStatus)>;
+
+        /// The DID an account is linked to.
+        fn did_by_account(account: AccountId) -> Option<Vec<u8>>;
+
+        /// Accounts linked to the DID, in the order they were linked.
+        fn did_linked_accounts(did: Vec<u8>) -> Vec<AccountId>;
     }
 }
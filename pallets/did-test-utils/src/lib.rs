@@ -0,0 +1,183 @@
+//! Test runtime and key fixtures shared by the unit tests of the identity pallets.
+//!
+//! Every pallet builds its mock runtime with `construct_runtime!` and the
+//! [`impl_identity_test_runtime`] macro, which configures the system, balances, timestamp and
+//! DID pallets the same way in all of them. This crate does not depend on the DID pallet:
+//! the macro names it `did` at the call site, so that the DID pallet's own tests use the
+//! crate under test rather than a second copy of it.
+
+pub use frame_support::{
+    self,
+    traits::{ConstU32, ConstU64},
+};
+pub use frame_system;
+pub use sp_core;
+pub use sp_io;
+pub use sp_keystore;
+pub use sp_runtime;
+
+use sp_core::{ed25519, Pair};
+use sp_keystore::{testing::MemoryKeystore, KeystoreExt};
+use sp_runtime::BuildStorage;
+
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+pub const DAVE: u64 = 4;
+
+pub const INITIAL_BALANCE: u64 = 1_000_000_000;
+/// Unix time in milliseconds of the blocks the tests run in.
+pub const NOW: u64 = 1_700_000_000_000;
+pub const PROPOSAL_LIFETIME: u64 = 100;
+pub const MIN_RECOVERY_DELAY: u64 = 10;
+
+/// The Ed25519 key derived from `seed`.
+pub fn pair(seed: u8) -> ed25519::Pair {
+    ed25519::Pair::from_seed(&[seed; 32])
+}
+
+pub fn public(pair: &ed25519::Pair) -> Vec<u8> {
+    pair.public().0.to_vec()
+}
+
+/// Signs `payload` followed by `nonce`, the way DID signatures are checked.
+pub fn sign(pair: &ed25519::Pair, payload: &[u8], nonce: u64) -> Vec<u8> {
+    let mut message = payload.to_vec();
+    message.extend_from_slice(&nonce.to_le_bytes());
+    pair.sign(&message).0.to_vec()
+}
+
+/// State with funded accounts at block 1, and a keystore for the benchmarks to sign with.
+pub fn new_test_ext<T>() -> sp_io::TestExternalities
+where
+    T: frame_system::Config<AccountId = u64>
+        + pallet_balances::Config<Balance = u64>
+        + pallet_timestamp::Config<Moment = u64>,
+{
+    let mut storage = frame_system::GenesisConfig::<T>::default()
+        .build_storage()
+        .unwrap();
+    pallet_balances::GenesisConfig::<T> {
+        balances: [ALICE, BOB, CHARLIE, DAVE]
+            .into_iter()
+            .map(|who| (who, INITIAL_BALANCE))
+            .collect(),
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.register_extension(KeystoreExt::new(MemoryKeystore::new()));
+    ext.execute_with(|| {
+        frame_system::Pallet::<T>::set_block_number(1u32.into());
+        pallet_timestamp::Pallet::<T>::set_timestamp(NOW);
+    });
+    ext
+}
+
+/// Configures `System`, `Balances`, `Timestamp` and `Did` for the runtime `$runtime` built by
+/// `construct_runtime!`, with `$on_did_deleted` notified of deleted DIDs. It also defines
+/// `new_test_ext` and `create_did` for the tests of the runtime.
+#[macro_export]
+macro_rules! impl_identity_test_runtime {
+    ($runtime:ident, $on_did_deleted:ty) => {
+        impl frame_system::Config for $runtime {
+            type BaseCallFilter = $crate::frame_support::traits::Everything;
+            type BlockWeights = ();
+            type BlockLength = ();
+            type DbWeight = ();
+            type RuntimeOrigin = RuntimeOrigin;
+            type RuntimeCall = RuntimeCall;
+            type Nonce = u64;
+            type Hash = $crate::sp_core::H256;
+            type Hashing = $crate::sp_runtime::traits::BlakeTwo256;
+            type AccountId = u64;
+            type Lookup = $crate::sp_runtime::traits::IdentityLookup<Self::AccountId>;
+            type Block = frame_system::mocking::MockBlock<$runtime>;
+            type RuntimeEvent = RuntimeEvent;
+            type BlockHashCount = $crate::ConstU64<250>;
+            type Version = ();
+            type PalletInfo = PalletInfo;
+            type AccountData = pallet_balances::AccountData<u64>;
+            type OnNewAccount = ();
+            type OnKilledAccount = ();
+            type SystemWeightInfo = ();
+            type SS58Prefix = ();
+            type OnSetCode = ();
+            type MaxConsumers = $crate::ConstU32<16>;
+        }
+
+        impl pallet_balances::Config for $runtime {
+            type MaxLocks = ();
+            type MaxReserves = ();
+            type ReserveIdentifier = [u8; 8];
+            type Balance = u64;
+            type RuntimeEvent = RuntimeEvent;
+            type DustRemoval = ();
+            type ExistentialDeposit = $crate::ConstU64<1>;
+            type AccountStore = System;
+            type WeightInfo = ();
+            type FreezeIdentifier = ();
+            type MaxFreezes = ();
+            type RuntimeHoldReason = ();
+            type MaxHolds = ();
+        }
+
+        impl pallet_timestamp::Config for $runtime {
+            type Moment = u64;
+            type OnTimestampSet = ();
+            type MinimumPeriod = $crate::ConstU64<1>;
+            type WeightInfo = ();
+        }
+
+        impl did::Config for $runtime {
+            type RuntimeEvent = RuntimeEvent;
+            type RuntimeOrigin = RuntimeOrigin;
+            type RuntimeCall = RuntimeCall;
+            type UnixTime = Timestamp;
+            type Currency = Balances;
+            type DepositPerItem = $crate::ConstU64<1_000>;
+            type DepositPerByte = $crate::ConstU64<10>;
+            type MaxControllers = $crate::ConstU32<8>;
+            type MaxKeys = $crate::ConstU32<16>;
+            type MaxKeyLength = $crate::ConstU32<2592>;
+            type MaxServices = $crate::ConstU32<16>;
+            type MaxUriLength = $crate::ConstU32<256>;
+            type MaxServiceEndpoints = $crate::ConstU32<4>;
+            type MaxServiceTypes = $crate::ConstU32<32>;
+            type ServiceTypeOrigin = frame_system::EnsureRoot<u64>;
+            type MaxMetadataEntries = $crate::ConstU32<16>;
+            type MaxMetadataLength = $crate::ConstU32<256>;
+            type MaxPendingProposals = $crate::ConstU32<8>;
+            type ProposalLifetime = $crate::ConstU64<{ $crate::PROPOSAL_LIFETIME }>;
+            type MaxGuardians = $crate::ConstU32<8>;
+            type MinRecoveryDelay = $crate::ConstU64<{ $crate::MIN_RECOVERY_DELAY }>;
+            type UnsignedPriority = $crate::ConstU64<100>;
+            type MaxLinkedAccounts = $crate::ConstU32<16>;
+            type OnDidDeleted = $on_did_deleted;
+            type WeightInfo = ();
+        }
+
+        /// State with funded accounts at block 1, and a keystore for the benchmarks to sign
+        /// with.
+        pub fn new_test_ext() -> $crate::sp_io::TestExternalities {
+            $crate::new_test_ext::<$runtime>()
+        }
+
+        /// Registers a DID controlled by `owner` with the Ed25519 key of `pair` and returns
+        /// it.
+        #[allow(dead_code)]
+        pub fn create_did(owner: u64, pair: &$crate::sp_core::ed25519::Pair) -> Vec<u8> {
+            let public = $crate::public(pair);
+            let payload = Did::create_payload(&public, None, &owner);
+            $crate::frame_support::assert_ok!(Did::create_did(
+                RuntimeOrigin::signed(owner),
+                did::KeyType::Ed25519,
+                public.clone(),
+                None,
+                $crate::sign(pair, &payload, 0),
+            ));
+            Did::find_dids_by_public_key(public).remove(0).0
+        }
+    };
+}
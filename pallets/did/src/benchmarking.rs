@@ -0,0 +1,924 @@
+//! Benchmarks of the DID pallet.
+//!
+//! Calls that do not touch keys start from a DID holding the maximum number of keys of the
+//! largest type, so that decoding and re-encoding the document is measured at its worst.
+
+use super::*;
+use codec::Encode;
+use frame_benchmarking::v2::*;
+use frame_support::{
+    traits::{Currency, EnsureOrigin, Get},
+    weights::Weight,
+    BoundedVec,
+};
+use frame_system::RawOrigin;
+use sp_core::{crypto::KeyTypeId, ed25519};
+use sp_runtime::traits::Bounded;
+use sp_std::{boxed::Box, vec, vec::Vec};
+
+const KEY_TYPE: KeyTypeId = KeyTypeId(*b"didb");
+
+fn fund<T: Config>(who: &T::AccountId) {
+    T::Currency::make_free_balance_be(who, BalanceOf::<T>::max_value() / 2u32.into());
+}
+
+/// Registers a DID controlled by `owner`, with a fresh ed25519 key held in the keystore.
+pub(crate) fn register_did<T: Config>(owner: &T::AccountId) -> (Vec<u8>, ed25519::Public) {
+    fund::<T>(owner);
+    let public = sp_io::crypto::ed25519_generate(KEY_TYPE, None);
+    let signature = sign_creation::<T>(owner, &public, None);
+    Pallet::<T>::create_did(
+        RawOrigin::Signed(owner.clone()).into(),
+        KeyType::Ed25519,
+        public.0.to_vec(),
+        None,
+        signature,
+    )
+    .expect("a fresh key can register a DID");
+    (did_of::<T>(&public, None), public)
+}
+
+/// Signs `payload` followed by the current nonce of a DID created by [`register_did`].
+pub(crate) fn sign<T: Config>(did: &[u8], payload: &[u8]) -> Vec<u8> {
+    let details = Pallet::<T>::get_did(did.to_vec()).expect("benchmark DID exists");
+    let raw_key: [u8; 32] = details.keys[0].public_key[..]
+        .try_into()
+        .expect("benchmark DIDs are created with an ed25519 key");
+    let mut message = payload.to_vec();
+    message.extend_from_slice(&details.nonce.to_le_bytes());
+    sp_io::crypto::ed25519_sign(KEY_TYPE, &ed25519::Public::from_raw(raw_key), &message)
+        .expect("key is held in the keystore")
+        .0
+        .to_vec()
+}
+
+fn sign_creation<T: Config>(
+    owner: &T::AccountId,
+    public: &ed25519::Public,
+    salt: Option<&[u8; 32]>,
+) -> Vec<u8> {
+    let mut payload = Pallet::<T>::create_payload(&public.0, salt, owner);
+    payload.extend_from_slice(&0u64.to_le_bytes());
+    sp_io::crypto::ed25519_sign(KEY_TYPE, public, &payload)
+        .expect("key is held in the keystore")
+        .0
+        .to_vec()
+}
+
+fn did_of<T: Config>(public: &ed25519::Public, salt: Option<&[u8; 32]>) -> Vec<u8> {
+    Pallet::<T>::did_string_from_did_id(&Pallet::<T>::did_id_from_public_key(&public.0, salt))
+}
+
+/// Bytes of length `len`, distinct for every `index`.
+fn filler(index: u32, len: usize) -> Vec<u8> {
+    let mut bytes = index.encode();
+    bytes.resize(len, b'x');
+    bytes
+}
+
+/// ASCII text of length `len` starting with `prefix`, distinct for every `index`. The
+/// characters after the prefix are lowercase letters, valid in every part of a service.
+fn text(prefix: &[u8], index: u32, len: usize) -> Vec<u8> {
+    let mut bytes = prefix.to_vec();
+    for byte in index.to_be_bytes() {
+        bytes.push(b'a' + byte / 16);
+        bytes.push(b'a' + byte % 16);
+    }
+    bytes.resize(len, b'x');
+    bytes
+}
+
+fn service_id<T: Config>(index: u32) -> Vec<u8> {
+    text(b"#", index, T::MaxUriLength::get() as usize)
+}
+
+/// Map endpoint holding the maximum number of the longest URIs.
+fn large_endpoint<T: Config>(index: u32) -> EndpointInput {
+    let len = T::MaxUriLength::get() as usize;
+    EndpointInput::Map(
+        (0..T::MaxServiceEndpoints::get())
+            .map(|entry| (text(b"k", entry, len), text(b"https:", index, len)))
+            .collect(),
+    )
+}
+
+/// Fills the allowlist of service types and returns the type checked last.
+fn allow_service_types<T: Config>() -> Vec<u8> {
+    let len = T::MaxUriLength::get() as usize;
+    let types: Vec<_> = (0..T::MaxServiceTypes::get())
+        .map(|index| text(b"Type", index, len))
+        .collect();
+    let last = types.last().cloned().unwrap_or_default();
+    let bounded = types
+        .into_iter()
+        .map(|service_type| service_type.try_into().unwrap())
+        .collect::<Vec<_>>();
+    AllowedServiceTypes::<T>::put(BoundedVec::try_from(bounded).unwrap());
+    last
+}
+
+/// Public key of the largest supported type, distinct for every `index`.
+fn large_key(index: u32) -> Vec<u8> {
+    filler(index, KeyType::MlDsa87.public_key_len())
+}
+
+fn add_keys<T: Config>(owner: &T::AccountId, did: &[u8], count: u32) {
+    for index in 0..count {
+        Pallet::<T>::add_key(
+            RawOrigin::Signed(owner.clone()).into(),
+            did.to_vec(),
+            KeyType::MlDsa87,
+            large_key(index),
+            vec![KeyRole::AssertionMethod],
+            None,
+            None,
+            None,
+        )
+        .expect("key count is within bounds");
+    }
+}
+
+fn add_services<T: Config>(owner: &T::AccountId, did: &[u8], count: u32) {
+    let len = T::MaxUriLength::get() as usize;
+    for index in 0..count {
+        Pallet::<T>::add_service(
+            RawOrigin::Signed(owner.clone()).into(),
+            did.to_vec(),
+            service_id::<T>(index),
+            text(b"Type", index, len),
+            large_endpoint::<T>(index),
+        )
+        .expect("service count is within bounds");
+    }
+}
+
+fn add_metadata<T: Config>(owner: &T::AccountId, did: &[u8], count: u32) {
+    let len = T::MaxMetadataLength::get() as usize;
+    for index in 0..count {
+        Pallet::<T>::set_metadata(
+            RawOrigin::Signed(owner.clone()).into(),
+            did.to_vec(),
+            filler(index, len),
+            filler(index, len),
+        )
+        .expect("metadata entry count is within bounds");
+    }
+}
+
+/// Links `count` funded accounts to a DID created by [`register_did`].
+fn link_accounts<T: Config>(did: &[u8], count: u32) {
+    let did_id = Pallet::<T>::decode_did_id(did).unwrap();
+    for index in 0..count {
+        let linked: T::AccountId = account("linked", index, 0);
+        fund::<T>(&linked);
+        let payload = Pallet::<T>::link_account_payload(&did_id, &linked);
+        Pallet::<T>::link_account(
+            RawOrigin::Signed(linked).into(),
+            did.to_vec(),
+            sign::<T>(did, &payload),
+        )
+        .expect("linked account count is within bounds");
+    }
+}
+
+fn add_controllers<T: Config>(owner: &T::AccountId, did: &[u8], count: u32) {
+    for index in 0..count {
+        Pallet::<T>::add_controller(
+            RawOrigin::Signed(owner.clone()).into(),
+            did.to_vec(),
+            account("controller", index, 0),
+        )
+        .expect("controller count is within bounds");
+    }
+}
+
+/// Makes `count` accounts, the owner included, controllers of a DID and requires all of
+/// them to approve sensitive operations.
+fn require_approvals<T: Config>(owner: &T::AccountId, did: &[u8], count: u32) {
+    add_controllers::<T>(owner, did, count - 1);
+    Pallet::<T>::set_threshold(RawOrigin::Signed(owner.clone()).into(), did.to_vec(), count)
+        .expect("the DID has enough controllers");
+}
+
+/// Proposes to deactivate a DID that requires several approvals.
+fn propose_deactivation<T: Config>(proposer: &T::AccountId, did: &[u8]) {
+    Pallet::<T>::deactivate_did(RawOrigin::Signed(proposer.clone()).into(), did.to_vec())
+        .expect("proposal count is within bounds");
+}
+
+/// Proposes the largest operation, a batch adding as many keys of the largest type as a
+/// proposal holds, to a DID that requires several approvals.
+fn propose_key_batch<T: Config>(proposer: &T::AccountId, did: &[u8]) {
+    let updates = (0..MaxProposedUpdates::get())
+        .map(|index| DocumentUpdate::AddKey {
+            key_type: KeyType::MlDsa87,
+            public_key: large_key(index),
+            roles: vec![KeyRole::AssertionMethod],
+            controller: None,
+            valid_from: None,
+            valid_until: None,
+        })
+        .collect();
+    Pallet::<T>::apply_operations(
+        RawOrigin::Signed(proposer.clone()).into(),
+        did.to_vec(),
+        updates,
+    )
+    .expect("proposal count is within bounds");
+}
+
+/// Registers `count` DIDs to act as recovery guardians, returning their owners and DIDs.
+fn register_guardians<T: Config>(count: u32) -> Vec<(T::AccountId, Vec<u8>)> {
+    (0..count)
+        .map(|index| {
+            let owner: T::AccountId = account("guardian", index, 0);
+            let (did, _) = register_did::<T>(&owner);
+            (owner, did)
+        })
+        .collect()
+}
+
+/// Lets `threshold` of the `guardians` DIDs recover `did` after the shortest delay.
+fn configure_recovery<T: Config>(
+    owner: &T::AccountId,
+    did: &[u8],
+    guardians: &[(T::AccountId, Vec<u8>)],
+    threshold: u32,
+) {
+    Pallet::<T>::set_recovery(
+        RawOrigin::Signed(owner.clone()).into(),
+        did.to_vec(),
+        Vec::new(),
+        guardians.iter().map(|(_, did)| did.clone()).collect(),
+        threshold,
+        T::MinRecoveryDelay::get(),
+    )
+    .expect("the recovery configuration is valid");
+}
+
+/// Starts the recovery of `did` by its first guardian towards `count` new keys.
+fn start_recovery<T: Config>(did: &[u8], guardians: &[(T::AccountId, Vec<u8>)], count: u32) {
+    let (owner, guardian_did) = &guardians[0];
+    Pallet::<T>::initiate_recovery(
+        RawOrigin::Signed(owner.clone()).into(),
+        did.to_vec(),
+        Some(guardian_did.clone()),
+        recovery_keys(count),
+    )
+    .expect("guardians can start a recovery");
+}
+
+/// An ed25519 `CapabilityInvocation` key followed by the largest keys, all distinct from
+/// those added by [`add_keys`].
+fn recovery_keys(count: u32) -> Vec<(KeyType, Vec<u8>, Vec<KeyRole>)> {
+    (0..count)
+        .map(|index| match index {
+            0 => (
+                KeyType::Ed25519,
+                filler(u32::MAX, KeyType::Ed25519.public_key_len()),
+                vec![KeyRole::Authentication, KeyRole::CapabilityInvocation],
+            ),
+            _ => (
+                KeyType::MlDsa87,
+                large_key(u32::MAX - index),
+                vec![KeyRole::Authentication],
+            ),
+        })
+        .collect()
+}
+
+/// Registers a DID holding the maximum number of keys.
+fn register_full_did<T: Config>(owner: &T::AccountId) -> Vec<u8> {
+    let (did, _) = register_did::<T>(owner);
+    add_keys::<T>(owner, &did, T::MaxKeys::get() - 1);
+    did
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    #[benchmark]
+    fn create_did() {
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+        let public = sp_io::crypto::ed25519_generate(KEY_TYPE, None);
+        let salt = [1u8; 32];
+        let signature = sign_creation::<T>(&caller, &public, Some(&salt));
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            KeyType::Ed25519,
+            public.0.to_vec(),
+            Some(salt),
+            signature,
+        );
+
+        assert!(Pallet::<T>::get_did(did_of::<T>(&public, Some(&salt))).is_ok());
+    }
+
+    #[benchmark]
+    fn add_key(k: Linear<1, { T::MaxKeys::get() - 1 }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let (did, _) = register_did::<T>(&caller);
+        add_keys::<T>(&caller, &did, k - 1);
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            did.clone(),
+            KeyType::MlDsa87,
+            large_key(k),
+            vec![KeyRole::AssertionMethod],
+            Some(did.clone()),
+            Some(KeyValidityBound::Block(0u32.into())),
+            Some(KeyValidityBound::Moment(u64::MAX)),
+        );
+
+        assert_eq!(Pallet::<T>::get_did(did).unwrap().keys.len() as u32, k + 1);
+    }
+
+    #[benchmark]
+    fn revoke_key(k: Linear<2, { T::MaxKeys::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let (did, _) = register_did::<T>(&caller);
+        add_keys::<T>(&caller, &did, k - 1);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), did.clone(), large_key(k - 2));
+
+        assert!(Pallet::<T>::get_did(did).unwrap().keys[k as usize - 1].revoked);
+    }
+
+    #[benchmark]
+    fn deactivate_did(k: Linear<1, { T::MaxKeys::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let (did, _) = register_did::<T>(&caller);
+        add_keys::<T>(&caller, &did, k - 1);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), did.clone());
+
+        assert!(Pallet::<T>::get_did(did).unwrap().deactivated);
+    }
+
+    #[benchmark]
+    fn add_service(s: Linear<0, { T::MaxServices::get() - 1 }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let did = register_full_did::<T>(&caller);
+        add_services::<T>(&caller, &did, s);
+        let service_type = allow_service_types::<T>();
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            did.clone(),
+            service_id::<T>(s),
+            service_type,
+            large_endpoint::<T>(s),
+        );
+
+        assert_eq!(
+            Pallet::<T>::get_did(did).unwrap().services.len() as u32,
+            s + 1
+        );
+    }
+
+    #[benchmark]
+    fn remove_service(s: Linear<1, { T::MaxServices::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let did = register_full_did::<T>(&caller);
+        add_services::<T>(&caller, &did, s);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), did.clone(), service_id::<T>(0));
+
+        assert_eq!(
+            Pallet::<T>::get_did(did).unwrap().services.len() as u32,
+            s - 1
+        );
+    }
+
+    /// Replacing both the type and the endpoint of the last of `s` services.
+    #[benchmark]
+    fn update_service(s: Linear<1, { T::MaxServices::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let did = register_full_did::<T>(&caller);
+        add_services::<T>(&caller, &did, s);
+        let service_type = allow_service_types::<T>();
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            did.clone(),
+            service_id::<T>(s - 1),
+            Some(service_type.clone()),
+            Some(large_endpoint::<T>(s)),
+        );
+
+        let details = Pallet::<T>::get_did(did).unwrap();
+        assert_eq!(
+            details.services[s as usize - 1].service_type[..],
+            service_type[..]
+        );
+    }
+
+    #[benchmark]
+    fn set_allowed_service_types(
+        t: Linear<1, { T::MaxServiceTypes::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let origin = T::ServiceTypeOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let len = T::MaxUriLength::get() as usize;
+        let types: Vec<_> = (0..t).map(|index| text(b"Type", index, len)).collect();
+
+        #[extrinsic_call]
+        _(
+            origin as <T as frame_system::Config>::RuntimeOrigin,
+            Some(types),
+        );
+
+        assert_eq!(AllowedServiceTypes::<T>::get().unwrap().len() as u32, t);
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_metadata(m: Linear<0, { T::MaxMetadataEntries::get() - 1 }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let did = register_full_did::<T>(&caller);
+        add_metadata::<T>(&caller, &did, m);
+        let len = T::MaxMetadataLength::get() as usize;
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            did.clone(),
+            filler(m, len),
+            filler(m, len),
+        );
+
+        assert_eq!(
+            Pallet::<T>::get_did(did).unwrap().metadata.len() as u32,
+            m + 1
+        );
+    }
+
+    #[benchmark]
+    fn remove_metadata(m: Linear<1, { T::MaxMetadataEntries::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let did = register_full_did::<T>(&caller);
+        add_metadata::<T>(&caller, &did, m);
+        let key = filler(m - 1, T::MaxMetadataLength::get() as usize);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), did.clone(), key);
+
+        assert_eq!(
+            Pallet::<T>::get_did(did).unwrap().metadata.len() as u32,
+            m - 1
+        );
+    }
+
+    #[benchmark]
+    fn rotate_key(k: Linear<2, { T::MaxKeys::get() - 1 }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let (did, _) = register_did::<T>(&caller);
+        add_keys::<T>(&caller, &did, k - 1);
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            did.clone(),
+            large_key(k - 2),
+            KeyType::MlDsa87,
+            large_key(k),
+            vec![KeyRole::AssertionMethod],
+            Some(KeyValidityBound::Block(0u32.into())),
+            Some(KeyValidityBound::Moment(u64::MAX)),
+        );
+
+        assert_eq!(Pallet::<T>::get_did(did).unwrap().keys.len() as u32, k + 1);
+    }
+
+    #[benchmark]
+    fn update_roles(k: Linear<2, { T::MaxKeys::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let (did, _) = register_did::<T>(&caller);
+        add_keys::<T>(&caller, &did, k - 1);
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            did.clone(),
+            large_key(k - 2),
+            vec![KeyRole::AssertionMethod, KeyRole::Authentication],
+        );
+
+        assert_eq!(
+            Pallet::<T>::get_did(did).unwrap().keys[k as usize - 1]
+                .roles
+                .len(),
+            2
+        );
+    }
+
+    #[benchmark]
+    fn add_controller(c: Linear<1, { T::MaxControllers::get() - 1 }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let did = register_full_did::<T>(&caller);
+        add_controllers::<T>(&caller, &did, c - 1);
+        let controller: T::AccountId = account("controller", c, 0);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), did.clone(), controller);
+
+        assert_eq!(
+            Pallet::<T>::get_did(did).unwrap().controllers.len() as u32,
+            c + 1
+        );
+    }
+
+    #[benchmark]
+    fn remove_controller(c: Linear<2, { T::MaxControllers::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let did = register_full_did::<T>(&caller);
+        add_controllers::<T>(&caller, &did, c - 1);
+        let controller: T::AccountId = account("controller", c - 2, 0);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), did.clone(), controller);
+
+        assert_eq!(
+            Pallet::<T>::get_did(did).unwrap().controllers.len() as u32,
+            c - 1
+        );
+    }
+
+    #[benchmark]
+    fn set_threshold(c: Linear<1, { T::MaxControllers::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let did = register_full_did::<T>(&caller);
+        add_controllers::<T>(&caller, &did, c - 1);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), did.clone(), c);
+
+        assert_eq!(Pallet::<T>::get_did(did).unwrap().threshold, c);
+    }
+
+    /// Submitting a sensitive operation to a DID that needs several approvals, with `p`
+    /// expired proposals to clear.
+    #[benchmark]
+    fn propose_operation(p: Linear<0, { T::MaxPendingProposals::get() - 1 }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let did = register_full_did::<T>(&caller);
+        require_approvals::<T>(&caller, &did, 2);
+        for _ in 0..p {
+            propose_key_batch::<T>(&caller, &did);
+        }
+        let now = frame_system::Pallet::<T>::block_number();
+        frame_system::Pallet::<T>::set_block_number(now + T::ProposalLifetime::get());
+
+        #[block]
+        {
+            propose_key_batch::<T>(&caller, &did);
+        }
+
+        assert_eq!(Pallet::<T>::get_pending_proposals(did).unwrap().len(), 1);
+    }
+
+    /// Approving a proposal without applying it, after `c - 3` other controllers did.
+    #[benchmark]
+    fn approve_proposal(c: Linear<3, { T::MaxControllers::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let did = register_full_did::<T>(&caller);
+        require_approvals::<T>(&caller, &did, c);
+        propose_deactivation::<T>(&caller, &did);
+        for index in 0..c - 3 {
+            Pallet::<T>::approve_proposal(
+                RawOrigin::Signed(account("controller", index, 0)).into(),
+                did.clone(),
+                0,
+            )
+            .expect("controllers can approve");
+        }
+        let approver: T::AccountId = account("controller", c - 3, 0);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(approver), did.clone(), 0);
+
+        let proposals = Pallet::<T>::get_pending_proposals(did).unwrap();
+        assert_eq!(proposals[0].approvals.len() as u32, c - 1);
+    }
+
+    #[benchmark]
+    fn cancel_proposal() {
+        let caller: T::AccountId = whitelisted_caller();
+        let did = register_full_did::<T>(&caller);
+        require_approvals::<T>(&caller, &did, 2);
+        propose_deactivation::<T>(&caller, &did);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), did.clone(), 0);
+
+        assert!(Pallet::<T>::get_pending_proposals(did).unwrap().is_empty());
+    }
+
+    /// Replacing a recovery configuration of `g` guardians, cancelling a recovery in progress.
+    #[benchmark]
+    fn set_recovery(g: Linear<1, { T::MaxGuardians::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let (did, _) = register_did::<T>(&caller);
+        // Leave room for the key of the recovery in progress.
+        add_keys::<T>(&caller, &did, T::MaxKeys::get() - 2);
+        let guardians = register_guardians::<T>(g);
+        configure_recovery::<T>(&caller, &did, &guardians, g);
+        start_recovery::<T>(&did, &guardians, 1);
+        let did_guardians = guardians.iter().map(|(_, did)| did.clone()).collect();
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            did.clone(),
+            Vec::new(),
+            did_guardians,
+            g,
+            T::MinRecoveryDelay::get(),
+        );
+
+        let details = Pallet::<T>::get_did(did).unwrap();
+        assert_eq!(details.recovery.unwrap().guardians.len() as u32, g);
+    }
+
+    #[benchmark]
+    fn initiate_recovery(k: Linear<1, { T::MaxKeys::get() - 1 }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let (did, _) = register_did::<T>(&caller);
+        let guardians = register_guardians::<T>(T::MaxGuardians::get());
+        configure_recovery::<T>(&caller, &did, &guardians, T::MaxGuardians::get());
+        let (guardian, guardian_did) = guardians[0].clone();
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(guardian),
+            did.clone(),
+            Some(guardian_did),
+            recovery_keys(k),
+        );
+
+        let did_id = Pallet::<T>::decode_did_id(&did).unwrap();
+        assert_eq!(
+            Recoveries::<T>::get(did_id).unwrap().new_keys.len() as u32,
+            k
+        );
+    }
+
+    /// The vouch of the last of `g` guardians, which starts the recovery delay.
+    #[benchmark]
+    fn vouch_recovery(g: Linear<2, { T::MaxGuardians::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let (did, _) = register_did::<T>(&caller);
+        let guardians = register_guardians::<T>(g);
+        configure_recovery::<T>(&caller, &did, &guardians, g);
+        start_recovery::<T>(&did, &guardians, T::MaxKeys::get() - 1);
+        for (owner, guardian_did) in &guardians[1..g as usize - 1] {
+            Pallet::<T>::vouch_recovery(
+                RawOrigin::Signed(owner.clone()).into(),
+                did.clone(),
+                Some(guardian_did.clone()),
+            )
+            .expect("guardians can vouch");
+        }
+        let (guardian, guardian_did) = guardians[g as usize - 1].clone();
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(guardian), did.clone(), Some(guardian_did));
+
+        let did_id = Pallet::<T>::decode_did_id(&did).unwrap();
+        assert!(Recoveries::<T>::get(did_id)
+            .unwrap()
+            .executable_at
+            .is_some());
+    }
+
+    #[benchmark]
+    fn cancel_recovery() {
+        let caller: T::AccountId = whitelisted_caller();
+        let (did, _) = register_did::<T>(&caller);
+        let guardians = register_guardians::<T>(1);
+        configure_recovery::<T>(&caller, &did, &guardians, 1);
+        start_recovery::<T>(&did, &guardians, T::MaxKeys::get() - 1);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), did.clone());
+
+        let did_id = Pallet::<T>::decode_did_id(&did).unwrap();
+        assert!(!Recoveries::<T>::contains_key(did_id));
+    }
+
+    #[benchmark]
+    fn complete_recovery(k: Linear<1, { T::MaxKeys::get() - 1 }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let (did, _) = register_did::<T>(&caller);
+        let guardians = register_guardians::<T>(1);
+        configure_recovery::<T>(&caller, &did, &guardians, 1);
+        start_recovery::<T>(&did, &guardians, k);
+        let now = frame_system::Pallet::<T>::block_number();
+        frame_system::Pallet::<T>::set_block_number(now + T::MinRecoveryDelay::get());
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), did.clone());
+
+        let details = Pallet::<T>::get_did(did).unwrap();
+        assert_eq!(
+            details.keys.iter().filter(|key| !key.revoked).count() as u32,
+            k
+        );
+    }
+
+    /// Deleting a deactivated DID with `h` versions in its history and every proposal slot
+    /// taken.
+    #[benchmark]
+    fn delete_did(h: Linear<4, 1_000>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let (did, _) = register_did::<T>(&caller);
+        let len = T::MaxMetadataLength::get() as usize;
+        // Creation, the controller, the threshold and the deactivation make up four versions.
+        for _ in 4..h {
+            Pallet::<T>::set_metadata(
+                RawOrigin::Signed(caller.clone()).into(),
+                did.clone(),
+                filler(0, len),
+                filler(0, len),
+            )
+            .expect("the entry is replaced");
+        }
+        link_accounts::<T>(&did, T::MaxLinkedAccounts::get());
+        require_approvals::<T>(&caller, &did, 2);
+        for _ in 0..T::MaxPendingProposals::get() {
+            propose_deactivation::<T>(&caller, &did);
+        }
+        Pallet::<T>::approve_proposal(
+            RawOrigin::Signed(account("controller", 0, 0)).into(),
+            did.clone(),
+            0,
+        )
+        .expect("the second approval deactivates the DID");
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), did.clone(), h);
+
+        let did_id = Pallet::<T>::decode_did_id(&did).unwrap();
+        assert!(DidTombstones::<T>::contains_key(did_id));
+    }
+
+    /// Flagging `k` expired keys of the only DID with expiring keys in `on_idle`.
+    #[benchmark]
+    fn expire_keys(k: Linear<1, { T::MaxKeys::get() - 1 }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let (did, _) = register_did::<T>(&caller);
+        let valid_until = frame_system::Pallet::<T>::block_number() + 1u32.into();
+        for index in 0..k {
+            Pallet::<T>::add_key(
+                RawOrigin::Signed(caller.clone()).into(),
+                did.clone(),
+                KeyType::MlDsa87,
+                large_key(index),
+                vec![KeyRole::AssertionMethod],
+                None,
+                None,
+                Some(KeyValidityBound::Block(valid_until)),
+            )
+            .expect("key count is within bounds");
+        }
+        frame_system::Pallet::<T>::set_block_number(valid_until);
+
+        #[block]
+        {
+            Pallet::<T>::expire_keys(Weight::MAX);
+        }
+
+        let details = Pallet::<T>::get_did(did).unwrap();
+        assert_eq!(
+            details.keys.iter().filter(|key| key.expired).count() as u32,
+            k
+        );
+    }
+
+    #[benchmark]
+    fn emergency_revoke_key(k: Linear<2, { T::MaxKeys::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let (did, public) = register_did::<T>(&caller);
+        Pallet::<T>::update_roles(
+            RawOrigin::Signed(caller.clone()).into(),
+            did.clone(),
+            public.0.to_vec(),
+            vec![KeyRole::Authentication, KeyRole::CapabilityInvocation],
+        )
+        .expect("the creation key can be updated");
+        add_keys::<T>(&caller, &did, k - 1);
+        let did_id = Pallet::<T>::decode_did_id(&did).unwrap();
+        let revoked_key = large_key(k - 2);
+        let valid_until = frame_system::Pallet::<T>::block_number();
+        let payload = Pallet::<T>::emergency_revoke_payload(&did_id, &revoked_key, valid_until);
+        let signature = sign::<T>(&did, &payload);
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::None,
+            did.clone(),
+            revoked_key,
+            public.0.to_vec(),
+            valid_until,
+            signature,
+        );
+
+        assert!(Pallet::<T>::get_did(did).unwrap().keys[k as usize - 1].revoked);
+    }
+
+    #[benchmark]
+    fn link_account() {
+        let caller: T::AccountId = whitelisted_caller();
+        let (did, _) = register_did::<T>(&caller);
+        link_accounts::<T>(&did, T::MaxLinkedAccounts::get() - 1);
+        let did_id = Pallet::<T>::decode_did_id(&did).unwrap();
+        let payload = Pallet::<T>::link_account_payload(&did_id, &caller);
+        let signature = sign::<T>(&did, &payload);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()), did.clone(), signature);
+
+        assert_eq!(Pallet::<T>::get_did_of_account(caller), Some(did));
+    }
+
+    /// A controller unlinking the first of `a` accounts linked to its DID.
+    #[benchmark]
+    fn unlink_account(a: Linear<1, { T::MaxLinkedAccounts::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let (did, _) = register_did::<T>(&caller);
+        link_accounts::<T>(&did, a);
+        let linked: T::AccountId = account("linked", 0, 0);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), linked.clone());
+
+        assert_eq!(Pallet::<T>::get_did_of_account(linked), None);
+    }
+
+    /// Dispatching a remark, whose own weight is added to that of `dispatch_as_did`.
+    #[benchmark]
+    fn dispatch_as_did() {
+        let caller: T::AccountId = whitelisted_caller();
+        let did = register_full_did::<T>(&caller);
+        let did_id = Pallet::<T>::decode_did_id(&did).unwrap();
+        let call: <T as Config>::RuntimeCall =
+            frame_system::Call::<T>::remark { remark: vec![] }.into();
+        let payload =
+            Pallet::<T>::dispatch_payload(&did_id, &KeyRole::Authentication, &caller, &call);
+        let signature = sign::<T>(&did, &payload);
+        let nonce = Pallet::<T>::get_did(did.clone()).unwrap().nonce;
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            did.clone(),
+            KeyRole::Authentication,
+            Box::new(call),
+            signature,
+        );
+
+        assert_eq!(Pallet::<T>::get_did(did).unwrap().nonce, nonce + 1);
+    }
+
+    /// Migrating a record of `k` keys of the largest type from the original layout.
+    #[benchmark]
+    fn migrate_did_v1(k: Linear<0, { T::MaxKeys::get() }>) {
+        use crate::migrations::v1::{migrate_did, OldDidDetails, OldDidKey};
+
+        let did_id = [7u8; 32];
+        let old = OldDidDetails {
+            version: 0,
+            deactivated: false,
+            keys: (0..k)
+                .map(|index| OldDidKey {
+                    public_key: large_key(index),
+                    roles: vec![KeyRole::AssertionMethod],
+                    revoked: false,
+                })
+                .collect(),
+            services: vec![],
+            metadata: vec![],
+        };
+        frame_support::storage::unhashed::put(&DidRecords::<T>::hashed_key_for(did_id), &old);
+
+        #[block]
+        {
+            migrate_did::<T>(&did_id);
+        }
+
+        assert_eq!(DidRecords::<T>::get(did_id).unwrap().keys.len() as u32, k);
+    }
+
+    impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
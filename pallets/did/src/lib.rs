@@ -1,24 +1,205 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::ensure;
+extern crate alloc;
+
+use alloc::string::String;
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+    dispatch::{DispatchError, DispatchResult},
+    ensure,
+    traits::{EnsureOrigin, Get, ReservableCurrency},
+    weights::Weight,
+    RuntimeDebug,
+};
 pub use pallet::*;
-use sp_std::vec::Vec;
+use scale_info::TypeInfo;
+use sp_runtime::traits::Saturating;
+use sp_std::{marker::PhantomData, vec::Vec};
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod migrations;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+pub use weights::WeightInfo;
+
+/// Verification of payloads signed with the keys of a registered DID, for use by other
+/// pallets that accept DID-authorised calls.
+pub trait DidSignatureVerifier {
+    /// Checks `signature` over `payload` followed by the current nonce of `did` against the
+    /// keys of the DID holding `role` that are valid in the current block, and consumes the
+    /// nonce on success.
+    fn verify_did_signature(
+        did: &[u8],
+        role: KeyRole,
+        payload: &[u8],
+        signature: &[u8],
+    ) -> DispatchResult;
+
+    /// Registers a DID whose key is held in the benchmark keystore and returns it.
+    #[cfg(feature = "runtime-benchmarks")]
+    fn create_benchmark_did() -> Vec<u8>;
+
+    /// Signs `payload` for a DID created by [`Self::create_benchmark_did`], the way
+    /// [`Self::verify_did_signature`] expects it.
+    #[cfg(feature = "runtime-benchmarks")]
+    fn sign_for_benchmark(did: &[u8], payload: &[u8]) -> Vec<u8>;
+}
+
+/// Cleanup of state other pallets keep for a DID, run when `delete_did` removes it.
+pub trait OnDidDeleted {
+    /// Called with the DID string of a DID that was just removed.
+    fn on_did_deleted(did: &[u8]);
+
+    /// Upper bound of the weight of [`Self::on_did_deleted`].
+    fn weight() -> Weight;
+}
+
+impl OnDidDeleted for () {
+    fn on_did_deleted(_did: &[u8]) {}
+
+    fn weight() -> Weight {
+        Weight::zero()
+    }
+}
+
+/// Storage deposit reserved from `owner` for a record kept in state.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct Deposit<AccountId, Balance> {
+    pub owner: AccountId,
+    pub amount: Balance,
+}
+
+impl<AccountId, Balance: Saturating + Ord + Copy> Deposit<AccountId, Balance> {
+    /// Reserves `amount` from `owner`.
+    pub fn reserve<C>(owner: AccountId, amount: Balance) -> Result<Self, DispatchError>
+    where
+        C: ReservableCurrency<AccountId, Balance = Balance>,
+    {
+        C::reserve(&owner, amount)?;
+        Ok(Self { owner, amount })
+    }
+
+    /// Reserves or releases the difference between the held and the `required` amount.
+    pub fn adjust<C>(&mut self, required: Balance) -> DispatchResult
+    where
+        C: ReservableCurrency<AccountId, Balance = Balance>,
+    {
+        if required > self.amount {
+            C::reserve(&self.owner, required.saturating_sub(self.amount))?;
+        } else {
+            C::unreserve(&self.owner, self.amount.saturating_sub(required));
+        }
+        self.amount = required;
+        Ok(())
+    }
+
+    /// Releases the whole deposit back to its owner.
+    pub fn release<C>(self)
+    where
+        C: ReservableCurrency<AccountId, Balance = Balance>,
+    {
+        C::unreserve(&self.owner, self.amount);
+    }
+}
+
+/// Ensures that a call was dispatched by `dispatch_as_did` with a key holding the role `R`,
+/// and yields the authenticated [`DidOrigin`].
+pub struct EnsureDid<T, R>(PhantomData<(T, R)>);
+
+impl<T, R, O> EnsureOrigin<O> for EnsureDid<T, R>
+where
+    T: Config,
+    R: Get<KeyRole>,
+    O: Into<Result<Origin<T>, O>> + From<Origin<T>>,
+{
+    type Success = DidOrigin<T::AccountId>;
+
+    fn try_origin(o: O) -> Result<Self::Success, O> {
+        o.into().and_then(|origin| {
+            if origin.role == R::get() {
+                Ok(origin)
+            } else {
+                Err(O::from(origin))
+            }
+        })
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn try_successful_origin() -> Result<O, ()> {
+        let submitter: T::AccountId = frame_benchmarking::account("did_owner", 0, 0);
+        let (did, _) = crate::benchmarking::register_did::<T>(&submitter);
+        let did_id = Pallet::<T>::decode_did_id(&did).map_err(|_| ())?;
+        Ok(O::from(DidOrigin {
+            did_id,
+            network: DidNetwork::<T>::get(),
+            role: R::get(),
+            submitter,
+        }))
+    }
+}
 
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
-    use frame_support::{dispatch::DispatchResult, pallet_prelude::*};
+    use frame_support::{
+        dispatch::{GetDispatchInfo, PostDispatchInfo},
+        pallet_prelude::*,
+        traits::{Currency, ReservableCurrency, UnixTime},
+    };
     use frame_system::pallet_prelude::{BlockNumberFor, OriginFor};
+    use sp_core::{ecdsa, ed25519, sr25519};
     use sp_io::hashing::blake2_256;
-    use sp_runtime::traits::Zero;
-    use sp_std::vec;
+    #[cfg(feature = "try-runtime")]
+    use sp_runtime::TryRuntimeError;
+    use sp_runtime::{
+        traits::{Dispatchable, Hash, Zero},
+        SaturatedConversion,
+    };
+    use sp_std::{boxed::Box, vec};
 
     const DID_PREFIX: &[u8] = b"did:qsb:";
     const DID_MATERIAL_PREFIX: &[u8] = b"QSB_DID";
     const DID_CREATE_PREFIX: &[u8] = b"QSB_DID_CREATE";
+    const DID_EMERGENCY_REVOKE_PREFIX: &[u8] = b"QSB_DID_EMERGENCY_REVOKE";
+    const DID_LINK_ACCOUNT_PREFIX: &[u8] = b"QSB_DID_LINK_ACCOUNT";
+    const DID_DISPATCH_PREFIX: &[u8] = b"QSB_DID_DISPATCH";
+    const KEY_ID_PREFIX: &[u8] = b"#key-";
+
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    /// Storage version of the pallet, bumped by the migrations in [`crate::migrations`].
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+    /// Bound on the roles of a key. Roles are deduplicated, so every role fits.
+    pub type MaxKeyRoles = ConstU32<5>;
+
+    /// Bound on the updates of a batch waiting for the approval of the controllers of a DID.
+    pub type MaxProposedUpdates = ConstU32<8>;
+
+    /// Bound on the id of a key, which fits `#key-` followed by any `u32`.
+    pub type MaxKeyIdLength = ConstU32<16>;
+
+    /// Fragment identifying a key in its DID document, such as `#key-1`.
+    pub type KeyId = BoundedVec<u8, MaxKeyIdLength>;
+
+    /// Bound on the network segment of DIDs.
+    pub type MaxNetworkLength = ConstU32<16>;
+
+    /// Network segment of DIDs, such as `testnet` in `did:qsb:testnet:<id>`.
+    pub type NetworkName = BoundedVec<u8, MaxNetworkLength>;
+
+    /// Segments that follow `did:qsb:` in identifiers other than DIDs, which cannot name a
+    /// network.
+    const RESERVED_NETWORKS: &[&[u8]] = &[b"name", b"schema", b"statuslist"];
 
     #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
-    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
     pub enum KeyRole {
         Authentication,
         AssertionMethod,
@@ -27,51 +208,970 @@ pub mod pallet {
         CapabilityDelegation,
     }
 
+    impl KeyRole {
+        const ALL: [KeyRole; 5] = [
+            KeyRole::Authentication,
+            KeyRole::AssertionMethod,
+            KeyRole::KeyAgreement,
+            KeyRole::CapabilityInvocation,
+            KeyRole::CapabilityDelegation,
+        ];
+
+        /// Name of the role as spelled in genesis configs, such as `AssertionMethod`.
+        pub fn name(&self) -> &'static str {
+            match self {
+                KeyRole::Authentication => "Authentication",
+                KeyRole::AssertionMethod => "AssertionMethod",
+                KeyRole::KeyAgreement => "KeyAgreement",
+                KeyRole::CapabilityInvocation => "CapabilityInvocation",
+                KeyRole::CapabilityDelegation => "CapabilityDelegation",
+            }
+        }
+
+        /// The role named `name`, as returned by [`KeyRole::name`].
+        pub fn from_name(name: &str) -> Option<Self> {
+            Self::ALL.into_iter().find(|role| role.name() == name)
+        }
+    }
+
+    /// Algorithm of the key material stored in a [`DidKey`].
     #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
-    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
-    pub struct DidKey {
-        pub public_key: Vec<u8>,
-        pub roles: Vec<KeyRole>,
+    #[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum KeyType {
+        Ed25519,
+        Sr25519,
+        /// Compressed secp256k1 public key.
+        EcdsaSecp256k1,
+        MlDsa44,
+        MlDsa65,
+        MlDsa87,
+        Falcon512,
+        /// SLH-DSA with the 128-bit parameter sets.
+        SlhDsa128s,
+        MlKem512,
+        MlKem768,
+        MlKem1024,
+        /// Key migrated from the original layout, whose algorithm was not recorded and cannot
+        /// be told from its length. It holds no roles and can only be revoked or rotated.
+        Legacy,
+    }
+
+    impl KeyType {
+        const ALL: [KeyType; 12] = [
+            KeyType::Ed25519,
+            KeyType::Sr25519,
+            KeyType::EcdsaSecp256k1,
+            KeyType::MlDsa44,
+            KeyType::MlDsa65,
+            KeyType::MlDsa87,
+            KeyType::Falcon512,
+            KeyType::SlhDsa128s,
+            KeyType::MlKem512,
+            KeyType::MlKem768,
+            KeyType::MlKem1024,
+            KeyType::Legacy,
+        ];
+
+        /// Name of the key type as spelled in genesis configs, such as `Ed25519`.
+        pub fn name(&self) -> &'static str {
+            match self {
+                KeyType::Ed25519 => "Ed25519",
+                KeyType::Sr25519 => "Sr25519",
+                KeyType::EcdsaSecp256k1 => "EcdsaSecp256k1",
+                KeyType::MlDsa44 => "MlDsa44",
+                KeyType::MlDsa65 => "MlDsa65",
+                KeyType::MlDsa87 => "MlDsa87",
+                KeyType::Falcon512 => "Falcon512",
+                KeyType::SlhDsa128s => "SlhDsa128s",
+                KeyType::MlKem512 => "MlKem512",
+                KeyType::MlKem768 => "MlKem768",
+                KeyType::MlKem1024 => "MlKem1024",
+                KeyType::Legacy => "Legacy",
+            }
+        }
+
+        /// The key type named `name`, as returned by [`KeyType::name`].
+        pub fn from_name(name: &str) -> Option<Self> {
+            Self::ALL
+                .into_iter()
+                .find(|key_type| key_type.name() == name)
+        }
+
+        /// Length in bytes of an encoded public key of this type. Legacy keys have no fixed
+        /// length, and no key can be added as one.
+        pub fn public_key_len(&self) -> usize {
+            match self {
+                KeyType::Legacy => 0,
+                KeyType::Ed25519 | KeyType::Sr25519 | KeyType::SlhDsa128s => 32,
+                KeyType::EcdsaSecp256k1 => 33,
+                KeyType::MlDsa44 => 1312,
+                KeyType::MlDsa65 => 1952,
+                KeyType::MlDsa87 => 2592,
+                KeyType::Falcon512 => 897,
+                KeyType::MlKem512 => 800,
+                KeyType::MlKem768 => 1184,
+                KeyType::MlKem1024 => 1568,
+            }
+        }
+
+        /// Whether signatures made with this key type can be checked on chain.
+        pub fn is_verifiable(&self) -> bool {
+            matches!(
+                self,
+                KeyType::Ed25519 | KeyType::Sr25519 | KeyType::EcdsaSecp256k1
+            )
+        }
+
+        /// Whether keys of this type make signatures, and so can hold roles other than
+        /// `KeyAgreement`. ML-KEM keys only agree on keys.
+        pub fn is_signing(&self) -> bool {
+            !matches!(
+                self,
+                KeyType::MlKem512 | KeyType::MlKem768 | KeyType::MlKem1024 | KeyType::Legacy
+            )
+        }
+
+        /// DID Core verification method type used when resolving keys of this type.
+        pub fn verification_method_type(&self) -> &'static str {
+            match self {
+                KeyType::Ed25519 => "Ed25519VerificationKey2020",
+                KeyType::Sr25519 => "Sr25519VerificationKey2020",
+                KeyType::EcdsaSecp256k1 => "EcdsaSecp256k1VerificationKey2019",
+                _ => "JsonWebKey2020",
+            }
+        }
+    }
+
+    #[cfg_attr(
+        feature = "std",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(bound = "")
+    )]
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct DidKey<T: Config> {
+        /// Keys are numbered in the order they are added to the DID, from `#key-1`.
+        pub id: KeyId,
+        pub key_type: KeyType,
+        pub public_key: BoundedVec<u8, T::MaxKeyLength>,
+        pub roles: BoundedVec<KeyRole, MaxKeyRoles>,
+        /// DID controlling the key, when it is not the DID itself.
+        pub controller: Option<[u8; 32]>,
         pub revoked: bool,
+        /// The key is not valid before this point.
+        pub valid_from: Option<KeyValidityBound<T>>,
+        /// The key is not valid from this point on.
+        pub valid_until: Option<KeyValidityBound<T>>,
+        /// Set in `on_idle` once `valid_until` has passed.
+        pub expired: bool,
     }
 
+    /// Point in time at which a key becomes or stops being valid.
+    #[cfg_attr(
+        feature = "std",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(bound = "")
+    )]
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub enum KeyValidityBound<T: Config> {
+        Block(BlockNumberFor<T>),
+        /// Unix time in milliseconds.
+        Moment(u64),
+    }
+
+    impl<T: Config> KeyValidityBound<T> {
+        /// Whether the bound is reached at `block`, whose timestamp is `moment`.
+        fn reached(&self, block: BlockNumberFor<T>, moment: u64) -> bool {
+            match self {
+                KeyValidityBound::Block(bound) => block >= *bound,
+                KeyValidityBound::Moment(bound) => moment >= *bound,
+            }
+        }
+    }
+
+    /// Whether a key can be used at a given point in time.
     #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
-    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
-    pub struct ServiceEndpoint {
-        pub id: Vec<u8>,
-        pub service_type: Vec<u8>,
-        pub endpoint: Vec<u8>,
+    #[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum KeyStatus {
+        Valid,
+        NotYetValid,
+        Expired,
+        Revoked,
+    }
+
+    pub type ServiceUri<T> = BoundedVec<u8, <T as Config>::MaxUriLength>;
+
+    /// Where a service can be reached. DID Core allows a single URI, a set of them or a map.
+    #[cfg_attr(
+        feature = "std",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(bound = "")
+    )]
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub enum Endpoint<T: Config> {
+        Uri(ServiceUri<T>),
+        /// Several URIs, in order of preference.
+        Set(BoundedVec<ServiceUri<T>, T::MaxServiceEndpoints>),
+        /// URIs keyed by name, such as `origins` for a linked domain.
+        Map(BoundedVec<(ServiceUri<T>, ServiceUri<T>), T::MaxServiceEndpoints>),
+    }
+
+    impl<T: Config> From<Endpoint<T>> for EndpointInput {
+        fn from(endpoint: Endpoint<T>) -> Self {
+            match endpoint {
+                Endpoint::Uri(uri) => EndpointInput::Uri(uri.into_inner()),
+                Endpoint::Set(uris) => {
+                    EndpointInput::Set(uris.into_iter().map(|uri| uri.into_inner()).collect())
+                }
+                Endpoint::Map(entries) => EndpointInput::Map(
+                    entries
+                        .into_iter()
+                        .map(|(key, uri)| (key.into_inner(), uri.into_inner()))
+                        .collect(),
+                ),
+            }
+        }
+    }
+
+    /// [`Endpoint`] as given to the calls that set it, before it is checked and bounded.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        serde::Serialize,
+        serde::Deserialize,
+    )]
+    pub enum EndpointInput {
+        Uri(Vec<u8>),
+        Set(Vec<Vec<u8>>),
+        Map(Vec<(Vec<u8>, Vec<u8>)>),
+    }
+
+    #[cfg_attr(
+        feature = "std",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(bound = "")
+    )]
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct ServiceEndpoint<T: Config> {
+        /// Fragment identifying the service within the document, such as `#linked-domain`.
+        /// Services migrated from the original layout may hold other ids.
+        pub id: ServiceUri<T>,
+        pub service_type: ServiceUri<T>,
+        pub endpoint: Endpoint<T>,
+    }
+
+    #[cfg_attr(
+        feature = "std",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(bound = "")
+    )]
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct MetadataEntry<T: Config> {
+        pub key: BoundedVec<u8, T::MaxMetadataLength>,
+        pub value: BoundedVec<u8, T::MaxMetadataLength>,
+    }
+
+    /// Party allowed to vouch for the recovery of a DID.
+    #[cfg_attr(
+        feature = "std",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(bound = "")
+    )]
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub enum Guardian<T: Config> {
+        Account(T::AccountId),
+        /// Any controller of the guardian DID vouches on its behalf.
+        Did([u8; 32]),
+    }
+
+    /// Guardians able to install a new key set on a DID whose keys were lost.
+    #[cfg_attr(
+        feature = "std",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(bound = "")
+    )]
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct RecoveryConfig<T: Config> {
+        pub guardians: BoundedVec<Guardian<T>, T::MaxGuardians>,
+        /// Number of guardians that must vouch for a recovery.
+        pub threshold: u32,
+        /// Blocks between the threshold being reached and the recovery taking effect,
+        /// during which the DID can still cancel it.
+        pub delay: BlockNumberFor<T>,
+    }
+
+    #[cfg_attr(
+        feature = "std",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(bound = "")
+    )]
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct DidDetails<T: Config> {
+        pub version: u64,
+        /// Next nonce expected in payloads signed with the DID keys.
+        pub nonce: u64,
+        pub deactivated: bool,
+        /// Unix time in milliseconds at which the DID was created.
+        pub created: u64,
+        /// Unix time in milliseconds of the last change to the DID document.
+        pub updated: u64,
+        /// Accounts allowed to modify the DID document. Keys of the DID modify it through
+        /// `dispatch_as_did` instead, see [`Pallet::is_authorized`].
+        pub controllers: BoundedVec<T::AccountId, T::MaxControllers>,
+        /// Number of controllers that must approve a sensitive operation before it applies.
+        /// With a threshold of one, operations apply as soon as they are submitted.
+        pub threshold: u32,
+        /// Keys of the DID. Revoked and expired keys are kept, so that they keep resolving to
+        /// the DID with their status and cannot be added again, and count against the bound.
+        pub keys: BoundedVec<DidKey<T>, T::MaxKeys>,
+        /// Number of keys ever added to the DID, which numbers the next key.
+        pub keys_added: u32,
+        pub services: BoundedVec<ServiceEndpoint<T>, T::MaxServices>,
+        pub metadata: BoundedVec<MetadataEntry<T>, T::MaxMetadataEntries>,
+        pub recovery: Option<RecoveryConfig<T>>,
+        /// Number of entries in the change log of the DID, which its deposit covers.
+        pub history_len: u32,
     }
 
+    /// Kind of change recorded in the history of a DID.
     #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
-    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
-    pub struct MetadataEntry {
-        pub key: Vec<u8>,
-        pub value: Vec<u8>,
+    #[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum DidChangeKind {
+        Create,
+        AddKey,
+        RevokeKey,
+        Deactivate,
+        AddService,
+        RemoveService,
+        SetMetadata,
+        RemoveMetadata,
+        RotateKey,
+        UpdateRoles,
+        AddController,
+        RemoveController,
+        SetThreshold,
+        SetRecovery,
+        Recover,
+        ExpireKeys,
+        /// Several updates applied together by `apply_operations`.
+        Batch,
+        UpdateService,
+        /// The document was carried over from the layout that predates the change log.
+        Migrate,
+    }
+
+    /// Change to a DID document. Several of them can be applied together with
+    /// `apply_operations`. Key changes are sensitive and wait for the approval of the
+    /// controllers when the DID requires several, others are made at once by any authorized
+    /// caller. Existing keys are referred to by their id, such as `#key-1`, or by their
+    /// public key.
+    #[derive(
+        CloneNoBound, Encode, Decode, EqNoBound, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo,
+    )]
+    #[scale_info(skip_type_params(T))]
+    pub enum DocumentUpdate<T: Config> {
+        AddKey {
+            key_type: KeyType,
+            public_key: Vec<u8>,
+            roles: Vec<KeyRole>,
+            /// DID controlling the key, when it is not the DID itself.
+            controller: Option<Vec<u8>>,
+            valid_from: Option<KeyValidityBound<T>>,
+            valid_until: Option<KeyValidityBound<T>>,
+        },
+        RevokeKey {
+            key: Vec<u8>,
+        },
+        UpdateRoles {
+            key: Vec<u8>,
+            roles: Vec<KeyRole>,
+        },
+        AddService {
+            service_id: Vec<u8>,
+            service_type: Vec<u8>,
+            endpoint: EndpointInput,
+        },
+        /// Replaces the type and/or the endpoint of a service, keeping its position.
+        UpdateService {
+            service_id: Vec<u8>,
+            service_type: Option<Vec<u8>>,
+            endpoint: Option<EndpointInput>,
+        },
+        RemoveService {
+            service_id: Vec<u8>,
+        },
+        SetMetadata {
+            key: Vec<u8>,
+            value: Vec<u8>,
+        },
+        RemoveMetadata {
+            key: Vec<u8>,
+        },
+    }
+
+    /// [`DocumentUpdate`] checked and bounded to be kept in a proposal. It is checked again
+    /// when the proposal is applied.
+    #[cfg_attr(
+        feature = "std",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(bound = "")
+    )]
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub enum ProposedUpdate<T: Config> {
+        AddKey {
+            key_type: KeyType,
+            public_key: BoundedVec<u8, T::MaxKeyLength>,
+            roles: BoundedVec<KeyRole, MaxKeyRoles>,
+            controller: Option<[u8; 32]>,
+            valid_from: Option<KeyValidityBound<T>>,
+            valid_until: Option<KeyValidityBound<T>>,
+        },
+        RevokeKey {
+            key: BoundedVec<u8, T::MaxKeyLength>,
+        },
+        UpdateRoles {
+            key: BoundedVec<u8, T::MaxKeyLength>,
+            roles: BoundedVec<KeyRole, MaxKeyRoles>,
+        },
+        AddService(ServiceEndpoint<T>),
+        UpdateService {
+            service_id: ServiceUri<T>,
+            service_type: Option<ServiceUri<T>>,
+            endpoint: Option<Endpoint<T>>,
+        },
+        RemoveService {
+            service_id: ServiceUri<T>,
+        },
+        SetMetadata(MetadataEntry<T>),
+        RemoveMetadata {
+            key: BoundedVec<u8, T::MaxMetadataLength>,
+        },
+    }
+
+    /// Sensitive change to a DID. When the DID requires more than one controller approval
+    /// it is queued as a [`DidProposal`] instead of being applied at once.
+    #[cfg_attr(
+        feature = "std",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(bound = "")
+    )]
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub enum DidOperation<T: Config> {
+        Deactivate,
+        /// Revokes `old_key`, given by its id or public key, in favour of a new key with the
+        /// same controller.
+        RotateKey {
+            old_key: BoundedVec<u8, T::MaxKeyLength>,
+            new_key_type: KeyType,
+            new_public_key: BoundedVec<u8, T::MaxKeyLength>,
+            roles: BoundedVec<KeyRole, MaxKeyRoles>,
+            valid_from: Option<KeyValidityBound<T>>,
+            valid_until: Option<KeyValidityBound<T>>,
+        },
+        AddController(T::AccountId),
+        RemoveController(T::AccountId),
+        SetThreshold(u32),
+        /// Sets the recovery configuration, or removes it with `None`.
+        SetRecovery(Option<RecoveryConfig<T>>),
+        /// Adds a key under the next key id. `controller` is the DID controlling the key, when
+        /// it is not the DID itself.
+        AddKey {
+            key_type: KeyType,
+            public_key: BoundedVec<u8, T::MaxKeyLength>,
+            roles: BoundedVec<KeyRole, MaxKeyRoles>,
+            controller: Option<[u8; 32]>,
+            valid_from: Option<KeyValidityBound<T>>,
+            valid_until: Option<KeyValidityBound<T>>,
+        },
+        /// Revokes `key`, given by its id or public key.
+        RevokeKey {
+            key: BoundedVec<u8, T::MaxKeyLength>,
+        },
+        /// Replaces the roles of `key`, given by its id or public key.
+        UpdateRoles {
+            key: BoundedVec<u8, T::MaxKeyLength>,
+            roles: BoundedVec<KeyRole, MaxKeyRoles>,
+        },
+        /// Updates applied together by `apply_operations`, which change the keys of the DID.
+        Batch(BoundedVec<ProposedUpdate<T>, MaxProposedUpdates>),
+    }
+
+    /// Operation waiting for the approval of the controllers of a DID.
+    #[cfg_attr(
+        feature = "std",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(bound = "")
+    )]
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct DidProposal<T: Config> {
+        pub id: u32,
+        pub operation: DidOperation<T>,
+        /// Deposit reserved from the proposer while the proposal is pending.
+        pub deposit: Deposit<T::AccountId, BalanceOf<T>>,
+        /// Controllers that approved the operation, the proposer included.
+        pub approvals: BoundedVec<T::AccountId, T::MaxControllers>,
+        /// First block at which the proposal can no longer be approved.
+        pub expires_at: BlockNumberFor<T>,
     }
 
+    /// Recovery of a DID initiated by one of its guardians.
+    #[cfg_attr(
+        feature = "std",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(bound = "")
+    )]
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct ActiveRecovery<T: Config> {
+        /// Keys installed by the recovery. All keys of the DID are revoked in their favour.
+        /// They are given their ids once installed.
+        pub new_keys: BoundedVec<DidKey<T>, T::MaxKeys>,
+        /// Guardians that vouched for the recovery, the initiator included.
+        pub vouchers: BoundedVec<Guardian<T>, T::MaxGuardians>,
+        /// Deposit reserved from the initiator while the recovery is active.
+        pub deposit: Deposit<T::AccountId, BalanceOf<T>>,
+        /// Block from which the recovery can be completed, set once enough guardians vouched.
+        pub executable_at: Option<BlockNumberFor<T>>,
+    }
+
+    /// Entry of the change log of a DID. The document itself is not kept: the state of the
+    /// block that made the change holds it.
+    #[cfg_attr(
+        feature = "std",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(bound = "")
+    )]
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct DidChange<T: Config> {
+        /// Version of the document the change produced.
+        pub version: u64,
+        pub block_number: BlockNumberFor<T>,
+        /// Unix time in milliseconds of the block that applied the change.
+        pub timestamp: u64,
+        pub kind: DidChangeKind,
+        /// Id of the key the change added, revoked or otherwise changed, the last one when it
+        /// changed several.
+        pub key: Option<KeyId>,
+    }
+
+    /// What remains of a deleted DID. It keeps the identifier from being registered again.
     #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
-    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
-    pub struct DidDetails {
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct DidTombstone {
+        /// Last version of the DID document before it was deleted.
         pub version: u64,
-        pub deactivated: bool,
-        pub keys: Vec<DidKey>,
-        pub services: Vec<ServiceEndpoint>,
-        pub metadata: Vec<MetadataEntry>,
+        /// Unix time in milliseconds at which the DID was deleted.
+        pub deleted: u64,
+    }
+
+    /// Link of an account to a DID, proven by both of them.
+    #[cfg_attr(
+        feature = "std",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(bound = "")
+    )]
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct AccountLink<T: Config> {
+        pub did_id: [u8; 32],
+        /// Deposit reserved from the linked account.
+        pub deposit: Deposit<T::AccountId, BalanceOf<T>>,
+    }
+
+    /// A DID authenticated by `dispatch_as_did` with one of its keys holding `role`.
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct DidOrigin<AccountId> {
+        pub did_id: [u8; 32],
+        /// Network segment of the DIDs of the chain, if it has one.
+        pub network: Option<NetworkName>,
+        pub role: KeyRole,
+        /// Account that submitted `dispatch_as_did`, which pays for any deposit of the call.
+        pub submitter: AccountId,
+    }
+
+    impl<AccountId> DidOrigin<AccountId> {
+        /// The DID, as `did:qsb:<id>` or `did:qsb:<network>:<id>`.
+        pub fn did(&self) -> Vec<u8> {
+            did_string(
+                &self.did_id,
+                self.network.as_ref().map(|network| network.as_slice()),
+            )
+        }
+    }
+
+    #[pallet::origin]
+    pub type Origin<T> = DidOrigin<<T as frame_system::Config>::AccountId>;
+
+    /// Caller of a call that changes a DID.
+    #[derive(Clone, Eq, PartialEq, RuntimeDebug)]
+    pub enum DidCaller<AccountId> {
+        /// An account that signed the call itself.
+        Account(AccountId),
+        /// A DID whose key signed the call for `dispatch_as_did`.
+        Did(DidOrigin<AccountId>),
+    }
+
+    impl<AccountId> DidCaller<AccountId> {
+        /// The account that submitted the call, which pays for any deposit it needs.
+        pub fn account(&self) -> &AccountId {
+            match self {
+                Self::Account(who) => who,
+                Self::Did(origin) => &origin.submitter,
+            }
+        }
+    }
+
+    /// Who made a change reported by an event of a DID.
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+    pub enum DidActor<AccountId> {
+        /// The account that signed the call: a controller, the submitter of a call the DID
+        /// dispatched with `dispatch_as_did`, or whoever completed a recovery.
+        Account(AccountId),
+        /// A key of the DID that signed a call submitted without an account.
+        Key(KeyId),
+        /// The runtime itself, as when keys expire in `on_idle`.
+        Runtime,
+    }
+
+    /// DID registered in the genesis block. Genesis configs are deserialized in `no_std`
+    /// builds as well, so key types and roles are given by name rather than as [`KeyType`] and
+    /// [`KeyRole`], whose serde support is only built with `std`.
+    #[derive(Clone, Eq, PartialEq, RuntimeDebug, serde::Serialize, serde::Deserialize)]
+    pub struct GenesisDid<AccountId> {
+        /// Accounts controlling the DID.
+        pub controllers: Vec<AccountId>,
+        /// Keys as `(key_type, public_key, roles)`, named as by [`KeyType::name`] and
+        /// [`KeyRole::name`]. The identifier of the DID is derived from the first one with
+        /// [`Pallet::genesis_did_id`].
+        pub keys: Vec<(String, Vec<u8>, Vec<String>)>,
+        /// Services as `(id, service_type, endpoint)`.
+        pub services: Vec<(Vec<u8>, Vec<u8>, EndpointInput)>,
     }
 
     #[pallet::pallet]
-    #[pallet::without_storage_info]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     #[pallet::config]
     pub trait Config: frame_system::Config {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        /// The overarching origin, which DID origins convert into. Calls that change a DID
+        /// accept it back from `dispatch_as_did`, so that keys of the DID can make them.
+        type RuntimeOrigin: From<Origin<Self>>
+            + From<<Self as frame_system::Config>::RuntimeOrigin>
+            + Into<Result<Origin<Self>, <Self as Config>::RuntimeOrigin>>;
+        /// The overarching call, which `dispatch_as_did` dispatches with a DID origin.
+        type RuntimeCall: Parameter
+            + Dispatchable<
+                RuntimeOrigin = <Self as Config>::RuntimeOrigin,
+                PostInfo = PostDispatchInfo,
+            > + GetDispatchInfo
+            + From<frame_system::Call<Self>>;
+        /// Source of the wall-clock time recorded in DID document metadata.
+        type UnixTime: UnixTime;
+        /// Currency the storage deposit of a DID is reserved in.
+        type Currency: ReservableCurrency<Self::AccountId>;
+        /// Deposit reserved for every stored DID document.
+        #[pallet::constant]
+        type DepositPerItem: Get<BalanceOf<Self>>;
+        /// Deposit reserved per byte of an encoded DID document.
+        #[pallet::constant]
+        type DepositPerByte: Get<BalanceOf<Self>>;
+        /// Maximum number of controller accounts of a DID.
+        #[pallet::constant]
+        type MaxControllers: Get<u32>;
+        /// Maximum number of keys of a DID, revoked keys included.
+        #[pallet::constant]
+        type MaxKeys: Get<u32>;
+        /// Maximum length of a public key. Must fit the largest supported key type.
+        #[pallet::constant]
+        type MaxKeyLength: Get<u32>;
+        /// Maximum number of service endpoints of a DID.
+        #[pallet::constant]
+        type MaxServices: Get<u32>;
+        /// Maximum length of the id, type and endpoint URI of a service.
+        #[pallet::constant]
+        type MaxUriLength: Get<u32>;
+        /// Maximum number of URIs in a set or map endpoint of a service.
+        #[pallet::constant]
+        type MaxServiceEndpoints: Get<u32>;
+        /// Maximum number of service types on the allowlist.
+        #[pallet::constant]
+        type MaxServiceTypes: Get<u32>;
+        /// Origin allowed to set the allowlist of service types, such as governance.
+        type ServiceTypeOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
+        /// Maximum number of metadata entries of a DID.
+        #[pallet::constant]
+        type MaxMetadataEntries: Get<u32>;
+        /// Maximum length of a metadata key or value.
+        #[pallet::constant]
+        type MaxMetadataLength: Get<u32>;
+        /// Maximum number of proposals pending for a DID at once.
+        #[pallet::constant]
+        type MaxPendingProposals: Get<u32>;
+        /// Number of blocks during which a proposal can be approved.
+        #[pallet::constant]
+        type ProposalLifetime: Get<BlockNumberFor<Self>>;
+        /// Maximum number of recovery guardians of a DID.
+        #[pallet::constant]
+        type MaxGuardians: Get<u32>;
+        /// Shortest delay a recovery configuration may set.
+        #[pallet::constant]
+        type MinRecoveryDelay: Get<BlockNumberFor<Self>>;
+        /// Transaction pool priority of unsigned emergency key revocations. It should be
+        /// above that of regular transactions so that revocations go through under load.
+        #[pallet::constant]
+        type UnsignedPriority: Get<TransactionPriority>;
+        /// Maximum number of accounts linked to a DID.
+        #[pallet::constant]
+        type MaxLinkedAccounts: Get<u32>;
+        /// Removes what other pallets, such as the name registry, hold for a deleted DID.
+        type OnDidDeleted: OnDidDeleted;
+        /// Weight information for the extrinsics of this pallet.
+        type WeightInfo: WeightInfo;
     }
 
     #[pallet::storage]
     pub(super) type DidRecords<T: Config> =
-        StorageMap<_, Twox64Concat, [u8; 32], DidDetails, OptionQuery>;
+        StorageMap<_, Twox64Concat, [u8; 32], DidDetails<T>, OptionQuery>;
+
+    /// Storage deposit held for each DID document. Documents registered before deposits were
+    /// introduced have none until their next change.
+    #[pallet::storage]
+    pub(super) type DidDeposits<T: Config> =
+        StorageMap<_, Twox64Concat, [u8; 32], Deposit<T::AccountId, BalanceOf<T>>, OptionQuery>;
+
+    /// Every version of every DID document, keyed by DID id and version.
+    #[pallet::storage]
+    pub(super) type DidHistory<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, [u8; 32], Twox64Concat, u64, DidChange<T>, OptionQuery>;
+
+    /// Operations awaiting controller approval, keyed by DID id and proposal id.
+    #[pallet::storage]
+    pub(super) type Proposals<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, [u8; 32], Twox64Concat, u32, DidProposal<T>, OptionQuery>;
+
+    /// Id given to the next proposal made for a DID.
+    #[pallet::storage]
+    pub(super) type NextProposalId<T: Config> =
+        StorageMap<_, Twox64Concat, [u8; 32], u32, ValueQuery>;
+
+    /// Recoveries in progress, keyed by the id of the DID being recovered.
+    #[pallet::storage]
+    pub(super) type Recoveries<T: Config> =
+        StorageMap<_, Twox64Concat, [u8; 32], ActiveRecovery<T>, OptionQuery>;
+
+    /// DIDs with keys that have a `valid_until` bound and are not yet flagged as expired.
+    #[pallet::storage]
+    pub(super) type ExpiringDids<T: Config> =
+        StorageMap<_, Twox64Concat, [u8; 32], (), OptionQuery>;
+
+    /// Last DID checked for expired keys, from which `on_idle` carries on.
+    #[pallet::storage]
+    pub(super) type ExpiryCursor<T: Config> = StorageValue<_, [u8; 32], OptionQuery>;
+
+    /// Progress of [`crate::migrations::v1::MigrateToV1`], set while DID records are migrated
+    /// in batches from `on_initialize`: the last DID migrated, or `None` before the first
+    /// batch.
+    #[pallet::storage]
+    pub(super) type MigrationCursor<T: Config> = StorageValue<_, Option<[u8; 32]>, OptionQuery>;
+
+    /// Deleted DIDs, whose identifiers cannot be registered again.
+    #[pallet::storage]
+    pub(super) type DidTombstones<T: Config> =
+        StorageMap<_, Twox64Concat, [u8; 32], DidTombstone, OptionQuery>;
+
+    /// Service types that can be added to DID documents. Any type is accepted when unset.
+    #[pallet::storage]
+    pub(super) type AllowedServiceTypes<T: Config> =
+        StorageValue<_, BoundedVec<ServiceUri<T>, T::MaxServiceTypes>, OptionQuery>;
+
+    /// DIDs holding each public key, revoked and expired keys included.
+    #[pallet::storage]
+    pub(super) type KeyToDid<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        BoundedVec<u8, T::MaxKeyLength>,
+        Twox64Concat,
+        [u8; 32],
+        (),
+        OptionQuery,
+    >;
+
+    /// DID each linked account belongs to.
+    #[pallet::storage]
+    pub(super) type AccountDids<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, AccountLink<T>, OptionQuery>;
+
+    /// Network segment of the DIDs of this chain, set at genesis. DIDs carry none when unset.
+    #[pallet::storage]
+    pub(super) type DidNetwork<T: Config> = StorageValue<_, NetworkName, OptionQuery>;
+
+    /// Accounts linked to each DID, in the order they were linked.
+    #[pallet::storage]
+    pub(super) type LinkedAccounts<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        [u8; 32],
+        BoundedVec<T::AccountId, T::MaxLinkedAccounts>,
+        ValueQuery,
+    >;
 
     #[pallet::error]
     pub enum Error<T> {
@@ -85,410 +1185,3490 @@ pub mod pallet {
         ServiceAlreadyExists,
         ServiceNotFound,
         MetadataNotFound,
+        NotController,
+        ControllerAlreadyExists,
+        ControllerNotFound,
+        LastController,
+        InvalidSignature,
+        InvalidKeyLength,
+        UnsupportedKeyType,
+        TooManyControllers,
+        TooManyKeys,
+        KeyTooLong,
+        TooManyServices,
+        ServiceTooLong,
+        TooManyMetadataEntries,
+        MetadataTooLong,
+        /// The threshold would be zero or exceed the number of controllers.
+        InvalidThreshold,
+        ProposalNotFound,
+        ProposalExpired,
+        AlreadyApproved,
+        TooManyProposals,
+        /// Only the proposer can cancel a proposal before it expires.
+        NotProposer,
+        TooManyGuardians,
+        DuplicateGuardian,
+        RecoveryDelayTooShort,
+        RecoveryNotConfigured,
+        NotGuardian,
+        RecoveryAlreadyActive,
+        RecoveryNotActive,
+        AlreadyVouched,
+        /// Not enough guardians vouched for the recovery, or its delay has not passed.
+        RecoveryNotReady,
+        NoRecoveryKeys,
+        /// The DID was deleted and its identifier cannot be registered again.
+        DidDeleted,
+        /// Only deactivated DIDs can be deleted.
+        DidNotDeactivated,
+        /// The given history length is lower than the number of recorded versions.
+        InvalidHistoryLength,
+        /// The key would stop being valid before it becomes valid, or has already expired.
+        InvalidKeyValidity,
+        /// The block the emergency revocation was valid until has passed.
+        RevocationExpired,
+        /// The signing key of an emergency revocation is not a valid `CapabilityInvocation`
+        /// key of the DID, or is the key being revoked.
+        InvalidSignerKey,
+        NoOperations,
+        /// The account is already linked to a DID.
+        AccountAlreadyLinked,
+        AccountNotLinked,
+        TooManyLinkedAccounts,
+        /// Service ids are fragments such as `#linked-domain`.
+        InvalidServiceId,
+        /// An endpoint is not an absolute URI, a set or map is empty, or a map key is repeated
+        /// or not made of letters, digits, `-` and `_`.
+        InvalidServiceEndpoint,
+        TooManyServiceEndpoints,
+        ServiceTypeNotAllowed,
+        TooManyServiceTypes,
+        /// The DID would be left without a `CapabilityInvocation` key whose signatures can be
+        /// checked on chain, valid now and with no end to its validity.
+        LastCapabilityInvocationKey,
+        /// The controller of a key is not a registered DID.
+        KeyControllerNotFound,
+        /// The DID names another network than that of this chain, or none while the chain
+        /// has one.
+        WrongDidNetwork,
+        /// A batch changing the keys of a DID that needs several approvals holds more updates
+        /// than a proposal can.
+        TooManyOperations,
+        /// Keys of a type that does not sign can only hold the `KeyAgreement` role.
+        RoleNotSupported,
     }
 
+    /// Events of a DID carry its string and raw identifiers, and are deposited with the topic
+    /// [`Pallet::did_topic`] of the DID. Events of changes to the document carry the version
+    /// the change produced and who made it.
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         DidCreated {
             did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
+            key_id: KeyId,
+            key_type: KeyType,
+            public_key: Vec<u8>,
         },
         KeyAdded {
             did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
+            key_id: KeyId,
+            key_type: KeyType,
             public_key: Vec<u8>,
+            roles: Vec<KeyRole>,
+            controller: Option<[u8; 32]>,
         },
         KeyRevoked {
             did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
+            key_id: KeyId,
             public_key: Vec<u8>,
         },
         DidDeactivated {
             did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
         },
         KeyRotated {
             did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
+            old_key_id: KeyId,
             old_public_key: Vec<u8>,
+            new_key_id: KeyId,
             new_public_key: Vec<u8>,
+            roles: Vec<KeyRole>,
         },
         RolesUpdated {
             did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
+            key_id: KeyId,
             public_key: Vec<u8>,
+            roles: Vec<KeyRole>,
         },
         ServiceAdded {
             did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
             service_id: Vec<u8>,
+            service_type: Vec<u8>,
         },
         ServiceRemoved {
             did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
+            service_id: Vec<u8>,
+        },
+        /// `service_type` is the type of the service after the update.
+        ServiceUpdated {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
             service_id: Vec<u8>,
+            service_type: Vec<u8>,
         },
+        /// The allowlist of service types was replaced, or lifted when `None`.
+        AllowedServiceTypesSet { service_types: Option<Vec<Vec<u8>>> },
         MetadataSet {
             did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
             key: Vec<u8>,
         },
         MetadataRemoved {
             did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
             key: Vec<u8>,
         },
+        ControllerAdded {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
+            controller: T::AccountId,
+        },
+        ControllerRemoved {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
+            controller: T::AccountId,
+        },
+        ThresholdSet {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
+            threshold: u32,
+        },
+        ProposalCreated {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            proposal_id: u32,
+            proposer: T::AccountId,
+            operation: DidOperation<T>,
+            expires_at: BlockNumberFor<T>,
+        },
+        ProposalApproved {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            proposal_id: u32,
+            approver: T::AccountId,
+        },
+        /// The approved operation is reported by its own event.
+        ProposalExecuted {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            proposal_id: u32,
+        },
+        ProposalCancelled {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            proposal_id: u32,
+        },
+        ProposalExpired {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            proposal_id: u32,
+        },
+        RecoveryConfigured {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
+        },
+        RecoveryRemoved {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
+        },
+        /// A guardian started a recovery. `executable_at` is set once enough guardians
+        /// vouched for it.
+        RecoveryInitiated {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            guardian: Guardian<T>,
+            executable_at: Option<BlockNumberFor<T>>,
+        },
+        RecoveryVouched {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            guardian: Guardian<T>,
+            executable_at: Option<BlockNumberFor<T>>,
+        },
+        RecoveryCancelled {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            actor: DidActor<T::AccountId>,
+        },
+        /// A recovery installed a new key set. The replaced keys are reported with
+        /// `KeyRotated` events.
+        DidRecovered {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
+        },
+        /// `version` is the last version of the document, kept in the tombstone.
+        DidDeleted {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
+        },
+        KeyExpired {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
+            key_id: KeyId,
+            public_key: Vec<u8>,
+        },
+        OperationsApplied {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            version: u64,
+            actor: DidActor<T::AccountId>,
+            count: u32,
+        },
+        AccountLinked {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            account: T::AccountId,
+        },
+        AccountUnlinked {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            account: T::AccountId,
+        },
+        /// A call was dispatched with the origin of the DID.
+        DidDispatched {
+            did: Vec<u8>,
+            did_id: [u8; 32],
+            role: KeyRole,
+            submitter: T::AccountId,
+            result: DispatchResult,
+        },
+    }
+
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        /// Network segment of the DIDs of the chain, such as `testnet` for
+        /// `did:qsb:testnet:<id>`. It is made of lowercase letters and digits.
+        pub network: Option<Vec<u8>>,
+        /// Allowlist of service types, as set by `set_allowed_service_types`.
+        pub allowed_service_types: Option<Vec<Vec<u8>>>,
+        /// Identifier of the chain, such as the id of its chain spec, that the identifiers of
+        /// genesis DIDs are derived from so that they differ from chain to chain.
+        #[serde(default)]
+        pub chain_id: Vec<u8>,
+        /// DIDs registered at genesis. The deposit of each is reserved from its first
+        /// controller, which must be endowed by then.
+        pub dids: Vec<GenesisDid<T::AccountId>>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            if let Some(network) = &self.network {
+                let network =
+                    Pallet::<T>::checked_network(network).expect("genesis DID network is valid");
+                DidNetwork::<T>::put(network);
+            }
+            if let Some(service_types) = &self.allowed_service_types {
+                let bounded = service_types
+                    .iter()
+                    .map(|service_type| Pallet::<T>::bounded_uri(service_type))
+                    .collect::<Result<Vec<_>, _>>()
+                    .expect("genesis service types fit MaxUriLength");
+                AllowedServiceTypes::<T>::put(
+                    BoundedVec::try_from(bounded)
+                        .expect("genesis service types fit MaxServiceTypes"),
+                );
+            }
+            for did in &self.dids {
+                Pallet::<T>::register_genesis_did(&self.chain_id, did)
+                    .expect("genesis DIDs are valid and their controllers can pay their deposit");
+            }
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+            crate::migrations::v1::migrate_batch::<T>(crate::migrations::v1::batch_limit::<T>())
+        }
+
+        fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            Self::expire_keys(remaining_weight)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_n: BlockNumberFor<T>) -> Result<(), TryRuntimeError> {
+            Self::do_try_state()
+        }
+    }
+
+    #[pallet::validate_unsigned]
+    impl<T: Config> ValidateUnsigned for Pallet<T> {
+        type Call = Call<T>;
+
+        /// Emergency revocations are valid until their `valid_until` block and provide the
+        /// DID nonce they are signed over, so only one of them per nonce enters the pool.
+        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            let Call::emergency_revoke_key {
+                did_id,
+                public_key,
+                signer_public_key,
+                valid_until,
+                signature,
+            } = call
+            else {
+                return InvalidTransaction::Call.into();
+            };
+            let did_id = Self::decode_did_id(did_id).map_err(|_| InvalidTransaction::Call)?;
+            let nonce = Self::check_emergency_revocation(
+                &did_id,
+                public_key,
+                signer_public_key,
+                *valid_until,
+                signature,
+            )
+            .map_err(|error| match error {
+                Error::<T>::RevocationExpired => InvalidTransaction::Stale,
+                Error::<T>::InvalidSignature => InvalidTransaction::BadProof,
+                _ => InvalidTransaction::Call,
+            })?;
+
+            let remaining = valid_until.saturating_sub(frame_system::Pallet::<T>::block_number());
+            ValidTransaction::with_tag_prefix("DidEmergencyRevocation")
+                .priority(T::UnsignedPriority::get())
+                .and_provides((did_id, nonce))
+                .longevity(remaining.saturated_into::<u64>().max(1))
+                .propagate(true)
+                .build()
+        }
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
+        /// Registers a DID whose identifier is derived from `public_key` and, when given,
+        /// `salt`. Different salts let the same key create several DIDs.
         #[pallet::call_index(0)]
-        #[pallet::weight({0})]
+        #[pallet::weight(T::WeightInfo::create_did())]
         pub fn create_did(
             origin: OriginFor<T>,
+            key_type: KeyType,
             public_key: Vec<u8>,
-            _did_signature: Vec<u8>,
+            salt: Option<[u8; 32]>,
+            did_signature: Vec<u8>,
         ) -> DispatchResult {
-            let _ = frame_system::ensure_signed(origin)?;
-            let did_id = Self::did_id_from_public_key(&public_key);
+            let who = frame_system::ensure_signed(origin)?;
+            let bounded_key = Self::bounded_key(key_type, &public_key)?;
+            ensure!(key_type.is_verifiable(), Error::<T>::UnsupportedKeyType);
+            let did_id = Self::did_id_from_public_key(&public_key, salt.as_ref());
             ensure!(
                 !DidRecords::<T>::contains_key(did_id),
                 Error::<T>::DidAlreadyExists
             );
+            ensure!(
+                !DidTombstones::<T>::contains_key(did_id),
+                Error::<T>::DidDeleted
+            );
 
-            let details = DidDetails {
+            let mut payload = Self::create_payload(&public_key, salt.as_ref(), &who);
+            payload.extend_from_slice(&0u64.to_le_bytes());
+            ensure!(
+                Self::verify_key_signature(key_type, &public_key, &payload, &did_signature),
+                Error::<T>::InvalidSignature
+            );
+
+            let now = Self::now();
+            let mut details = DidDetails {
                 version: 0,
+                nonce: 1,
                 deactivated: false,
-                keys: vec![DidKey {
-                    public_key,
-                    roles: vec![KeyRole::Authentication],
+                created: now,
+                updated: now,
+                controllers: BoundedVec::try_from(vec![who.clone()])
+                    .map_err(|_| Error::<T>::TooManyControllers)?,
+                threshold: 1,
+                keys: BoundedVec::try_from(vec![DidKey {
+                    id: Self::key_id(0),
+                    key_type,
+                    public_key: bounded_key,
+                    roles: Self::bounded_roles(vec![
+                        KeyRole::Authentication,
+                        KeyRole::CapabilityInvocation,
+                    ]),
+                    controller: None,
                     revoked: false,
-                }],
-                services: Vec::new(),
-                metadata: Vec::new(),
+                    valid_from: None,
+                    valid_until: None,
+                    expired: false,
+                }])
+                .map_err(|_| Error::<T>::TooManyKeys)?,
+                keys_added: 1,
+                services: BoundedVec::new(),
+                metadata: BoundedVec::new(),
+                recovery: None,
+                history_len: 0,
             };
 
+            let key_id = details.keys[0].id.clone();
+            Self::index_keys(&did_id, &details.keys);
+            Self::record_change(
+                &did_id,
+                DidChangeKind::Create,
+                &mut details,
+                Some(key_id.clone()),
+            );
+            let deposit =
+                Deposit::reserve::<T::Currency>(who.clone(), Self::deposit_for(&details))?;
+            DidDeposits::<T>::insert(did_id, deposit);
             DidRecords::<T>::insert(did_id, details);
-            let did = Self::did_string_from_did_id(&did_id);
-            Self::deposit_event(Event::DidCreated { did });
+            Self::deposit_did_event(
+                &did_id,
+                Event::DidCreated {
+                    did: Self::did_string_from_did_id(&did_id),
+                    did_id,
+                    version: 0,
+                    actor: DidActor::Account(who),
+                    key_id,
+                    key_type,
+                    public_key,
+                },
+            );
             Ok(())
         }
 
+        /// Adds a key to the DID under the next key id. `controller` is the DID controlling the
+        /// key, when it is not the DID itself.
         #[pallet::call_index(1)]
-        #[pallet::weight({0})]
+        #[pallet::weight(
+            T::WeightInfo::add_key(T::MaxKeys::get())
+                .max(T::WeightInfo::propose_operation(T::MaxPendingProposals::get()))
+        )]
         pub fn add_key(
             origin: OriginFor<T>,
             did_id: Vec<u8>,
+            key_type: KeyType,
             public_key: Vec<u8>,
             roles: Vec<KeyRole>,
+            controller: Option<Vec<u8>>,
+            valid_from: Option<KeyValidityBound<T>>,
+            valid_until: Option<KeyValidityBound<T>>,
         ) -> DispatchResult {
-            let _ = frame_system::ensure_signed(origin)?;
-            let did_id = Self::decode_did_id(&did_id)?;
-            let did = Self::did_string_from_did_id(&did_id);
-
-            DidRecords::<T>::try_mutate(did_id, |maybe_details| -> DispatchResult {
-                let details = maybe_details.as_mut().ok_or(Error::<T>::DidNotFound)?;
-                ensure!(!details.deactivated, Error::<T>::DidDeactivated);
-                ensure!(
-                    !details.keys.iter().any(|key| key.public_key == public_key),
-                    Error::<T>::KeyAlreadyExists
-                );
-
-                details.keys.push(DidKey {
-                    public_key: public_key.clone(),
-                    roles,
-                    revoked: false,
-                });
-                details.version = details.version.saturating_add(1);
-                Ok(())
-            })?;
-
-            Self::deposit_event(Event::KeyAdded { did, public_key });
-            Ok(())
+            let caller = Self::ensure_caller(origin)?;
+            Self::ensure_valid_bounds(&valid_from, &valid_until)?;
+            let operation = DidOperation::AddKey {
+                key_type,
+                public_key: Self::bounded_key(key_type, &public_key)?,
+                roles: Self::checked_roles(key_type, roles)?,
+                controller: Self::checked_key_controller(controller)?,
+                valid_from,
+                valid_until,
+            };
+            Self::submit_operation(&did_id, &caller, operation)
         }
 
+        /// Revokes `key`, given by its id, such as `#key-1`, or by its public key.
         #[pallet::call_index(2)]
-        #[pallet::weight({0})]
-        pub fn revoke_key(
-            origin: OriginFor<T>,
-            did_id: Vec<u8>,
-            public_key: Vec<u8>,
-        ) -> DispatchResult {
-            let _ = frame_system::ensure_signed(origin)?;
-            let did_id = Self::decode_did_id(&did_id)?;
-            let did = Self::did_string_from_did_id(&did_id);
-
-            DidRecords::<T>::try_mutate(did_id, |maybe_details| -> DispatchResult {
-                let details = maybe_details.as_mut().ok_or(Error::<T>::DidNotFound)?;
-                ensure!(!details.deactivated, Error::<T>::DidDeactivated);
-
-                let key = details
-                    .keys
-                    .iter_mut()
-                    .find(|key| key.public_key == public_key)
-                    .ok_or(Error::<T>::KeyNotFound)?;
-
-                ensure!(!key.revoked, Error::<T>::KeyAlreadyRevoked);
-                key.revoked = true;
-                details.version = details.version.saturating_add(1);
-                Ok(())
-            })?;
-
-            Self::deposit_event(Event::KeyRevoked { did, public_key });
-            Ok(())
+        #[pallet::weight(
+            T::WeightInfo::revoke_key(T::MaxKeys::get())
+                .max(T::WeightInfo::propose_operation(T::MaxPendingProposals::get()))
+        )]
+        pub fn revoke_key(origin: OriginFor<T>, did_id: Vec<u8>, key: Vec<u8>) -> DispatchResult {
+            let caller = Self::ensure_caller(origin)?;
+            let operation = DidOperation::RevokeKey {
+                key: Self::proposed_key(key)?,
+            };
+            Self::submit_operation(&did_id, &caller, operation)
         }
 
         #[pallet::call_index(3)]
-        #[pallet::weight({0})]
+        #[pallet::weight(
+            T::WeightInfo::deactivate_did(T::MaxKeys::get())
+                .max(T::WeightInfo::propose_operation(T::MaxPendingProposals::get()))
+        )]
         pub fn deactivate_did(origin: OriginFor<T>, did_id: Vec<u8>) -> DispatchResult {
-            let _ = frame_system::ensure_signed(origin)?;
-            let did_id = Self::decode_did_id(&did_id)?;
-            let did = Self::did_string_from_did_id(&did_id);
-
-            DidRecords::<T>::try_mutate(did_id, |maybe_details| -> DispatchResult {
-                let details = maybe_details.as_mut().ok_or(Error::<T>::DidNotFound)?;
-                ensure!(!details.deactivated, Error::<T>::DidDeactivated);
-                details.deactivated = true;
-                details.version = details.version.saturating_add(1);
-                Ok(())
-            })?;
-
-            Self::deposit_event(Event::DidDeactivated { did });
-            Ok(())
+            let caller = Self::ensure_caller(origin)?;
+            Self::submit_operation(&did_id, &caller, DidOperation::Deactivate)
         }
 
         #[pallet::call_index(4)]
-        #[pallet::weight({0})]
+        #[pallet::weight(T::WeightInfo::add_service(T::MaxServices::get()))]
         pub fn add_service(
             origin: OriginFor<T>,
             did_id: Vec<u8>,
-            service: ServiceEndpoint,
+            service_id: Vec<u8>,
+            service_type: Vec<u8>,
+            endpoint: EndpointInput,
         ) -> DispatchResult {
-            let _ = frame_system::ensure_signed(origin)?;
-            let did_id = Self::decode_did_id(&did_id)?;
-            let did = Self::did_string_from_did_id(&did_id);
-            let service_id = service.id.clone();
-
-            DidRecords::<T>::try_mutate(did_id, |maybe_details| -> DispatchResult {
-                let details = maybe_details.as_mut().ok_or(Error::<T>::DidNotFound)?;
-                ensure!(!details.deactivated, Error::<T>::DidDeactivated);
-                ensure!(
-                    !details.services.iter().any(|entry| entry.id == service.id),
-                    Error::<T>::ServiceAlreadyExists
-                );
-                details.services.push(service);
-                details.version = details.version.saturating_add(1);
-                Ok(())
-            })?;
+            let caller = Self::ensure_caller(origin)?;
+            let update = DocumentUpdate::AddService {
+                service_id: service_id.clone(),
+                service_type: service_type.clone(),
+                endpoint,
+            };
+            let (did_id, version) =
+                Self::update_did(&did_id, &caller, DidChangeKind::AddService, |details| {
+                    Self::apply_update(details, update)
+                })?;
 
-            Self::deposit_event(Event::ServiceAdded { did, service_id });
+            Self::deposit_did_event(
+                &did_id,
+                Event::ServiceAdded {
+                    did: Self::did_string_from_did_id(&did_id),
+                    did_id,
+                    version,
+                    actor: DidActor::Account(caller.account().clone()),
+                    service_id,
+                    service_type,
+                },
+            );
             Ok(())
         }
 
         #[pallet::call_index(5)]
-        #[pallet::weight({0})]
+        #[pallet::weight(T::WeightInfo::remove_service(T::MaxServices::get()))]
         pub fn remove_service(
             origin: OriginFor<T>,
             did_id: Vec<u8>,
             service_id: Vec<u8>,
         ) -> DispatchResult {
-            let _ = frame_system::ensure_signed(origin)?;
-            let did_id = Self::decode_did_id(&did_id)?;
-            let did = Self::did_string_from_did_id(&did_id);
-
-            DidRecords::<T>::try_mutate(did_id, |maybe_details| -> DispatchResult {
-                let details = maybe_details.as_mut().ok_or(Error::<T>::DidNotFound)?;
-                ensure!(!details.deactivated, Error::<T>::DidDeactivated);
-                let index = details
-                    .services
-                    .iter()
-                    .position(|entry| entry.id == service_id)
-                    .ok_or(Error::<T>::ServiceNotFound)?;
-                details.services.swap_remove(index);
-                details.version = details.version.saturating_add(1);
-                Ok(())
-            })?;
+            let caller = Self::ensure_caller(origin)?;
+            let update = DocumentUpdate::RemoveService {
+                service_id: service_id.clone(),
+            };
+            let (did_id, version) =
+                Self::update_did(&did_id, &caller, DidChangeKind::RemoveService, |details| {
+                    Self::apply_update(details, update)
+                })?;
 
-            Self::deposit_event(Event::ServiceRemoved { did, service_id });
+            Self::deposit_did_event(
+                &did_id,
+                Event::ServiceRemoved {
+                    did: Self::did_string_from_did_id(&did_id),
+                    did_id,
+                    version,
+                    actor: DidActor::Account(caller.account().clone()),
+                    service_id,
+                },
+            );
             Ok(())
         }
 
         #[pallet::call_index(6)]
-        #[pallet::weight({0})]
+        #[pallet::weight(T::WeightInfo::set_metadata(T::MaxMetadataEntries::get()))]
         pub fn set_metadata(
             origin: OriginFor<T>,
             did_id: Vec<u8>,
-            entry: MetadataEntry,
+            key: Vec<u8>,
+            value: Vec<u8>,
         ) -> DispatchResult {
-            let _ = frame_system::ensure_signed(origin)?;
-            let did_id = Self::decode_did_id(&did_id)?;
-            let did = Self::did_string_from_did_id(&did_id);
-            let key = entry.key.clone();
-
-            DidRecords::<T>::try_mutate(did_id, |maybe_details| -> DispatchResult {
-                let details = maybe_details.as_mut().ok_or(Error::<T>::DidNotFound)?;
-                ensure!(!details.deactivated, Error::<T>::DidDeactivated);
-                if let Some(existing) = details
-                    .metadata
-                    .iter_mut()
-                    .find(|item| item.key == entry.key)
-                {
-                    existing.value = entry.value;
-                } else {
-                    details.metadata.push(entry);
-                }
-                details.version = details.version.saturating_add(1);
-                Ok(())
-            })?;
+            let caller = Self::ensure_caller(origin)?;
+            let update = DocumentUpdate::SetMetadata {
+                key: key.clone(),
+                value,
+            };
+            let (did_id, version) =
+                Self::update_did(&did_id, &caller, DidChangeKind::SetMetadata, |details| {
+                    Self::apply_update(details, update)
+                })?;
 
-            Self::deposit_event(Event::MetadataSet { did, key });
+            Self::deposit_did_event(
+                &did_id,
+                Event::MetadataSet {
+                    did: Self::did_string_from_did_id(&did_id),
+                    did_id,
+                    version,
+                    actor: DidActor::Account(caller.account().clone()),
+                    key,
+                },
+            );
             Ok(())
         }
 
         #[pallet::call_index(7)]
-        #[pallet::weight({0})]
+        #[pallet::weight(T::WeightInfo::remove_metadata(T::MaxMetadataEntries::get()))]
         pub fn remove_metadata(
             origin: OriginFor<T>,
             did_id: Vec<u8>,
             key: Vec<u8>,
         ) -> DispatchResult {
-            let _ = frame_system::ensure_signed(origin)?;
-            let did_id = Self::decode_did_id(&did_id)?;
-            let did = Self::did_string_from_did_id(&did_id);
-
-            DidRecords::<T>::try_mutate(did_id, |maybe_details| -> DispatchResult {
-                let details = maybe_details.as_mut().ok_or(Error::<T>::DidNotFound)?;
-                ensure!(!details.deactivated, Error::<T>::DidDeactivated);
-                let index = details
-                    .metadata
-                    .iter()
-                    .position(|item| item.key == key)
-                    .ok_or(Error::<T>::MetadataNotFound)?;
-                details.metadata.swap_remove(index);
-                details.version = details.version.saturating_add(1);
-                Ok(())
-            })?;
+            let caller = Self::ensure_caller(origin)?;
+            let update = DocumentUpdate::RemoveMetadata { key: key.clone() };
+            let (did_id, version) =
+                Self::update_did(&did_id, &caller, DidChangeKind::RemoveMetadata, |details| {
+                    Self::apply_update(details, update)
+                })?;
 
-            Self::deposit_event(Event::MetadataRemoved { did, key });
+            Self::deposit_did_event(
+                &did_id,
+                Event::MetadataRemoved {
+                    did: Self::did_string_from_did_id(&did_id),
+                    did_id,
+                    version,
+                    actor: DidActor::Account(caller.account().clone()),
+                    key,
+                },
+            );
             Ok(())
         }
 
+        /// Revokes `old_key`, given by its id or public key, in favour of a new key under the
+        /// next key id. The new key keeps the controller of the old one.
         #[pallet::call_index(8)]
-        #[pallet::weight({0})]
+        #[pallet::weight(
+            T::WeightInfo::rotate_key(T::MaxKeys::get())
+                .max(T::WeightInfo::propose_operation(T::MaxPendingProposals::get()))
+        )]
         pub fn rotate_key(
             origin: OriginFor<T>,
             did_id: Vec<u8>,
-            old_public_key: Vec<u8>,
+            old_key: Vec<u8>,
+            new_key_type: KeyType,
             new_public_key: Vec<u8>,
             roles: Vec<KeyRole>,
+            valid_from: Option<KeyValidityBound<T>>,
+            valid_until: Option<KeyValidityBound<T>>,
+        ) -> DispatchResult {
+            let caller = Self::ensure_caller(origin)?;
+            Self::ensure_valid_bounds(&valid_from, &valid_until)?;
+            let operation = DidOperation::RotateKey {
+                old_key: Self::proposed_key(old_key)?,
+                new_key_type,
+                new_public_key: Self::bounded_key(new_key_type, &new_public_key)?,
+                roles: Self::checked_roles(new_key_type, roles)?,
+                valid_from,
+                valid_until,
+            };
+            Self::submit_operation(&did_id, &caller, operation)
+        }
+
+        /// Replaces the roles of `key`, given by its id, such as `#key-1`, or by its public key.
+        #[pallet::call_index(9)]
+        #[pallet::weight(
+            T::WeightInfo::update_roles(T::MaxKeys::get())
+                .max(T::WeightInfo::propose_operation(T::MaxPendingProposals::get()))
+        )]
+        pub fn update_roles(
+            origin: OriginFor<T>,
+            did_id: Vec<u8>,
+            key: Vec<u8>,
+            roles: Vec<KeyRole>,
+        ) -> DispatchResult {
+            let caller = Self::ensure_caller(origin)?;
+            let operation = DidOperation::UpdateRoles {
+                key: Self::proposed_key(key)?,
+                roles: Self::bounded_roles(roles),
+            };
+            Self::submit_operation(&did_id, &caller, operation)
+        }
+
+        #[pallet::call_index(10)]
+        #[pallet::weight(
+            T::WeightInfo::add_controller(T::MaxControllers::get())
+                .max(T::WeightInfo::propose_operation(T::MaxPendingProposals::get()))
+        )]
+        pub fn add_controller(
+            origin: OriginFor<T>,
+            did_id: Vec<u8>,
+            controller: T::AccountId,
+        ) -> DispatchResult {
+            let caller = Self::ensure_caller(origin)?;
+            Self::submit_operation(&did_id, &caller, DidOperation::AddController(controller))
+        }
+
+        #[pallet::call_index(11)]
+        #[pallet::weight(
+            T::WeightInfo::remove_controller(T::MaxControllers::get())
+                .max(T::WeightInfo::propose_operation(T::MaxPendingProposals::get()))
+        )]
+        pub fn remove_controller(
+            origin: OriginFor<T>,
+            did_id: Vec<u8>,
+            controller: T::AccountId,
         ) -> DispatchResult {
-            let _ = frame_system::ensure_signed(origin)?;
+            let caller = Self::ensure_caller(origin)?;
+            Self::submit_operation(&did_id, &caller, DidOperation::RemoveController(controller))
+        }
+
+        /// Sets how many controllers must approve sensitive operations on a DID.
+        #[pallet::call_index(12)]
+        #[pallet::weight(
+            T::WeightInfo::set_threshold(T::MaxControllers::get())
+                .max(T::WeightInfo::propose_operation(T::MaxPendingProposals::get()))
+        )]
+        pub fn set_threshold(
+            origin: OriginFor<T>,
+            did_id: Vec<u8>,
+            threshold: u32,
+        ) -> DispatchResult {
+            let caller = Self::ensure_caller(origin)?;
+            Self::submit_operation(&did_id, &caller, DidOperation::SetThreshold(threshold))
+        }
+
+        /// Approves a pending proposal, applying its operation once enough controllers have
+        /// approved it.
+        #[pallet::call_index(13)]
+        #[pallet::weight(
+            T::WeightInfo::approve_proposal(T::MaxControllers::get())
+                .saturating_add(Pallet::<T>::max_operation_weight())
+        )]
+        pub fn approve_proposal(
+            origin: OriginFor<T>,
+            did_id: Vec<u8>,
+            proposal_id: u32,
+        ) -> DispatchResult {
+            let who = frame_system::ensure_signed(origin)?;
             let did_id = Self::decode_did_id(&did_id)?;
+            let details = DidRecords::<T>::get(did_id).ok_or(Error::<T>::DidNotFound)?;
+            ensure!(!details.deactivated, Error::<T>::DidDeactivated);
+            ensure!(
+                details.controllers.contains(&who),
+                Error::<T>::NotController
+            );
+
+            let mut proposal =
+                Proposals::<T>::get(did_id, proposal_id).ok_or(Error::<T>::ProposalNotFound)?;
+            ensure!(
+                frame_system::Pallet::<T>::block_number() < proposal.expires_at,
+                Error::<T>::ProposalExpired
+            );
+            ensure!(
+                !proposal.approvals.contains(&who),
+                Error::<T>::AlreadyApproved
+            );
+            // Approvals of accounts removed from the controllers since no longer count.
+            proposal
+                .approvals
+                .retain(|account| details.controllers.contains(account));
+            proposal
+                .approvals
+                .try_push(who.clone())
+                .map_err(|_| Error::<T>::TooManyControllers)?;
+
             let did = Self::did_string_from_did_id(&did_id);
+            Self::deposit_did_event(
+                &did_id,
+                Event::ProposalApproved {
+                    did: did.clone(),
+                    did_id,
+                    proposal_id,
+                    approver: who.clone(),
+                },
+            );
+            if (proposal.approvals.len() as u32) < details.threshold {
+                Proposals::<T>::insert(did_id, proposal_id, proposal);
+                return Ok(());
+            }
 
-            DidRecords::<T>::try_mutate(did_id, |maybe_details| -> DispatchResult {
-                let details = maybe_details.as_mut().ok_or(Error::<T>::DidNotFound)?;
-                ensure!(!details.deactivated, Error::<T>::DidDeactivated);
+            Proposals::<T>::remove(did_id, proposal_id);
+            proposal.deposit.release::<T::Currency>();
+            Self::execute_operation(&did_id, &DidCaller::Account(who), proposal.operation)?;
+            Self::deposit_did_event(
+                &did_id,
+                Event::ProposalExecuted {
+                    did,
+                    did_id,
+                    proposal_id,
+                },
+            );
+            Ok(())
+        }
+
+        /// Withdraws a pending proposal. Only the proposer can cancel a live proposal, while
+        /// anyone can clear one that has expired.
+        #[pallet::call_index(14)]
+        #[pallet::weight(T::WeightInfo::cancel_proposal())]
+        pub fn cancel_proposal(
+            origin: OriginFor<T>,
+            did_id: Vec<u8>,
+            proposal_id: u32,
+        ) -> DispatchResult {
+            let who = frame_system::ensure_signed(origin)?;
+            let did_id = Self::decode_did_id(&did_id)?;
+            let proposal =
+                Proposals::<T>::take(did_id, proposal_id).ok_or(Error::<T>::ProposalNotFound)?;
+            let expired = frame_system::Pallet::<T>::block_number() >= proposal.expires_at;
+            ensure!(
+                expired || proposal.deposit.owner == who,
+                Error::<T>::NotProposer
+            );
+            proposal.deposit.release::<T::Currency>();
+
+            let did = Self::did_string_from_did_id(&did_id);
+            let event = if expired {
+                Event::ProposalExpired {
+                    did,
+                    did_id,
+                    proposal_id,
+                }
+            } else {
+                Event::ProposalCancelled {
+                    did,
+                    did_id,
+                    proposal_id,
+                }
+            };
+            Self::deposit_did_event(&did_id, event);
+            Ok(())
+        }
+
+        /// Lets `threshold` of the given accounts and DIDs install a new key set on the DID,
+        /// `delay` blocks after they vouched for it. Replaces any previous configuration and
+        /// cancels a recovery in progress.
+        #[pallet::call_index(15)]
+        #[pallet::weight(
+            T::WeightInfo::set_recovery(T::MaxGuardians::get())
+                .max(T::WeightInfo::propose_operation(T::MaxPendingProposals::get()))
+        )]
+        pub fn set_recovery(
+            origin: OriginFor<T>,
+            did_id: Vec<u8>,
+            account_guardians: Vec<T::AccountId>,
+            did_guardians: Vec<Vec<u8>>,
+            threshold: u32,
+            delay: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            let caller = Self::ensure_caller(origin)?;
+            let mut guardians: Vec<Guardian<T>> = account_guardians
+                .into_iter()
+                .map(Guardian::Account)
+                .collect();
+            for did in did_guardians {
+                guardians.push(Guardian::Did(Self::decode_did_id(&did)?));
+            }
+            for (index, guardian) in guardians.iter().enumerate() {
                 ensure!(
-                    !details
-                        .keys
-                        .iter()
-                        .any(|key| key.public_key == new_public_key),
-                    Error::<T>::KeyAlreadyExists
+                    !guardians[..index].contains(guardian),
+                    Error::<T>::DuplicateGuardian
                 );
+            }
+            ensure!(
+                threshold >= 1 && threshold as usize <= guardians.len(),
+                Error::<T>::InvalidThreshold
+            );
+            ensure!(
+                delay >= T::MinRecoveryDelay::get(),
+                Error::<T>::RecoveryDelayTooShort
+            );
+            let config = RecoveryConfig {
+                guardians: guardians
+                    .try_into()
+                    .map_err(|_| Error::<T>::TooManyGuardians)?,
+                threshold,
+                delay,
+            };
+            Self::submit_operation(&did_id, &caller, DidOperation::SetRecovery(Some(config)))
+        }
 
-                let key = details
-                    .keys
-                    .iter_mut()
-                    .find(|key| key.public_key == old_public_key)
-                    .ok_or(Error::<T>::KeyNotFound)?;
-                ensure!(!key.revoked, Error::<T>::KeyAlreadyRevoked);
-                key.revoked = true;
+        /// Removes the recovery configuration of the DID, cancelling a recovery in progress.
+        #[pallet::call_index(16)]
+        #[pallet::weight(
+            T::WeightInfo::set_recovery(T::MaxGuardians::get())
+                .max(T::WeightInfo::propose_operation(T::MaxPendingProposals::get()))
+        )]
+        pub fn remove_recovery(origin: OriginFor<T>, did_id: Vec<u8>) -> DispatchResult {
+            let caller = Self::ensure_caller(origin)?;
+            Self::submit_operation(&did_id, &caller, DidOperation::SetRecovery(None))
+        }
 
-                details.keys.push(DidKey {
-                    public_key: new_public_key.clone(),
-                    roles,
+        /// Starts the recovery of a DID towards `new_keys`, vouching for it as the guardian
+        /// account, or as `guardian_did` when given.
+        #[pallet::call_index(17)]
+        #[pallet::weight(T::WeightInfo::initiate_recovery(new_keys.len() as u32))]
+        pub fn initiate_recovery(
+            origin: OriginFor<T>,
+            did_id: Vec<u8>,
+            guardian_did: Option<Vec<u8>>,
+            new_keys: Vec<(KeyType, Vec<u8>, Vec<KeyRole>)>,
+        ) -> DispatchResult {
+            let who = frame_system::ensure_signed(origin)?;
+            let did_id = Self::decode_did_id(&did_id)?;
+            let details = DidRecords::<T>::get(did_id).ok_or(Error::<T>::DidNotFound)?;
+            ensure!(!details.deactivated, Error::<T>::DidDeactivated);
+            let config = details
+                .recovery
+                .as_ref()
+                .ok_or(Error::<T>::RecoveryNotConfigured)?;
+            ensure!(
+                !Recoveries::<T>::contains_key(did_id),
+                Error::<T>::RecoveryAlreadyActive
+            );
+            let guardian = Self::guardian_of(&who, guardian_did)?;
+            ensure!(
+                config.guardians.contains(&guardian),
+                Error::<T>::NotGuardian
+            );
+
+            ensure!(!new_keys.is_empty(), Error::<T>::NoRecoveryKeys);
+            // Recovery keys have no validity bounds, so they are valid once installed.
+            ensure!(
+                new_keys
+                    .iter()
+                    .any(|(key_type, _, roles)| key_type.is_verifiable()
+                        && roles.contains(&KeyRole::CapabilityInvocation)),
+                Error::<T>::LastCapabilityInvocationKey
+            );
+            let mut keys = Vec::with_capacity(new_keys.len());
+            for (key_type, public_key, roles) in new_keys {
+                ensure!(
+                    !keys
+                        .iter()
+                        .any(|key: &DidKey<T>| key.public_key == public_key)
+                        && !details.keys.iter().any(|key| key.public_key == public_key),
+                    Error::<T>::KeyAlreadyExists
+                );
+                keys.push(DidKey {
+                    id: KeyId::new(),
+                    key_type,
+                    public_key: Self::bounded_key(key_type, &public_key)?,
+                    roles: Self::checked_roles(key_type, roles)?,
+                    controller: None,
                     revoked: false,
+                    valid_from: None,
+                    valid_until: None,
+                    expired: false,
                 });
-                details.version = details.version.saturating_add(1);
-                Ok(())
-            })?;
+            }
+            // The keys of the DID are all revoked on completion, which leaves room for as many
+            // new keys as the bound allows.
+            let new_keys: BoundedVec<_, T::MaxKeys> =
+                keys.try_into().map_err(|_| Error::<T>::TooManyKeys)?;
 
-            Self::deposit_event(Event::KeyRotated {
-                did,
-                old_public_key,
-                new_public_key,
-            });
+            let executable_at = (config.threshold <= 1)
+                .then(|| frame_system::Pallet::<T>::block_number().saturating_add(config.delay));
+            let deposit = Self::storage_deposit(new_keys.encoded_size());
+            let recovery = ActiveRecovery {
+                new_keys,
+                vouchers: BoundedVec::try_from(vec![guardian.clone()])
+                    .map_err(|_| Error::<T>::TooManyGuardians)?,
+                deposit: Deposit::reserve::<T::Currency>(who, deposit)?,
+                executable_at,
+            };
+            Recoveries::<T>::insert(did_id, recovery);
+
+            Self::deposit_did_event(
+                &did_id,
+                Event::RecoveryInitiated {
+                    did: Self::did_string_from_did_id(&did_id),
+                    did_id,
+                    guardian,
+                    executable_at,
+                },
+            );
             Ok(())
         }
 
-        #[pallet::call_index(9)]
-        #[pallet::weight({0})]
-        pub fn update_roles(
+        /// Vouches for the recovery in progress of a DID, as the guardian account or as
+        /// `guardian_did` when given. The recovery delay starts once enough guardians vouched.
+        #[pallet::call_index(18)]
+        #[pallet::weight(T::WeightInfo::vouch_recovery(T::MaxGuardians::get()))]
+        pub fn vouch_recovery(
             origin: OriginFor<T>,
             did_id: Vec<u8>,
-            public_key: Vec<u8>,
-            roles: Vec<KeyRole>,
+            guardian_did: Option<Vec<u8>>,
         ) -> DispatchResult {
-            let _ = frame_system::ensure_signed(origin)?;
+            let who = frame_system::ensure_signed(origin)?;
             let did_id = Self::decode_did_id(&did_id)?;
-            let did = Self::did_string_from_did_id(&did_id);
+            let details = DidRecords::<T>::get(did_id).ok_or(Error::<T>::DidNotFound)?;
+            ensure!(!details.deactivated, Error::<T>::DidDeactivated);
+            let config = details.recovery.ok_or(Error::<T>::RecoveryNotConfigured)?;
+            let guardian = Self::guardian_of(&who, guardian_did)?;
+            ensure!(
+                config.guardians.contains(&guardian),
+                Error::<T>::NotGuardian
+            );
 
-            DidRecords::<T>::try_mutate(did_id, |maybe_details| -> DispatchResult {
-                let details = maybe_details.as_mut().ok_or(Error::<T>::DidNotFound)?;
-                ensure!(!details.deactivated, Error::<T>::DidDeactivated);
-                let key = details
-                    .keys
-                    .iter_mut()
-                    .find(|key| key.public_key == public_key)
-                    .ok_or(Error::<T>::KeyNotFound)?;
-                ensure!(!key.revoked, Error::<T>::KeyAlreadyRevoked);
-                key.roles = roles;
-                details.version = details.version.saturating_add(1);
-                Ok(())
+            let executable_at = Recoveries::<T>::try_mutate(did_id, |maybe_recovery| {
+                let recovery = maybe_recovery
+                    .as_mut()
+                    .ok_or(Error::<T>::RecoveryNotActive)?;
+                ensure!(
+                    !recovery.vouchers.contains(&guardian),
+                    Error::<T>::AlreadyVouched
+                );
+                recovery
+                    .vouchers
+                    .try_push(guardian.clone())
+                    .map_err(|_| Error::<T>::TooManyGuardians)?;
+                if recovery.executable_at.is_none()
+                    && recovery.vouchers.len() as u32 >= config.threshold
+                {
+                    recovery.executable_at = Some(
+                        frame_system::Pallet::<T>::block_number().saturating_add(config.delay),
+                    );
+                }
+                Ok::<_, Error<T>>(recovery.executable_at)
             })?;
 
-            Self::deposit_event(Event::RolesUpdated { did, public_key });
+            Self::deposit_did_event(
+                &did_id,
+                Event::RecoveryVouched {
+                    did: Self::did_string_from_did_id(&did_id),
+                    did_id,
+                    guardian,
+                    executable_at,
+                },
+            );
             Ok(())
         }
-    }
 
-    impl<T: Config> Pallet<T> {
-        fn did_id_from_public_key(public_key: &[u8]) -> [u8; 32] {
-            let genesis = frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero());
-            let mut material = Vec::with_capacity(
-                DID_MATERIAL_PREFIX.len() + genesis.as_ref().len() + public_key.len(),
+        /// Stops the recovery in progress of a DID. Any caller authorized to modify the DID
+        /// can cancel, until the recovery is completed.
+        #[pallet::call_index(19)]
+        #[pallet::weight(T::WeightInfo::cancel_recovery())]
+        pub fn cancel_recovery(origin: OriginFor<T>, did_id: Vec<u8>) -> DispatchResult {
+            let caller = Self::ensure_caller(origin)?;
+            let did_id = Self::decode_did_id(&did_id)?;
+            let details = DidRecords::<T>::get(did_id).ok_or(Error::<T>::DidNotFound)?;
+            ensure!(
+                Self::is_authorized(&did_id, &details, &caller),
+                Error::<T>::NotController
             );
-            material.extend_from_slice(DID_MATERIAL_PREFIX);
-            material.extend_from_slice(genesis.as_ref());
-            material.extend_from_slice(public_key);
-            blake2_256(&material)
-        }
+            let recovery = Recoveries::<T>::take(did_id).ok_or(Error::<T>::RecoveryNotActive)?;
+            recovery.deposit.release::<T::Currency>();
 
-        fn did_string_from_did_id(did_id: &[u8; 32]) -> Vec<u8> {
-            let did_id_b58 = bs58::encode(did_id).into_string();
-            let mut did = Vec::with_capacity(DID_PREFIX.len() + did_id_b58.len());
-            did.extend_from_slice(DID_PREFIX);
-            did.extend_from_slice(did_id_b58.as_bytes());
-            did
+            Self::deposit_did_event(
+                &did_id,
+                Event::RecoveryCancelled {
+                    did: Self::did_string_from_did_id(&did_id),
+                    did_id,
+                    actor: DidActor::Account(caller.account().clone()),
+                },
+            );
+            Ok(())
         }
 
-        fn decode_did_id(input: &[u8]) -> Result<[u8; 32], Error<T>> {
-            let did_id_bytes = if input.starts_with(DID_PREFIX) {
-                &input[DID_PREFIX.len()..]
-            } else {
-                input
-            };
+        /// Installs the key set of a recovery whose delay has passed, revoking every other key
+        /// of the DID. Anyone can complete a recovery.
+        #[pallet::call_index(20)]
+        #[pallet::weight(T::WeightInfo::complete_recovery(T::MaxKeys::get()))]
+        pub fn complete_recovery(origin: OriginFor<T>, did_id: Vec<u8>) -> DispatchResult {
+            let who = frame_system::ensure_signed(origin)?;
+            let did_id = Self::decode_did_id(&did_id)?;
+            let recovery = Recoveries::<T>::take(did_id).ok_or(Error::<T>::RecoveryNotActive)?;
+            let ready = recovery
+                .executable_at
+                .map_or(false, |at| frame_system::Pallet::<T>::block_number() >= at);
+            ensure!(ready, Error::<T>::RecoveryNotReady);
+            recovery.deposit.release::<T::Currency>();
 
-            let decoded = bs58::decode(did_id_bytes)
-                .into_vec()
-                .map_err(|_| Error::<T>::InvalidDidId)?;
-            let did_id: [u8; 32] = decoded.try_into().map_err(|_| Error::<T>::InvalidDidId)?;
-            Ok(did_id)
-        }
+            let new_keys = recovery.new_keys;
+            let vouchers = recovery.vouchers;
+            let mut replaced = Vec::new();
+            let mut installed = Vec::new();
+            let version = Self::apply_change(&did_id, &who, DidChangeKind::Recover, |details| {
+                // The configuration may have changed since the guardians vouched.
+                let config = details
+                    .recovery
+                    .as_ref()
+                    .ok_or(Error::<T>::RecoveryNotConfigured)?;
+                let vouches = vouchers
+                    .iter()
+                    .filter(|guardian| config.guardians.contains(guardian))
+                    .count();
+                ensure!(
+                    vouches as u32 >= config.threshold,
+                    Error::<T>::RecoveryNotReady
+                );
 
-        #[allow(dead_code)]
-        fn create_payload(public_key: &[u8]) -> Vec<u8> {
-            let mut payload = Vec::with_capacity(DID_CREATE_PREFIX.len() + public_key.len());
-            payload.extend_from_slice(DID_CREATE_PREFIX);
-            payload.extend_from_slice(public_key);
-            payload
-        }
+                for key in details.keys.iter_mut().filter(|key| !key.revoked) {
+                    key.revoked = true;
+                    replaced.push((key.id.clone(), key.public_key.to_vec()));
+                }
+                for key in new_keys {
+                    Self::push_key(details, key)?;
+                    installed.extend(details.keys.last().cloned());
+                }
+                Ok(())
+            })?;
+
+            // Each new key is reported as the rotation of a replaced key, in order. Keys left
+            // over on either side are reported as added or revoked.
+            let did = Self::did_string_from_did_id(&did_id);
+            let actor = DidActor::Account(who);
+            let mut replaced = replaced.into_iter();
+            for key in installed {
+                let event = match replaced.next() {
+                    Some((old_key_id, old_public_key)) => Event::KeyRotated {
+                        did: did.clone(),
+                        did_id,
+                        version,
+                        actor: actor.clone(),
+                        old_key_id,
+                        old_public_key,
+                        new_key_id: key.id,
+                        new_public_key: key.public_key.into_inner(),
+                        roles: key.roles.into_inner(),
+                    },
+                    None => Event::KeyAdded {
+                        did: did.clone(),
+                        did_id,
+                        version,
+                        actor: actor.clone(),
+                        key_id: key.id,
+                        key_type: key.key_type,
+                        public_key: key.public_key.into_inner(),
+                        roles: key.roles.into_inner(),
+                        controller: key.controller,
+                    },
+                };
+                Self::deposit_did_event(&did_id, event);
+            }
+            for (key_id, public_key) in replaced {
+                Self::deposit_did_event(
+                    &did_id,
+                    Event::KeyRevoked {
+                        did: did.clone(),
+                        did_id,
+                        version,
+                        actor: actor.clone(),
+                        key_id,
+                        public_key,
+                    },
+                );
+            }
+            Self::deposit_did_event(
+                &did_id,
+                Event::DidRecovered {
+                    did,
+                    did_id,
+                    version,
+                    actor,
+                },
+            );
+            Ok(())
+        }
+
+        /// Removes a deactivated DID from state, releasing its deposits, unlinking its
+        /// accounts and letting [`Config::OnDidDeleted`] release what other pallets hold for
+        /// it, and leaves a tombstone in its place. `history_len` is the number of entries in
+        /// the change log of the DID, its `history_len`, and bounds the weight of the call.
+        #[pallet::call_index(21)]
+        #[pallet::weight(
+            T::WeightInfo::delete_did(*history_len).saturating_add(T::OnDidDeleted::weight())
+        )]
+        pub fn delete_did(
+            origin: OriginFor<T>,
+            did_id: Vec<u8>,
+            history_len: u32,
+        ) -> DispatchResult {
+            let caller = Self::ensure_caller(origin)?;
+            let did_id = Self::decode_did_id(&did_id)?;
+            let details = DidRecords::<T>::get(did_id).ok_or(Error::<T>::DidNotFound)?;
+            ensure!(details.deactivated, Error::<T>::DidNotDeactivated);
+            ensure!(
+                Self::is_authorized(&did_id, &details, &caller),
+                Error::<T>::NotController
+            );
+            ensure!(
+                history_len >= details.history_len,
+                Error::<T>::InvalidHistoryLength
+            );
+
+            let history = DidHistory::<T>::clear_prefix(did_id, history_len, None);
+            ensure!(
+                history.maybe_cursor.is_none(),
+                Error::<T>::InvalidHistoryLength
+            );
+            DidRecords::<T>::remove(did_id);
+            for key in &details.keys {
+                KeyToDid::<T>::remove(&key.public_key, did_id);
+            }
+            if let Some(deposit) = DidDeposits::<T>::take(did_id) {
+                deposit.release::<T::Currency>();
+            }
+            let proposals: Vec<_> = Proposals::<T>::drain_prefix(did_id).collect();
+            for (_, proposal) in proposals {
+                proposal.deposit.release::<T::Currency>();
+            }
+            NextProposalId::<T>::remove(did_id);
+            ExpiringDids::<T>::remove(did_id);
+            if let Some(recovery) = Recoveries::<T>::take(did_id) {
+                recovery.deposit.release::<T::Currency>();
+            }
+            for account in LinkedAccounts::<T>::take(did_id) {
+                if let Some(link) = AccountDids::<T>::take(&account) {
+                    link.deposit.release::<T::Currency>();
+                }
+            }
+            DidTombstones::<T>::insert(
+                did_id,
+                DidTombstone {
+                    version: details.version,
+                    deleted: Self::now(),
+                },
+            );
+            let did = Self::did_string_from_did_id(&did_id);
+            T::OnDidDeleted::on_did_deleted(&did);
+
+            Self::deposit_did_event(
+                &did_id,
+                Event::DidDeleted {
+                    did,
+                    did_id,
+                    version: details.version,
+                    actor: DidActor::Account(caller.account().clone()),
+                },
+            );
+            Ok(())
+        }
+
+        /// Revokes `public_key` without a signed origin, for when the owner of a compromised
+        /// key has no funded account at hand. `signature` is made by `signer_public_key`,
+        /// another `CapabilityInvocation` key of the DID, over
+        /// [`Self::emergency_revoke_payload`] followed by the DID nonce. The revocation can be
+        /// included up to block `valid_until`.
+        #[pallet::call_index(22)]
+        #[pallet::weight(T::WeightInfo::emergency_revoke_key(T::MaxKeys::get()))]
+        pub fn emergency_revoke_key(
+            origin: OriginFor<T>,
+            did_id: Vec<u8>,
+            public_key: Vec<u8>,
+            signer_public_key: Vec<u8>,
+            valid_until: BlockNumberFor<T>,
+            signature: Vec<u8>,
+        ) -> DispatchResult {
+            frame_system::ensure_none(origin)?;
+            let did_id = Self::decode_did_id(&did_id)?;
+            Self::check_emergency_revocation(
+                &did_id,
+                &public_key,
+                &signer_public_key,
+                valid_until,
+                &signature,
+            )?;
+
+            // Nobody pays here: revoking does not change the size of the document, and the
+            // entry added to its change log is charged at the next change made by an account.
+            let (version, key_id, signer_key_id) =
+                DidRecords::<T>::try_mutate(did_id, |maybe_details| -> Result<_, DispatchError> {
+                    let details = maybe_details.as_mut().ok_or(Error::<T>::DidNotFound)?;
+                    let signer_key_id = details
+                        .keys
+                        .iter()
+                        .find(|key| key.public_key == signer_public_key)
+                        .map(|key| key.id.clone())
+                        .ok_or(Error::<T>::KeyNotFound)?;
+                    let key = details
+                        .keys
+                        .iter_mut()
+                        .find(|key| key.public_key == public_key)
+                        .ok_or(Error::<T>::KeyNotFound)?;
+                    key.revoked = true;
+                    let key_id = key.id.clone();
+                    Self::ensure_capability_invocation(details)?;
+                    details.nonce = details.nonce.saturating_add(1);
+                    details.version = details.version.saturating_add(1);
+                    details.updated = Self::now();
+                    Self::record_change(
+                        &did_id,
+                        DidChangeKind::RevokeKey,
+                        details,
+                        Some(key_id.clone()),
+                    );
+                    Ok((details.version, key_id, signer_key_id))
+                })?;
+
+            Self::deposit_did_event(
+                &did_id,
+                Event::KeyRevoked {
+                    did: Self::did_string_from_did_id(&did_id),
+                    did_id,
+                    version,
+                    actor: DidActor::Key(signer_key_id),
+                    key_id,
+                    public_key,
+                },
+            );
+            Ok(())
+        }
+
+        /// Applies `operations` in order as a single change to the DID document. Either all of
+        /// them are applied or none is, the version is bumped once and a single
+        /// `OperationsApplied` event replaces the events of the individual operations.
+        ///
+        /// Operations changing keys are sensitive, so a batch holding any of them is queued
+        /// as a proposal when the DID needs several approvals.
+        #[pallet::call_index(23)]
+        #[pallet::weight(
+            operations
+                .iter()
+                .map(Pallet::<T>::update_weight)
+                .fold(Weight::zero(), |total, weight| total.saturating_add(weight))
+                .max(T::WeightInfo::propose_operation(T::MaxPendingProposals::get()))
+        )]
+        pub fn apply_operations(
+            origin: OriginFor<T>,
+            did_id: Vec<u8>,
+            operations: Vec<DocumentUpdate<T>>,
+        ) -> DispatchResult {
+            let caller = Self::ensure_caller(origin)?;
+            ensure!(!operations.is_empty(), Error::<T>::NoOperations);
+            let decoded_id = Self::decode_did_id(&did_id)?;
+            let details = DidRecords::<T>::get(decoded_id).ok_or(Error::<T>::DidNotFound)?;
+            let changes_keys = operations.iter().any(|operation| {
+                matches!(
+                    operation,
+                    DocumentUpdate::AddKey { .. }
+                        | DocumentUpdate::RevokeKey { .. }
+                        | DocumentUpdate::UpdateRoles { .. }
+                )
+            });
+            if changes_keys && details.threshold > 1 {
+                let updates = operations
+                    .into_iter()
+                    .map(Self::proposed_update)
+                    .collect::<Result<Vec<_>, _>>()?;
+                let updates =
+                    BoundedVec::try_from(updates).map_err(|_| Error::<T>::TooManyOperations)?;
+                return Self::submit_operation(&did_id, &caller, DidOperation::Batch(updates));
+            }
+
+            let count = operations.len().saturated_into::<u32>();
+            let version =
+                Self::apply_updates(&decoded_id, &caller, DidChangeKind::Batch, operations)?;
+
+            Self::deposit_did_event(
+                &decoded_id,
+                Event::OperationsApplied {
+                    did: Self::did_string_from_did_id(&decoded_id),
+                    did_id: decoded_id,
+                    version,
+                    actor: DidActor::Account(caller.account().clone()),
+                    count,
+                },
+            );
+            Ok(())
+        }
+
+        /// Links the calling account to a DID. The account proves its side by signing the
+        /// extrinsic, and a key of the DID proves the other by signing
+        /// [`Self::link_account_payload`] followed by the DID nonce. The account pays the
+        /// deposit of the link.
+        #[pallet::call_index(24)]
+        #[pallet::weight(T::WeightInfo::link_account())]
+        pub fn link_account(
+            origin: OriginFor<T>,
+            did_id: Vec<u8>,
+            did_signature: Vec<u8>,
+        ) -> DispatchResult {
+            let who = frame_system::ensure_signed(origin)?;
+            let decoded_id = Self::decode_did_id(&did_id)?;
+            ensure!(
+                !AccountDids::<T>::contains_key(&who),
+                Error::<T>::AccountAlreadyLinked
+            );
+
+            let payload = Self::link_account_payload(&decoded_id, &who);
+            <Self as DidSignatureVerifier>::verify_did_signature(
+                &did_id,
+                KeyRole::CapabilityInvocation,
+                &payload,
+                &did_signature,
+            )?;
+
+            LinkedAccounts::<T>::try_mutate(decoded_id, |accounts| {
+                accounts
+                    .try_push(who.clone())
+                    .map_err(|_| Error::<T>::TooManyLinkedAccounts)
+            })?;
+            let amount = Self::storage_deposit(AccountLink::<T>::max_encoded_len());
+            let deposit = Deposit::reserve::<T::Currency>(who.clone(), amount)?;
+            AccountDids::<T>::insert(
+                &who,
+                AccountLink {
+                    did_id: decoded_id,
+                    deposit,
+                },
+            );
+
+            Self::deposit_did_event(
+                &decoded_id,
+                Event::AccountLinked {
+                    did: Self::did_string_from_did_id(&decoded_id),
+                    did_id: decoded_id,
+                    account: who,
+                },
+            );
+            Ok(())
+        }
+
+        /// Removes the link of `account` to its DID and releases its deposit. Either the
+        /// account itself or a controller of the DID can unlink it.
+        #[pallet::call_index(25)]
+        #[pallet::weight(T::WeightInfo::unlink_account(T::MaxLinkedAccounts::get()))]
+        pub fn unlink_account(origin: OriginFor<T>, account: T::AccountId) -> DispatchResult {
+            let caller = Self::ensure_caller(origin)?;
+            let link = AccountDids::<T>::get(&account).ok_or(Error::<T>::AccountNotLinked)?;
+            if caller.account() != &account {
+                let details = DidRecords::<T>::get(link.did_id).ok_or(Error::<T>::DidNotFound)?;
+                ensure!(
+                    Self::is_authorized(&link.did_id, &details, &caller),
+                    Error::<T>::NotController
+                );
+            }
+
+            AccountDids::<T>::remove(&account);
+            LinkedAccounts::<T>::mutate(link.did_id, |accounts| {
+                accounts.retain(|linked| linked != &account)
+            });
+            link.deposit.release::<T::Currency>();
+
+            Self::deposit_did_event(
+                &link.did_id,
+                Event::AccountUnlinked {
+                    did: Self::did_string_from_did_id(&link.did_id),
+                    did_id: link.did_id,
+                    account,
+                },
+            );
+            Ok(())
+        }
+
+        /// Replaces the type and/or the endpoint of a service in place, so that services keep
+        /// their order in the document.
+        #[pallet::call_index(26)]
+        #[pallet::weight(T::WeightInfo::update_service(T::MaxServices::get()))]
+        pub fn update_service(
+            origin: OriginFor<T>,
+            did_id: Vec<u8>,
+            service_id: Vec<u8>,
+            service_type: Option<Vec<u8>>,
+            endpoint: Option<EndpointInput>,
+        ) -> DispatchResult {
+            let caller = Self::ensure_caller(origin)?;
+            let update = DocumentUpdate::UpdateService {
+                service_id: service_id.clone(),
+                service_type,
+                endpoint,
+            };
+            let mut service_type = Vec::new();
+            let (did_id, version) =
+                Self::update_did(&did_id, &caller, DidChangeKind::UpdateService, |details| {
+                    Self::apply_update(details, update)?;
+                    service_type = details
+                        .services
+                        .iter()
+                        .find(|service| service.id == service_id)
+                        .map(|service| service.service_type.to_vec())
+                        .unwrap_or_default();
+                    Ok(())
+                })?;
+
+            Self::deposit_did_event(
+                &did_id,
+                Event::ServiceUpdated {
+                    did: Self::did_string_from_did_id(&did_id),
+                    did_id,
+                    version,
+                    actor: DidActor::Account(caller.account().clone()),
+                    service_id,
+                    service_type,
+                },
+            );
+            Ok(())
+        }
+
+        /// Restricts the types of services that can be added to DID documents to
+        /// `service_types`, or lifts the restriction when `None`. Services already in
+        /// documents are left as they are.
+        #[pallet::call_index(27)]
+        #[pallet::weight(T::WeightInfo::set_allowed_service_types(T::MaxServiceTypes::get()))]
+        pub fn set_allowed_service_types(
+            origin: OriginFor<T>,
+            service_types: Option<Vec<Vec<u8>>>,
+        ) -> DispatchResult {
+            T::ServiceTypeOrigin::ensure_origin(origin)?;
+            match &service_types {
+                Some(types) => {
+                    let bounded = types
+                        .iter()
+                        .map(|service_type| Self::bounded_uri(service_type))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let bounded = BoundedVec::try_from(bounded)
+                        .map_err(|_| Error::<T>::TooManyServiceTypes)?;
+                    AllowedServiceTypes::<T>::put(bounded);
+                }
+                None => AllowedServiceTypes::<T>::kill(),
+            }
+
+            Self::deposit_event(Event::AllowedServiceTypesSet { service_types });
+            Ok(())
+        }
+
+        /// Dispatches `call` with the origin of a DID, for pallets that accept calls from
+        /// DIDs through [`EnsureDid`]. A key of the DID holding `role` signs
+        /// [`Pallet::dispatch_payload`] followed by the DID nonce. The nonce is consumed even
+        /// if `call` fails; its result is reported in `DidDispatched`.
+        #[pallet::call_index(28)]
+        #[pallet::weight({
+            let info = call.get_dispatch_info();
+            (T::WeightInfo::dispatch_as_did().saturating_add(info.weight), info.class)
+        })]
+        pub fn dispatch_as_did(
+            origin: OriginFor<T>,
+            did_id: Vec<u8>,
+            role: KeyRole,
+            call: Box<<T as Config>::RuntimeCall>,
+            did_signature: Vec<u8>,
+        ) -> DispatchResult {
+            let who = frame_system::ensure_signed(origin)?;
+            let decoded_id = Self::decode_did_id(&did_id)?;
+            let payload = Self::dispatch_payload(&decoded_id, &role, &who, &call);
+            Self::verify_signature(&decoded_id, &role, &payload, &did_signature)?;
+
+            let did_origin = DidOrigin {
+                did_id: decoded_id,
+                network: DidNetwork::<T>::get(),
+                role: role.clone(),
+                submitter: who.clone(),
+            };
+            let result = call.dispatch(did_origin.into());
+            Self::deposit_did_event(
+                &decoded_id,
+                Event::DidDispatched {
+                    did: Self::did_string_from_did_id(&decoded_id),
+                    did_id: decoded_id,
+                    role,
+                    submitter: who,
+                    result: result.map(|_| ()).map_err(|e| e.error),
+                },
+            );
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Identifier of the DID created with `public_key` and `salt`. Without a salt it is the
+        /// identifier DIDs were always derived with.
+        pub(crate) fn did_id_from_public_key(
+            public_key: &[u8],
+            salt: Option<&[u8; 32]>,
+        ) -> [u8; 32] {
+            let genesis = frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero());
+            let mut material = Vec::with_capacity(
+                DID_MATERIAL_PREFIX.len() + genesis.as_ref().len() + public_key.len() + 32,
+            );
+            material.extend_from_slice(DID_MATERIAL_PREFIX);
+            material.extend_from_slice(genesis.as_ref());
+            material.extend_from_slice(public_key);
+            if let Some(salt) = salt {
+                material.extend_from_slice(salt);
+            }
+            blake2_256(&material)
+        }
+
+        /// The caller of a call that changes a DID: the account that signed it, or a DID
+        /// through [`Pallet::dispatch_as_did`].
+        fn ensure_caller(origin: OriginFor<T>) -> Result<DidCaller<T::AccountId>, DispatchError> {
+            if let Ok(who) = frame_system::ensure_signed(origin.clone()) {
+                return Ok(DidCaller::Account(who));
+            }
+            let origin = <T as Config>::RuntimeOrigin::from(origin);
+            let did_origin: Result<Origin<T>, <T as Config>::RuntimeOrigin> = origin.into();
+            did_origin
+                .map(DidCaller::Did)
+                .map_err(|_| DispatchError::BadOrigin)
+        }
+
+        /// Applies `f` to an active DID after checking that `caller` may modify it, bumping the
+        /// document version on success. Returns the decoded DID id and the new version for
+        /// event reporting.
+        fn update_did(
+            did_id: &[u8],
+            caller: &DidCaller<T::AccountId>,
+            kind: DidChangeKind,
+            f: impl FnOnce(&mut DidDetails<T>) -> DispatchResult,
+        ) -> Result<([u8; 32], u64), DispatchError> {
+            let did_id = Self::decode_did_id(did_id)?;
+            let version = Self::mutate_did(&did_id, caller, kind, f)?;
+            Ok((did_id, version))
+        }
+
+        /// [`Self::update_did`] for a decoded DID id, returning the new version.
+        fn mutate_did(
+            did_id: &[u8; 32],
+            caller: &DidCaller<T::AccountId>,
+            kind: DidChangeKind,
+            f: impl FnOnce(&mut DidDetails<T>) -> DispatchResult,
+        ) -> Result<u64, DispatchError> {
+            Self::apply_change(did_id, caller.account(), kind, |details| {
+                ensure!(
+                    Self::is_authorized(did_id, details, caller),
+                    Error::<T>::NotController
+                );
+                f(details)
+            })
+        }
+
+        /// Applies `f` to an active DID without checking who changes it, bumping the document
+        /// version on success, and returns the new version. A missing deposit is charged to
+        /// `who`.
+        fn apply_change(
+            did_id: &[u8; 32],
+            who: &T::AccountId,
+            kind: DidChangeKind,
+            f: impl FnOnce(&mut DidDetails<T>) -> DispatchResult,
+        ) -> Result<u64, DispatchError> {
+            DidRecords::<T>::try_mutate(did_id, |maybe_details| -> Result<u64, DispatchError> {
+                let details = maybe_details.as_mut().ok_or(Error::<T>::DidNotFound)?;
+                ensure!(!details.deactivated, Error::<T>::DidDeactivated);
+                let known = details.keys.clone();
+                f(details)?;
+                Self::reindex_keys(did_id, &known, &details.keys);
+                // Keys are appended, so the last key that differs is the latest one changed.
+                let changed = details
+                    .keys
+                    .iter()
+                    .rev()
+                    .find(|key| !known.contains(key))
+                    .map(|key| key.id.clone());
+                details.version = details.version.saturating_add(1);
+                details.updated = Self::now();
+                Self::record_change(did_id, kind, details, changed);
+                Self::update_deposit(did_id, who, details)?;
+                Ok(details.version)
+            })
+        }
+
+        /// Topic the events of a DID are deposited with: the hash of its raw id under the
+        /// hasher of the system pallet.
+        pub fn did_topic(did_id: &[u8; 32]) -> T::Hash {
+            T::Hashing::hash(did_id)
+        }
+
+        /// Deposits an event of the DID `did_id`, indexed by [`Self::did_topic`].
+        fn deposit_did_event(did_id: &[u8; 32], event: Event<T>) {
+            frame_system::Pallet::<T>::deposit_event_indexed(
+                &[Self::did_topic(did_id)],
+                <T as Config>::RuntimeEvent::from(event).into(),
+            );
+        }
+
+        fn register_genesis_did(chain_id: &[u8], did: &GenesisDid<T::AccountId>) -> DispatchResult {
+            let (_, public_key, _) = did.keys.first().ok_or(Error::<T>::KeyNotFound)?;
+            let owner = did
+                .controllers
+                .first()
+                .cloned()
+                .ok_or(Error::<T>::ControllerNotFound)?;
+            let did_id = Self::genesis_did_id(chain_id, public_key);
+            ensure!(
+                !DidRecords::<T>::contains_key(did_id),
+                Error::<T>::DidAlreadyExists
+            );
+
+            let mut details = DidDetails {
+                version: 0,
+                nonce: 0,
+                deactivated: false,
+                created: 0,
+                updated: 0,
+                controllers: BoundedVec::try_from(did.controllers.clone())
+                    .map_err(|_| Error::<T>::TooManyControllers)?,
+                threshold: 1,
+                keys: BoundedVec::new(),
+                keys_added: 0,
+                services: BoundedVec::new(),
+                metadata: BoundedVec::new(),
+                recovery: None,
+                history_len: 0,
+            };
+            for (key_type, public_key, roles) in did.keys.iter().cloned() {
+                let key_type =
+                    KeyType::from_name(&key_type).ok_or(Error::<T>::UnsupportedKeyType)?;
+                let roles = roles
+                    .iter()
+                    .map(|role| KeyRole::from_name(role).ok_or(Error::<T>::RoleNotSupported))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let update = DocumentUpdate::AddKey {
+                    key_type,
+                    public_key,
+                    roles,
+                    controller: None,
+                    valid_from: None,
+                    valid_until: None,
+                };
+                Self::apply_update(&mut details, update)?;
+            }
+            for (service_id, service_type, endpoint) in did.services.iter().cloned() {
+                let update = DocumentUpdate::AddService {
+                    service_id,
+                    service_type,
+                    endpoint,
+                };
+                Self::apply_update(&mut details, update)?;
+            }
+
+            Self::ensure_capability_invocation(&details)?;
+
+            Self::index_keys(&did_id, &details.keys);
+            let key_id = details.keys.first().map(|key| key.id.clone());
+            Self::record_change(&did_id, DidChangeKind::Create, &mut details, key_id);
+            // The deposit is reserved like that of any other DID, rather than charged to
+            // whoever changes the DID first.
+            let deposit = Deposit::reserve::<T::Currency>(owner, Self::deposit_for(&details))?;
+            DidDeposits::<T>::insert(did_id, deposit);
+            DidRecords::<T>::insert(did_id, details);
+            Ok(())
+        }
+
+        /// Applies `updates` in order to a DID on behalf of `caller` as a single change, and
+        /// returns the new version.
+        fn apply_updates(
+            did_id: &[u8; 32],
+            caller: &DidCaller<T::AccountId>,
+            kind: DidChangeKind,
+            updates: Vec<DocumentUpdate<T>>,
+        ) -> Result<u64, DispatchError> {
+            let expiring = updates.iter().any(|update| {
+                matches!(
+                    update,
+                    DocumentUpdate::AddKey {
+                        valid_until: Some(_),
+                        ..
+                    }
+                )
+            });
+            let version = Self::mutate_did(did_id, caller, kind, |details| {
+                updates
+                    .into_iter()
+                    .try_for_each(|update| Self::apply_update(details, update))
+            })?;
+            if expiring {
+                ExpiringDids::<T>::insert(did_id, ());
+            }
+            Ok(version)
+        }
+
+        /// Applies a single document update to `details`, leaving the version to the caller.
+        fn apply_update(details: &mut DidDetails<T>, update: DocumentUpdate<T>) -> DispatchResult {
+            match update {
+                DocumentUpdate::AddKey {
+                    key_type,
+                    public_key,
+                    roles,
+                    controller,
+                    valid_from,
+                    valid_until,
+                } => {
+                    let bounded_key = Self::bounded_key(key_type, &public_key)?;
+                    Self::ensure_valid_bounds(&valid_from, &valid_until)?;
+                    let key = DidKey {
+                        id: KeyId::new(),
+                        key_type,
+                        public_key: bounded_key,
+                        roles: Self::checked_roles(key_type, roles)?,
+                        controller: Self::checked_key_controller(controller)?,
+                        revoked: false,
+                        valid_from,
+                        valid_until,
+                        expired: false,
+                    };
+                    Self::push_key(details, key)?;
+                }
+                DocumentUpdate::RevokeKey { key } => {
+                    let index = Self::key_index(details, &key)?;
+                    let key = &mut details.keys[index];
+                    ensure!(!key.revoked, Error::<T>::KeyAlreadyRevoked);
+                    key.revoked = true;
+                    Self::ensure_capability_invocation(details)?;
+                }
+                DocumentUpdate::UpdateRoles { key, roles } => {
+                    let index = Self::key_index(details, &key)?;
+                    let key = &mut details.keys[index];
+                    ensure!(!key.revoked, Error::<T>::KeyAlreadyRevoked);
+                    key.roles = Self::checked_roles(key.key_type, roles)?;
+                    Self::ensure_capability_invocation(details)?;
+                }
+                DocumentUpdate::AddService {
+                    service_id,
+                    service_type,
+                    endpoint,
+                } => {
+                    let service = ServiceEndpoint::<T> {
+                        id: Self::checked_service_id(&service_id)?,
+                        service_type: Self::checked_service_type(&service_type)?,
+                        endpoint: Self::checked_endpoint(endpoint)?,
+                    };
+                    ensure!(
+                        !details.services.iter().any(|entry| entry.id == service.id),
+                        Error::<T>::ServiceAlreadyExists
+                    );
+                    details
+                        .services
+                        .try_push(service)
+                        .map_err(|_| Error::<T>::TooManyServices)?;
+                }
+                DocumentUpdate::UpdateService {
+                    service_id,
+                    service_type,
+                    endpoint,
+                } => {
+                    let service = details
+                        .services
+                        .iter_mut()
+                        .find(|entry| entry.id == service_id)
+                        .ok_or(Error::<T>::ServiceNotFound)?;
+                    if let Some(service_type) = service_type {
+                        service.service_type = Self::checked_service_type(&service_type)?;
+                    }
+                    if let Some(endpoint) = endpoint {
+                        service.endpoint = Self::checked_endpoint(endpoint)?;
+                    }
+                }
+                DocumentUpdate::RemoveService { service_id } => {
+                    let index = details
+                        .services
+                        .iter()
+                        .position(|entry| entry.id == service_id)
+                        .ok_or(Error::<T>::ServiceNotFound)?;
+                    details.services.remove(index);
+                }
+                DocumentUpdate::SetMetadata { key, value } => {
+                    let entry = MetadataEntry::<T> {
+                        key: Self::bounded_metadata(&key)?,
+                        value: Self::bounded_metadata(&value)?,
+                    };
+                    if let Some(existing) = details
+                        .metadata
+                        .iter_mut()
+                        .find(|item| item.key == entry.key)
+                    {
+                        existing.value = entry.value;
+                    } else {
+                        details
+                            .metadata
+                            .try_push(entry)
+                            .map_err(|_| Error::<T>::TooManyMetadataEntries)?;
+                    }
+                }
+                DocumentUpdate::RemoveMetadata { key } => {
+                    let index = details
+                        .metadata
+                        .iter()
+                        .position(|item| item.key == key)
+                        .ok_or(Error::<T>::MetadataNotFound)?;
+                    details.metadata.swap_remove(index);
+                }
+            }
+            Ok(())
+        }
+
+        /// Weight of applying `update` on its own, an upper bound of its share in a batch.
+        pub(crate) fn update_weight(update: &DocumentUpdate<T>) -> Weight {
+            match update {
+                DocumentUpdate::AddKey { .. } => T::WeightInfo::add_key(T::MaxKeys::get()),
+                DocumentUpdate::RevokeKey { .. } => T::WeightInfo::revoke_key(T::MaxKeys::get()),
+                DocumentUpdate::UpdateRoles { .. } => {
+                    T::WeightInfo::update_roles(T::MaxKeys::get())
+                }
+                DocumentUpdate::AddService { .. } => {
+                    T::WeightInfo::add_service(T::MaxServices::get())
+                }
+                DocumentUpdate::UpdateService { .. } => {
+                    T::WeightInfo::update_service(T::MaxServices::get())
+                }
+                DocumentUpdate::RemoveService { .. } => {
+                    T::WeightInfo::remove_service(T::MaxServices::get())
+                }
+                DocumentUpdate::SetMetadata { .. } => {
+                    T::WeightInfo::set_metadata(T::MaxMetadataEntries::get())
+                }
+                DocumentUpdate::RemoveMetadata { .. } => {
+                    T::WeightInfo::remove_metadata(T::MaxMetadataEntries::get())
+                }
+            }
+        }
+
+        /// Checks and bounds `update` to be kept in a proposal.
+        fn proposed_update(update: DocumentUpdate<T>) -> Result<ProposedUpdate<T>, Error<T>> {
+            Ok(match update {
+                DocumentUpdate::AddKey {
+                    key_type,
+                    public_key,
+                    roles,
+                    controller,
+                    valid_from,
+                    valid_until,
+                } => {
+                    Self::ensure_valid_bounds(&valid_from, &valid_until)?;
+                    ProposedUpdate::AddKey {
+                        key_type,
+                        public_key: Self::bounded_key(key_type, &public_key)?,
+                        roles: Self::checked_roles(key_type, roles)?,
+                        controller: Self::checked_key_controller(controller)?,
+                        valid_from,
+                        valid_until,
+                    }
+                }
+                DocumentUpdate::RevokeKey { key } => ProposedUpdate::RevokeKey {
+                    key: Self::proposed_key(key)?,
+                },
+                DocumentUpdate::UpdateRoles { key, roles } => ProposedUpdate::UpdateRoles {
+                    key: Self::proposed_key(key)?,
+                    roles: Self::bounded_roles(roles),
+                },
+                DocumentUpdate::AddService {
+                    service_id,
+                    service_type,
+                    endpoint,
+                } => ProposedUpdate::AddService(ServiceEndpoint {
+                    id: Self::checked_service_id(&service_id)?,
+                    service_type: Self::checked_service_type(&service_type)?,
+                    endpoint: Self::checked_endpoint(endpoint)?,
+                }),
+                DocumentUpdate::UpdateService {
+                    service_id,
+                    service_type,
+                    endpoint,
+                } => ProposedUpdate::UpdateService {
+                    service_id: service_id
+                        .try_into()
+                        .map_err(|_| Error::<T>::ServiceNotFound)?,
+                    service_type: service_type
+                        .map(|service_type| Self::checked_service_type(&service_type))
+                        .transpose()?,
+                    endpoint: endpoint.map(Self::checked_endpoint).transpose()?,
+                },
+                DocumentUpdate::RemoveService { service_id } => ProposedUpdate::RemoveService {
+                    service_id: service_id
+                        .try_into()
+                        .map_err(|_| Error::<T>::ServiceNotFound)?,
+                },
+                DocumentUpdate::SetMetadata { key, value } => {
+                    ProposedUpdate::SetMetadata(MetadataEntry {
+                        key: Self::bounded_metadata(&key)?,
+                        value: Self::bounded_metadata(&value)?,
+                    })
+                }
+                DocumentUpdate::RemoveMetadata { key } => ProposedUpdate::RemoveMetadata {
+                    key: key.try_into().map_err(|_| Error::<T>::MetadataNotFound)?,
+                },
+            })
+        }
+
+        /// Bounds a key given by its id or public key. Neither an id nor a key longer than the
+        /// bound can belong to the DID.
+        fn proposed_key(key: Vec<u8>) -> Result<BoundedVec<u8, T::MaxKeyLength>, Error<T>> {
+            key.try_into().map_err(|_| Error::<T>::KeyNotFound)
+        }
+
+        /// Turns an update kept in a proposal back into the update it was made from.
+        fn document_update(update: ProposedUpdate<T>) -> DocumentUpdate<T> {
+            match update {
+                ProposedUpdate::AddKey {
+                    key_type,
+                    public_key,
+                    roles,
+                    controller,
+                    valid_from,
+                    valid_until,
+                } => DocumentUpdate::AddKey {
+                    key_type,
+                    public_key: public_key.into_inner(),
+                    roles: roles.into_inner(),
+                    controller: controller.map(|did_id| Self::did_string_from_did_id(&did_id)),
+                    valid_from,
+                    valid_until,
+                },
+                ProposedUpdate::RevokeKey { key } => DocumentUpdate::RevokeKey {
+                    key: key.into_inner(),
+                },
+                ProposedUpdate::UpdateRoles { key, roles } => DocumentUpdate::UpdateRoles {
+                    key: key.into_inner(),
+                    roles: roles.into_inner(),
+                },
+                ProposedUpdate::AddService(service) => DocumentUpdate::AddService {
+                    service_id: service.id.into_inner(),
+                    service_type: service.service_type.into_inner(),
+                    endpoint: service.endpoint.into(),
+                },
+                ProposedUpdate::UpdateService {
+                    service_id,
+                    service_type,
+                    endpoint,
+                } => DocumentUpdate::UpdateService {
+                    service_id: service_id.into_inner(),
+                    service_type: service_type.map(|service_type| service_type.into_inner()),
+                    endpoint: endpoint.map(Into::into),
+                },
+                ProposedUpdate::RemoveService { service_id } => DocumentUpdate::RemoveService {
+                    service_id: service_id.into_inner(),
+                },
+                ProposedUpdate::SetMetadata(entry) => DocumentUpdate::SetMetadata {
+                    key: entry.key.into_inner(),
+                    value: entry.value.into_inner(),
+                },
+                ProposedUpdate::RemoveMetadata { key } => DocumentUpdate::RemoveMetadata {
+                    key: key.into_inner(),
+                },
+            }
+        }
+
+        /// Applies a sensitive operation at once when the DID needs a single approval, and
+        /// queues it for the other controllers to approve otherwise. Only controllers can
+        /// propose, so a DID acting through its keys with [`Pallet::dispatch_as_did`] loses
+        /// access to sensitive operations once a threshold is set.
+        fn submit_operation(
+            did_id: &[u8],
+            caller: &DidCaller<T::AccountId>,
+            operation: DidOperation<T>,
+        ) -> DispatchResult {
+            let did_id = Self::decode_did_id(did_id)?;
+            let details = DidRecords::<T>::get(did_id).ok_or(Error::<T>::DidNotFound)?;
+            if details.threshold <= 1 {
+                return Self::execute_operation(&did_id, caller, operation);
+            }
+            ensure!(!details.deactivated, Error::<T>::DidDeactivated);
+            let DidCaller::Account(who) = caller else {
+                return Err(Error::<T>::NotController.into());
+            };
+            ensure!(details.controllers.contains(who), Error::<T>::NotController);
+            Self::propose(&did_id, who, operation)
+        }
+
+        /// Queues `operation` with the approval of its proposer, clearing expired proposals of
+        /// the DID first so that they do not count against the limit.
+        fn propose(
+            did_id: &[u8; 32],
+            who: &T::AccountId,
+            operation: DidOperation<T>,
+        ) -> DispatchResult {
+            let did = Self::did_string_from_did_id(did_id);
+            let now = frame_system::Pallet::<T>::block_number();
+            let mut pending = 0u32;
+            let proposals: Vec<_> = Proposals::<T>::iter_prefix_values(did_id).collect();
+            for proposal in proposals {
+                if now < proposal.expires_at {
+                    pending += 1;
+                    continue;
+                }
+                Proposals::<T>::remove(did_id, proposal.id);
+                proposal.deposit.release::<T::Currency>();
+                Self::deposit_did_event(
+                    did_id,
+                    Event::ProposalExpired {
+                        did: did.clone(),
+                        did_id: *did_id,
+                        proposal_id: proposal.id,
+                    },
+                );
+            }
+            ensure!(
+                pending < T::MaxPendingProposals::get(),
+                Error::<T>::TooManyProposals
+            );
+
+            let proposal_id = NextProposalId::<T>::mutate(did_id, |next| {
+                let id = *next;
+                *next = next.wrapping_add(1);
+                id
+            });
+            let expires_at = now.saturating_add(T::ProposalLifetime::get());
+            let proposal = DidProposal {
+                id: proposal_id,
+                operation: operation.clone(),
+                deposit: Deposit::reserve::<T::Currency>(
+                    who.clone(),
+                    Self::proposal_deposit(&operation),
+                )?,
+                approvals: BoundedVec::try_from(vec![who.clone()])
+                    .map_err(|_| Error::<T>::TooManyControllers)?,
+                expires_at,
+            };
+            Proposals::<T>::insert(did_id, proposal_id, proposal);
+            Self::deposit_did_event(
+                did_id,
+                Event::ProposalCreated {
+                    did,
+                    did_id: *did_id,
+                    proposal_id,
+                    proposer: who.clone(),
+                    operation,
+                    expires_at,
+                },
+            );
+            Ok(())
+        }
+
+        /// Applies a sensitive operation on behalf of `caller` and deposits the matching event.
+        fn execute_operation(
+            did_id: &[u8; 32],
+            caller: &DidCaller<T::AccountId>,
+            operation: DidOperation<T>,
+        ) -> DispatchResult {
+            let did = Self::did_string_from_did_id(did_id);
+            let actor = DidActor::Account(caller.account().clone());
+            let event = match operation {
+                DidOperation::Deactivate => {
+                    let version =
+                        Self::mutate_did(did_id, caller, DidChangeKind::Deactivate, |details| {
+                            details.deactivated = true;
+                            Ok(())
+                        })?;
+                    Event::DidDeactivated {
+                        did,
+                        did_id: *did_id,
+                        version,
+                        actor,
+                    }
+                }
+                DidOperation::RotateKey {
+                    old_key,
+                    new_key_type,
+                    new_public_key,
+                    roles,
+                    valid_from,
+                    valid_until,
+                } => {
+                    let expiring = valid_until.is_some();
+                    let new_roles = roles.to_vec();
+                    let mut old_key_id = KeyId::new();
+                    let mut old_public_key = Vec::new();
+                    let mut new_key_id = KeyId::new();
+                    let version =
+                        Self::mutate_did(did_id, caller, DidChangeKind::RotateKey, |details| {
+                            let index = Self::key_index(details, &old_key)?;
+                            let key = &mut details.keys[index];
+                            ensure!(!key.revoked, Error::<T>::KeyAlreadyRevoked);
+                            key.revoked = true;
+                            old_key_id = key.id.clone();
+                            old_public_key = key.public_key.to_vec();
+                            let controller = key.controller;
+
+                            let key = DidKey {
+                                id: KeyId::new(),
+                                key_type: new_key_type,
+                                public_key: new_public_key.clone(),
+                                roles,
+                                controller,
+                                revoked: false,
+                                valid_from,
+                                valid_until,
+                                expired: false,
+                            };
+                            Self::push_key(details, key)?;
+                            new_key_id = Self::key_id(details.keys_added.saturating_sub(1));
+                            Self::ensure_capability_invocation(details)
+                        })?;
+                    if expiring {
+                        ExpiringDids::<T>::insert(did_id, ());
+                    }
+                    Event::KeyRotated {
+                        did,
+                        did_id: *did_id,
+                        version,
+                        actor,
+                        old_key_id,
+                        old_public_key,
+                        new_key_id,
+                        new_public_key: new_public_key.into_inner(),
+                        roles: new_roles,
+                    }
+                }
+                DidOperation::AddController(controller) => {
+                    let version = Self::apply_change(
+                        did_id,
+                        caller.account(),
+                        DidChangeKind::AddController,
+                        |details| {
+                            ensure!(
+                                Self::can_delegate(did_id, details, caller),
+                                Error::<T>::NotController
+                            );
+                            ensure!(
+                                !details.controllers.contains(&controller),
+                                Error::<T>::ControllerAlreadyExists
+                            );
+                            details
+                                .controllers
+                                .try_push(controller.clone())
+                                .map_err(|_| Error::<T>::TooManyControllers)?;
+                            Ok(())
+                        },
+                    )?;
+                    Event::ControllerAdded {
+                        did,
+                        did_id: *did_id,
+                        version,
+                        actor,
+                        controller,
+                    }
+                }
+                DidOperation::RemoveController(controller) => {
+                    let version = Self::apply_change(
+                        did_id,
+                        caller.account(),
+                        DidChangeKind::RemoveController,
+                        |details| {
+                            ensure!(
+                                Self::can_delegate(did_id, details, caller),
+                                Error::<T>::NotController
+                            );
+                            let index = details
+                                .controllers
+                                .iter()
+                                .position(|item| item == &controller)
+                                .ok_or(Error::<T>::ControllerNotFound)?;
+                            ensure!(details.controllers.len() > 1, Error::<T>::LastController);
+                            details.controllers.remove(index);
+                            ensure!(
+                                details.threshold as usize <= details.controllers.len(),
+                                Error::<T>::InvalidThreshold
+                            );
+                            Ok(())
+                        },
+                    )?;
+                    Event::ControllerRemoved {
+                        did,
+                        did_id: *did_id,
+                        version,
+                        actor,
+                        controller,
+                    }
+                }
+                DidOperation::SetThreshold(threshold) => {
+                    let version =
+                        Self::mutate_did(did_id, caller, DidChangeKind::SetThreshold, |details| {
+                            ensure!(
+                                threshold >= 1 && threshold as usize <= details.controllers.len(),
+                                Error::<T>::InvalidThreshold
+                            );
+                            details.threshold = threshold;
+                            Ok(())
+                        })?;
+                    Event::ThresholdSet {
+                        did,
+                        did_id: *did_id,
+                        version,
+                        actor,
+                        threshold,
+                    }
+                }
+                DidOperation::SetRecovery(config) => {
+                    let configured = config.is_some();
+                    let version =
+                        Self::mutate_did(did_id, caller, DidChangeKind::SetRecovery, |details| {
+                            details.recovery = config;
+                            Ok(())
+                        })?;
+                    if let Some(recovery) = Recoveries::<T>::take(did_id) {
+                        recovery.deposit.release::<T::Currency>();
+                        Self::deposit_did_event(
+                            did_id,
+                            Event::RecoveryCancelled {
+                                did: did.clone(),
+                                did_id: *did_id,
+                                actor: actor.clone(),
+                            },
+                        );
+                    }
+                    if configured {
+                        Event::RecoveryConfigured {
+                            did,
+                            did_id: *did_id,
+                            version,
+                            actor,
+                        }
+                    } else {
+                        Event::RecoveryRemoved {
+                            did,
+                            did_id: *did_id,
+                            version,
+                            actor,
+                        }
+                    }
+                }
+                DidOperation::AddKey {
+                    key_type,
+                    public_key,
+                    roles,
+                    controller,
+                    valid_from,
+                    valid_until,
+                } => {
+                    let expiring = valid_until.is_some();
+                    let update = Self::document_update(ProposedUpdate::AddKey {
+                        key_type,
+                        public_key,
+                        roles,
+                        controller,
+                        valid_from,
+                        valid_until,
+                    });
+                    let mut added = None;
+                    let version =
+                        Self::mutate_did(did_id, caller, DidChangeKind::AddKey, |details| {
+                            Self::apply_update(details, update)?;
+                            added = details.keys.last().cloned();
+                            Ok(())
+                        })?;
+                    if expiring {
+                        ExpiringDids::<T>::insert(did_id, ());
+                    }
+                    let key = added.ok_or(Error::<T>::KeyNotFound)?;
+                    Event::KeyAdded {
+                        did,
+                        did_id: *did_id,
+                        version,
+                        actor,
+                        key_id: key.id,
+                        key_type: key.key_type,
+                        public_key: key.public_key.into_inner(),
+                        roles: key.roles.into_inner(),
+                        controller: key.controller,
+                    }
+                }
+                DidOperation::RevokeKey { key } => {
+                    let mut revoked = None;
+                    let version =
+                        Self::mutate_did(did_id, caller, DidChangeKind::RevokeKey, |details| {
+                            let index = Self::key_index(details, &key)?;
+                            let update = DocumentUpdate::RevokeKey {
+                                key: key.into_inner(),
+                            };
+                            Self::apply_update(details, update)?;
+                            revoked = Some(details.keys[index].clone());
+                            Ok(())
+                        })?;
+                    let key = revoked.ok_or(Error::<T>::KeyNotFound)?;
+                    Event::KeyRevoked {
+                        did,
+                        did_id: *did_id,
+                        version,
+                        actor,
+                        key_id: key.id,
+                        public_key: key.public_key.into_inner(),
+                    }
+                }
+                DidOperation::UpdateRoles { key, roles } => {
+                    let mut updated = None;
+                    let version =
+                        Self::mutate_did(did_id, caller, DidChangeKind::UpdateRoles, |details| {
+                            let index = Self::key_index(details, &key)?;
+                            let update = DocumentUpdate::UpdateRoles {
+                                key: key.into_inner(),
+                                roles: roles.into_inner(),
+                            };
+                            Self::apply_update(details, update)?;
+                            updated = Some(details.keys[index].clone());
+                            Ok(())
+                        })?;
+                    let key = updated.ok_or(Error::<T>::KeyNotFound)?;
+                    Event::RolesUpdated {
+                        did,
+                        did_id: *did_id,
+                        version,
+                        actor,
+                        key_id: key.id,
+                        public_key: key.public_key.into_inner(),
+                        roles: key.roles.into_inner(),
+                    }
+                }
+                DidOperation::Batch(updates) => {
+                    let count = updates.len().saturated_into::<u32>();
+                    let updates = updates.into_iter().map(Self::document_update).collect();
+                    let version =
+                        Self::apply_updates(did_id, caller, DidChangeKind::Batch, updates)?;
+                    Event::OperationsApplied {
+                        did,
+                        did_id: *did_id,
+                        version,
+                        actor,
+                        count,
+                    }
+                }
+            };
+            Self::deposit_did_event(did_id, event);
+            Ok(())
+        }
+
+        /// Guardian `who` acts as: its own account, or `guardian_did` if it is one of the
+        /// controllers of that DID.
+        fn guardian_of(
+            who: &T::AccountId,
+            guardian_did: Option<Vec<u8>>,
+        ) -> Result<Guardian<T>, DispatchError> {
+            let Some(guardian_did) = guardian_did else {
+                return Ok(Guardian::Account(who.clone()));
+            };
+            let guardian_id = Self::decode_did_id(&guardian_did)?;
+            let details = DidRecords::<T>::get(guardian_id).ok_or(Error::<T>::DidNotFound)?;
+            ensure!(!details.deactivated, Error::<T>::DidDeactivated);
+            ensure!(details.controllers.contains(who), Error::<T>::NotController);
+            Ok(Guardian::Did(guardian_id))
+        }
+
+        /// Weight of the heaviest operation a proposal can apply.
+        pub(crate) fn max_operation_weight() -> Weight {
+            T::WeightInfo::deactivate_did(T::MaxKeys::get())
+                .max(T::WeightInfo::rotate_key(T::MaxKeys::get()))
+                .max(T::WeightInfo::add_controller(T::MaxControllers::get()))
+                .max(T::WeightInfo::remove_controller(T::MaxControllers::get()))
+                .max(T::WeightInfo::set_threshold(T::MaxControllers::get()))
+                .max(T::WeightInfo::set_recovery(T::MaxGuardians::get()))
+                .max(Self::max_update_weight().saturating_mul(MaxProposedUpdates::get().into()))
+        }
+
+        /// Weight of the heaviest update a batch can hold.
+        fn max_update_weight() -> Weight {
+            T::WeightInfo::add_key(T::MaxKeys::get())
+                .max(T::WeightInfo::revoke_key(T::MaxKeys::get()))
+                .max(T::WeightInfo::update_roles(T::MaxKeys::get()))
+                .max(T::WeightInfo::add_service(T::MaxServices::get()))
+                .max(T::WeightInfo::update_service(T::MaxServices::get()))
+                .max(T::WeightInfo::remove_service(T::MaxServices::get()))
+                .max(T::WeightInfo::set_metadata(T::MaxMetadataEntries::get()))
+                .max(T::WeightInfo::remove_metadata(T::MaxMetadataEntries::get()))
+        }
+
+        /// Deposit reserved for a pending proposal of `operation`. Batches can be far larger
+        /// than other operations, so the proposal is sized with the operation it holds.
+        fn proposal_deposit(operation: &DidOperation<T>) -> BalanceOf<T> {
+            let unused =
+                DidOperation::<T>::max_encoded_len().saturating_sub(operation.encoded_size());
+            Self::storage_deposit(DidProposal::<T>::max_encoded_len().saturating_sub(unused))
+        }
+
+        /// Deposit required to keep an item of `bytes` bytes in state.
+        fn storage_deposit(bytes: usize) -> BalanceOf<T> {
+            let bytes = bytes.saturated_into::<u32>();
+            T::DepositPerItem::get()
+                .saturating_add(T::DepositPerByte::get().saturating_mul(bytes.into()))
+        }
+
+        /// Deposit required to keep `details` and the entries of its change log in state.
+        fn deposit_for(details: &DidDetails<T>) -> BalanceOf<T> {
+            let history = Self::storage_deposit(DidChange::<T>::max_encoded_len())
+                .saturating_mul(details.history_len.into());
+            Self::storage_deposit(details.encoded_size()).saturating_add(history)
+        }
+
+        /// Resizes the deposit of a DID to match `details`. Documents migrated without a
+        /// deposit are charged to `who`, the account changing them.
+        fn update_deposit(
+            did_id: &[u8; 32],
+            who: &T::AccountId,
+            details: &DidDetails<T>,
+        ) -> DispatchResult {
+            let required = Self::deposit_for(details);
+            DidDeposits::<T>::try_mutate(did_id, |maybe_deposit| match maybe_deposit {
+                Some(deposit) => deposit.adjust::<T::Currency>(required),
+                None => {
+                    *maybe_deposit = Some(Deposit::reserve::<T::Currency>(who.clone(), required)?);
+                    Ok(())
+                }
+            })
+        }
+
+        /// Adds `keys` of a DID to the public key index.
+        pub(crate) fn index_keys(did_id: &[u8; 32], keys: &[DidKey<T>]) {
+            for key in keys {
+                KeyToDid::<T>::insert(&key.public_key, did_id, ());
+            }
+        }
+
+        /// Adds the keys of a DID that are not among the `known` ones to the public key index.
+        /// Keys never leave a DID, so none are dropped from it.
+        fn reindex_keys(did_id: &[u8; 32], known: &[DidKey<T>], keys: &[DidKey<T>]) {
+            for key in keys {
+                if !known.iter().any(|old| old.public_key == key.public_key) {
+                    KeyToDid::<T>::insert(&key.public_key, did_id, ());
+                }
+            }
+        }
+
+        /// Adds the change that produced the current version of `details` to its change log.
+        pub(crate) fn record_change(
+            did_id: &[u8; 32],
+            kind: DidChangeKind,
+            details: &mut DidDetails<T>,
+            key: Option<KeyId>,
+        ) {
+            let change = DidChange {
+                version: details.version,
+                block_number: frame_system::Pallet::<T>::block_number(),
+                timestamp: details.updated,
+                kind,
+                key,
+            };
+            DidHistory::<T>::insert(did_id, details.version, change);
+            details.history_len = details.history_len.saturating_add(1);
+        }
+
+        /// A caller is authorized to modify a DID if it is one of its controllers, or the DID
+        /// itself through [`Pallet::dispatch_as_did`] with a `CapabilityInvocation` key. An
+        /// account is never authorized by its bytes matching a key of the DID.
+        pub fn is_authorized(
+            did_id: &[u8; 32],
+            details: &DidDetails<T>,
+            caller: &DidCaller<T::AccountId>,
+        ) -> bool {
+            match caller {
+                DidCaller::Account(who) => details.controllers.contains(who),
+                DidCaller::Did(origin) => {
+                    Self::acts_as(origin, did_id, KeyRole::CapabilityInvocation)
+                }
+            }
+        }
+
+        /// Controllers act as delegates of a DID. Besides the callers authorized to modify the
+        /// DID, the DID itself can add and remove them through [`Pallet::dispatch_as_did`]
+        /// with a `CapabilityDelegation` key.
+        pub fn can_delegate(
+            did_id: &[u8; 32],
+            details: &DidDetails<T>,
+            caller: &DidCaller<T::AccountId>,
+        ) -> bool {
+            Self::is_authorized(did_id, details, caller)
+                || matches!(
+                    caller,
+                    DidCaller::Did(origin)
+                        if Self::acts_as(origin, did_id, KeyRole::CapabilityDelegation)
+                )
+        }
+
+        /// Whether `origin` is the DID `did_id` authenticated with a key holding `role`.
+        fn acts_as(origin: &Origin<T>, did_id: &[u8; 32], role: KeyRole) -> bool {
+            origin.did_id == *did_id && origin.role == role
+        }
+
+        /// A DID must keep a `CapabilityInvocation` key that its owner can still change it
+        /// with: one whose signatures can be checked on chain, that is valid now and that
+        /// never expires.
+        fn ensure_capability_invocation(details: &DidDetails<T>) -> DispatchResult {
+            ensure!(
+                details.keys.iter().any(|key| key.key_type.is_verifiable()
+                    && key.valid_until.is_none()
+                    && !key.expired
+                    && Self::is_key_valid(key)
+                    && key.roles.contains(&KeyRole::CapabilityInvocation)),
+                Error::<T>::LastCapabilityInvocationKey
+            );
+            Ok(())
+        }
+
+        /// Bounds the roles of a key of `key_type`, which can only hold signing roles if it
+        /// signs.
+        fn checked_roles(
+            key_type: KeyType,
+            roles: Vec<KeyRole>,
+        ) -> Result<BoundedVec<KeyRole, MaxKeyRoles>, Error<T>> {
+            ensure!(
+                key_type.is_signing() || roles.iter().all(|role| *role == KeyRole::KeyAgreement),
+                Error::<T>::RoleNotSupported
+            );
+            Ok(Self::bounded_roles(roles))
+        }
+
+        /// Whether `key` can be used at `block`, whose timestamp is `moment`. Only the
+        /// validity bounds are checked, so the status does not depend on when `on_idle`
+        /// flagged the key.
+        pub fn key_status_at(key: &DidKey<T>, block: BlockNumberFor<T>, moment: u64) -> KeyStatus {
+            if key.revoked {
+                KeyStatus::Revoked
+            } else if key
+                .valid_until
+                .as_ref()
+                .map_or(false, |bound| bound.reached(block, moment))
+            {
+                KeyStatus::Expired
+            } else if key
+                .valid_from
+                .as_ref()
+                .map_or(false, |bound| !bound.reached(block, moment))
+            {
+                KeyStatus::NotYetValid
+            } else {
+                KeyStatus::Valid
+            }
+        }
+
+        /// Whether `key` can be used in the current block.
+        fn is_key_valid(key: &DidKey<T>) -> bool {
+            let (block, moment) = Self::instant();
+            Self::key_status_at(key, block, moment) == KeyStatus::Valid
+        }
+
+        /// Checks that a key with these bounds has not expired already and would become valid
+        /// before it expires. Bounds of different kinds cannot be compared and are accepted.
+        fn ensure_valid_bounds(
+            valid_from: &Option<KeyValidityBound<T>>,
+            valid_until: &Option<KeyValidityBound<T>>,
+        ) -> DispatchResult {
+            let Some(valid_until) = valid_until else {
+                return Ok(());
+            };
+            let (block, moment) = Self::instant();
+            ensure!(
+                !valid_until.reached(block, moment),
+                Error::<T>::InvalidKeyValidity
+            );
+            let ordered = match (valid_from, valid_until) {
+                (Some(KeyValidityBound::Block(from)), KeyValidityBound::Block(until)) => {
+                    from < until
+                }
+                (Some(KeyValidityBound::Moment(from)), KeyValidityBound::Moment(until)) => {
+                    from < until
+                }
+                _ => true,
+            };
+            ensure!(ordered, Error::<T>::InvalidKeyValidity);
+            Ok(())
+        }
+
+        /// Flags expired keys of as many DIDs in [`ExpiringDids`] as `limit` allows, carrying
+        /// on from the DID the previous call stopped at. Returns the weight used.
+        pub(crate) fn expire_keys(limit: Weight) -> Weight {
+            let per_did = T::WeightInfo::expire_keys(T::MaxKeys::get());
+            let mut used = T::DbWeight::get().reads_writes(1, 1);
+            if used.any_gt(limit) {
+                return Weight::zero();
+            }
+
+            let cursor = ExpiryCursor::<T>::get();
+            let dids = match cursor {
+                Some(cursor) => {
+                    ExpiringDids::<T>::iter_keys_from(ExpiringDids::<T>::hashed_key_for(cursor))
+                }
+                None => ExpiringDids::<T>::iter_keys(),
+            };
+            let mut batch = Vec::new();
+            let mut exhausted = true;
+            for did_id in dids {
+                if used.saturating_add(per_did).any_gt(limit) {
+                    exhausted = false;
+                    break;
+                }
+                used.saturating_accrue(per_did);
+                batch.push(did_id);
+            }
+
+            if !exhausted {
+                if let Some(last) = batch.last() {
+                    ExpiryCursor::<T>::put(last);
+                }
+            } else if cursor.is_some() {
+                ExpiryCursor::<T>::kill();
+            }
+            for did_id in batch {
+                Self::expire_keys_of(&did_id);
+            }
+            used
+        }
+
+        /// Flags the keys of a DID whose `valid_until` has passed as expired, and drops the
+        /// DID from [`ExpiringDids`] once it has no keys left to expire. The deposit is left as
+        /// it is: flagging does not change the size of the document, and its change log entry
+        /// is charged at the next change made by an account.
+        pub(crate) fn expire_keys_of(did_id: &[u8; 32]) {
+            let (block, moment) = Self::instant();
+            let mut expired = Vec::new();
+            let mut pending = false;
+            let mut version = 0;
+            DidRecords::<T>::mutate(did_id, |maybe_details| {
+                let Some(details) = maybe_details else {
+                    return;
+                };
+                if details.deactivated {
+                    return;
+                }
+                for key in details
+                    .keys
+                    .iter_mut()
+                    .filter(|key| !key.revoked && !key.expired)
+                {
+                    let Some(valid_until) = &key.valid_until else {
+                        continue;
+                    };
+                    if valid_until.reached(block, moment) {
+                        key.expired = true;
+                        expired.push((key.id.clone(), key.public_key.to_vec()));
+                    } else {
+                        pending = true;
+                    }
+                }
+                if !expired.is_empty() {
+                    details.version = details.version.saturating_add(1);
+                    details.updated = moment;
+                    let key_id = expired.last().map(|(key_id, _)| key_id.clone());
+                    Self::record_change(did_id, DidChangeKind::ExpireKeys, details, key_id);
+                }
+                version = details.version;
+            });
+
+            if !pending {
+                ExpiringDids::<T>::remove(did_id);
+            }
+            let did = Self::did_string_from_did_id(did_id);
+            for (key_id, public_key) in expired {
+                Self::deposit_did_event(
+                    did_id,
+                    Event::KeyExpired {
+                        did: did.clone(),
+                        did_id: *did_id,
+                        version,
+                        actor: DidActor::Runtime,
+                        key_id,
+                        public_key,
+                    },
+                );
+            }
+        }
+
+        /// Id of the key added to a DID after `added` others.
+        pub(crate) fn key_id(added: u32) -> KeyId {
+            let mut digits = Vec::new();
+            let mut number = added.saturating_add(1);
+            loop {
+                digits.push(b'0' + (number % 10) as u8);
+                number /= 10;
+                if number == 0 {
+                    break;
+                }
+            }
+            let mut id = KEY_ID_PREFIX.to_vec();
+            id.extend(digits.into_iter().rev());
+            BoundedVec::truncate_from(id)
+        }
+
+        /// Position of the key `key` refers to, by its id or by its public key. Ids are
+        /// shorter than any supported public key, so the two cannot be confused.
+        fn key_index(details: &DidDetails<T>, key: &[u8]) -> Result<usize, Error<T>> {
+            details
+                .keys
+                .iter()
+                .position(|existing| existing.id == key || existing.public_key == key)
+                .ok_or(Error::<T>::KeyNotFound)
+        }
+
+        /// Appends `key` to the keys of the DID under the next key id. Revoked and expired keys
+        /// are never removed to make room, so a DID whose keys are at their bound cannot take
+        /// more.
+        fn push_key(details: &mut DidDetails<T>, mut key: DidKey<T>) -> DispatchResult {
+            ensure!(
+                !details
+                    .keys
+                    .iter()
+                    .any(|existing| existing.public_key == key.public_key),
+                Error::<T>::KeyAlreadyExists
+            );
+            key.id = Self::key_id(details.keys_added);
+            details.keys_added = details.keys_added.saturating_add(1);
+            details
+                .keys
+                .try_push(key)
+                .map_err(|_| Error::<T>::TooManyKeys)?;
+            Ok(())
+        }
+
+        /// Decodes the DID controlling a key, which must be registered.
+        fn checked_key_controller(
+            controller: Option<Vec<u8>>,
+        ) -> Result<Option<[u8; 32]>, Error<T>> {
+            let Some(controller) = controller else {
+                return Ok(None);
+            };
+            let controller = Self::decode_did_id(&controller)?;
+            ensure!(
+                DidRecords::<T>::contains_key(controller),
+                Error::<T>::KeyControllerNotFound
+            );
+            Ok(Some(controller))
+        }
+
+        /// Checks that `network` is a valid DID network segment: lowercase letters and digits,
+        /// other than the segments reserved for names, schemas and status lists.
+        pub(crate) fn checked_network(network: &[u8]) -> Result<NetworkName, Error<T>> {
+            ensure!(
+                !network.is_empty()
+                    && network
+                        .iter()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+                    && !RESERVED_NETWORKS.contains(&network),
+                Error::<T>::WrongDidNetwork
+            );
+            network
+                .to_vec()
+                .try_into()
+                .map_err(|_| Error::<T>::WrongDidNetwork)
+        }
+
+        fn bounded_key(
+            key_type: KeyType,
+            public_key: &[u8],
+        ) -> Result<BoundedVec<u8, T::MaxKeyLength>, Error<T>> {
+            ensure!(key_type != KeyType::Legacy, Error::<T>::UnsupportedKeyType);
+            ensure!(
+                public_key.len() == key_type.public_key_len(),
+                Error::<T>::InvalidKeyLength
+            );
+            public_key
+                .to_vec()
+                .try_into()
+                .map_err(|_| Error::<T>::KeyTooLong)
+        }
+
+        /// Deduplicates `roles`. There are only as many distinct roles as the bound allows, so
+        /// nothing is ever dropped.
+        pub(crate) fn bounded_roles(roles: Vec<KeyRole>) -> BoundedVec<KeyRole, MaxKeyRoles> {
+            let mut unique = Vec::with_capacity(roles.len());
+            for role in roles {
+                if !unique.contains(&role) {
+                    unique.push(role);
+                }
+            }
+            BoundedVec::truncate_from(unique)
+        }
+
+        fn bounded_uri(input: &[u8]) -> Result<BoundedVec<u8, T::MaxUriLength>, Error<T>> {
+            input
+                .to_vec()
+                .try_into()
+                .map_err(|_| Error::<T>::ServiceTooLong)
+        }
+
+        fn checked_service_id(input: &[u8]) -> Result<ServiceUri<T>, Error<T>> {
+            match input.split_first() {
+                Some((b'#', fragment))
+                    if !fragment.is_empty() && is_uri_text(fragment, b":@/?") =>
+                {
+                    Self::bounded_uri(input)
+                }
+                _ => Err(Error::<T>::InvalidServiceId),
+            }
+        }
+
+        /// Bounds a service type and checks it against the allowlist, if one is set.
+        fn checked_service_type(input: &[u8]) -> Result<ServiceUri<T>, Error<T>> {
+            let service_type = Self::bounded_uri(input)?;
+            if let Some(allowed) = AllowedServiceTypes::<T>::get() {
+                ensure!(
+                    allowed.contains(&service_type),
+                    Error::<T>::ServiceTypeNotAllowed
+                );
+            }
+            Ok(service_type)
+        }
+
+        fn checked_endpoint(input: EndpointInput) -> Result<Endpoint<T>, Error<T>> {
+            let checked_uri = |uri: Vec<u8>| -> Result<ServiceUri<T>, Error<T>> {
+                ensure!(is_absolute_uri(&uri), Error::<T>::InvalidServiceEndpoint);
+                Self::bounded_uri(&uri)
+            };
+            let endpoint = match input {
+                EndpointInput::Uri(uri) => Endpoint::Uri(checked_uri(uri)?),
+                EndpointInput::Set(uris) => {
+                    ensure!(!uris.is_empty(), Error::<T>::InvalidServiceEndpoint);
+                    let uris = uris
+                        .into_iter()
+                        .map(checked_uri)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Endpoint::Set(
+                        uris.try_into()
+                            .map_err(|_| Error::<T>::TooManyServiceEndpoints)?,
+                    )
+                }
+                EndpointInput::Map(entries) => {
+                    ensure!(!entries.is_empty(), Error::<T>::InvalidServiceEndpoint);
+                    let mut checked: Vec<(ServiceUri<T>, ServiceUri<T>)> =
+                        Vec::with_capacity(entries.len());
+                    for (key, uri) in entries {
+                        ensure!(
+                            !key.is_empty()
+                                && key
+                                    .iter()
+                                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, b'-' | b'_'))
+                                && !checked.iter().any(|(existing, _)| existing[..] == key[..]),
+                            Error::<T>::InvalidServiceEndpoint
+                        );
+                        checked.push((Self::bounded_uri(&key)?, checked_uri(uri)?));
+                    }
+                    Endpoint::Map(
+                        checked
+                            .try_into()
+                            .map_err(|_| Error::<T>::TooManyServiceEndpoints)?,
+                    )
+                }
+            };
+            Ok(endpoint)
+        }
 
-        pub fn get_did(did_id: Vec<u8>) -> Result<DidDetails, Error<T>> {
+        fn bounded_metadata(
+            input: &[u8],
+        ) -> Result<BoundedVec<u8, T::MaxMetadataLength>, Error<T>> {
+            input
+                .to_vec()
+                .try_into()
+                .map_err(|_| Error::<T>::MetadataTooLong)
+        }
+
+        fn now() -> u64 {
+            T::UnixTime::now().as_millis().saturated_into::<u64>()
+        }
+
+        /// Current block number and Unix time in milliseconds.
+        fn instant() -> (BlockNumberFor<T>, u64) {
+            (frame_system::Pallet::<T>::block_number(), Self::now())
+        }
+
+        /// Identifier of a DID registered in the genesis block of the chain `chain_id`. The
+        /// genesis hash is not known while the genesis state is built, so the chain is told
+        /// apart by `chain_id` instead, which is length-prefixed in the material.
+        pub fn genesis_did_id(chain_id: &[u8], public_key: &[u8]) -> [u8; 32] {
+            let mut material = DID_MATERIAL_PREFIX.to_vec();
+            chain_id.encode_to(&mut material);
+            material.extend_from_slice(public_key);
+            blake2_256(&material)
+        }
+
+        pub(crate) fn did_string_from_did_id(did_id: &[u8; 32]) -> Vec<u8> {
+            let network = DidNetwork::<T>::get();
+            did_string(did_id, network.as_ref().map(|network| network.as_slice()))
+        }
+
+        /// Decodes a DID, or a bare identifier. A DID must carry the network segment of the
+        /// chain when it has one, and none otherwise.
+        pub(crate) fn decode_did_id(input: &[u8]) -> Result<[u8; 32], Error<T>> {
+            let did_id_bytes = match input.strip_prefix(DID_PREFIX) {
+                Some(rest) => {
+                    let segment = rest.iter().position(|c| *c == b':');
+                    match (DidNetwork::<T>::get(), segment) {
+                        (None, None) => rest,
+                        (Some(network), Some(end)) if rest[..end] == network[..] => {
+                            &rest[end + 1..]
+                        }
+                        _ => return Err(Error::<T>::WrongDidNetwork),
+                    }
+                }
+                None => input,
+            };
+
+            let decoded = bs58::decode(did_id_bytes)
+                .into_vec()
+                .map_err(|_| Error::<T>::InvalidDidId)?;
+            let did_id: [u8; 32] = decoded.try_into().map_err(|_| Error::<T>::InvalidDidId)?;
+            Ok(did_id)
+        }
+
+        /// Payload the creation key signs in `create_did`, before the nonce is appended. The
+        /// creating account is included so a signature observed in the pool cannot be replayed
+        /// by another account to take control of the DID. The salt, when given, is appended so
+        /// that the signature only registers the DID the key holder chose.
+        pub fn create_payload(
+            public_key: &[u8],
+            salt: Option<&[u8; 32]>,
+            creator: &T::AccountId,
+        ) -> Vec<u8> {
+            let genesis = frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero());
+            let creator = creator.encode();
+            let mut payload = Vec::with_capacity(
+                DID_CREATE_PREFIX.len()
+                    + genesis.as_ref().len()
+                    + public_key.len()
+                    + creator.len()
+                    + 32,
+            );
+            payload.extend_from_slice(DID_CREATE_PREFIX);
+            payload.extend_from_slice(genesis.as_ref());
+            payload.extend_from_slice(public_key);
+            payload.extend_from_slice(&creator);
+            if let Some(salt) = salt {
+                payload.extend_from_slice(salt);
+            }
+            payload
+        }
+
+        /// Payload a key of the DID signs in `emergency_revoke_key`, before the DID nonce is
+        /// appended.
+        pub fn emergency_revoke_payload(
+            did_id: &[u8; 32],
+            public_key: &[u8],
+            valid_until: BlockNumberFor<T>,
+        ) -> Vec<u8> {
+            let genesis = frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero());
+            let valid_until = valid_until.encode();
+            let mut payload = Vec::with_capacity(
+                DID_EMERGENCY_REVOKE_PREFIX.len()
+                    + genesis.as_ref().len()
+                    + did_id.len()
+                    + public_key.len()
+                    + valid_until.len(),
+            );
+            payload.extend_from_slice(DID_EMERGENCY_REVOKE_PREFIX);
+            payload.extend_from_slice(genesis.as_ref());
+            payload.extend_from_slice(did_id);
+            payload.extend_from_slice(public_key);
+            payload.extend_from_slice(&valid_until);
+            payload
+        }
+
+        /// Payload a DID key signs in `dispatch_as_did`, before the DID nonce. It names the
+        /// account submitting the call, so that no other account can submit the signature.
+        pub fn dispatch_payload(
+            did_id: &[u8; 32],
+            role: &KeyRole,
+            submitter: &T::AccountId,
+            call: &<T as Config>::RuntimeCall,
+        ) -> Vec<u8> {
+            let genesis = frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero());
+            let role = role.encode();
+            let submitter = submitter.encode();
+            let call = call.encode();
+            let mut payload = Vec::with_capacity(
+                DID_DISPATCH_PREFIX.len()
+                    + genesis.as_ref().len()
+                    + did_id.len()
+                    + role.len()
+                    + submitter.len()
+                    + call.len(),
+            );
+            payload.extend_from_slice(DID_DISPATCH_PREFIX);
+            payload.extend_from_slice(genesis.as_ref());
+            payload.extend_from_slice(did_id);
+            payload.extend_from_slice(&role);
+            payload.extend_from_slice(&submitter);
+            payload.extend_from_slice(&call);
+            payload
+        }
+
+        /// Payload a key of the DID signs in `link_account`, before the DID nonce is appended.
+        pub fn link_account_payload(did_id: &[u8; 32], account: &T::AccountId) -> Vec<u8> {
+            let genesis = frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero());
+            let account = account.encode();
+            let mut payload = Vec::with_capacity(
+                DID_LINK_ACCOUNT_PREFIX.len()
+                    + genesis.as_ref().len()
+                    + did_id.len()
+                    + account.len(),
+            );
+            payload.extend_from_slice(DID_LINK_ACCOUNT_PREFIX);
+            payload.extend_from_slice(genesis.as_ref());
+            payload.extend_from_slice(did_id);
+            payload.extend_from_slice(&account);
+            payload
+        }
+
+        /// Checks an emergency revocation of `public_key` signed by `signer_public_key` and
+        /// returns the DID nonce it is signed over.
+        fn check_emergency_revocation(
+            did_id: &[u8; 32],
+            public_key: &[u8],
+            signer_public_key: &[u8],
+            valid_until: BlockNumberFor<T>,
+            signature: &[u8],
+        ) -> Result<u64, Error<T>> {
+            ensure!(
+                frame_system::Pallet::<T>::block_number() <= valid_until,
+                Error::<T>::RevocationExpired
+            );
+            let details = DidRecords::<T>::get(did_id).ok_or(Error::<T>::DidNotFound)?;
+            ensure!(!details.deactivated, Error::<T>::DidDeactivated);
+            ensure!(
+                public_key != signer_public_key,
+                Error::<T>::InvalidSignerKey
+            );
+
+            let key = details
+                .keys
+                .iter()
+                .find(|key| key.public_key == public_key)
+                .ok_or(Error::<T>::KeyNotFound)?;
+            ensure!(!key.revoked, Error::<T>::KeyAlreadyRevoked);
+            let signer = details
+                .keys
+                .iter()
+                .find(|key| key.public_key == signer_public_key)
+                .filter(|key| {
+                    key.roles.contains(&KeyRole::CapabilityInvocation) && Self::is_key_valid(key)
+                })
+                .ok_or(Error::<T>::InvalidSignerKey)?;
+
+            let mut message = Self::emergency_revoke_payload(did_id, public_key, valid_until);
+            message.extend_from_slice(&details.nonce.to_le_bytes());
+            ensure!(
+                Self::verify_key_signature(
+                    signer.key_type,
+                    &signer.public_key,
+                    &message,
+                    signature
+                ),
+                Error::<T>::InvalidSignature
+            );
+            Ok(details.nonce)
+        }
+
+        /// Verifies `signature` over `message` with a public key of the given type. Post-quantum
+        /// and key agreement keys cannot be checked on chain and never verify.
+        fn verify_key_signature(
+            key_type: KeyType,
+            public_key: &[u8],
+            message: &[u8],
+            signature: &[u8],
+        ) -> bool {
+            match key_type {
+                KeyType::Ed25519 => {
+                    let (Ok(raw_key), Ok(raw_signature)) = (
+                        <[u8; 32]>::try_from(public_key),
+                        <[u8; 64]>::try_from(signature),
+                    ) else {
+                        return false;
+                    };
+                    sp_io::crypto::ed25519_verify(
+                        &ed25519::Signature::from_raw(raw_signature),
+                        message,
+                        &ed25519::Public::from_raw(raw_key),
+                    )
+                }
+                KeyType::Sr25519 => {
+                    let (Ok(raw_key), Ok(raw_signature)) = (
+                        <[u8; 32]>::try_from(public_key),
+                        <[u8; 64]>::try_from(signature),
+                    ) else {
+                        return false;
+                    };
+                    sp_io::crypto::sr25519_verify(
+                        &sr25519::Signature::from_raw(raw_signature),
+                        message,
+                        &sr25519::Public::from_raw(raw_key),
+                    )
+                }
+                KeyType::EcdsaSecp256k1 => {
+                    let (Ok(raw_key), Ok(raw_signature)) = (
+                        <[u8; 33]>::try_from(public_key),
+                        <[u8; 65]>::try_from(signature),
+                    ) else {
+                        return false;
+                    };
+                    sp_io::crypto::ecdsa_verify(
+                        &ecdsa::Signature::from_raw(raw_signature),
+                        message,
+                        &ecdsa::Public::from_raw(raw_key),
+                    )
+                }
+                _ => false,
+            }
+        }
+
+        pub fn get_did(did_id: Vec<u8>) -> Result<DidDetails<T>, Error<T>> {
             let did_id = Self::decode_did_id(&did_id)?;
             DidRecords::<T>::get(did_id).ok_or(Error::<T>::DidNotFound)
         }
+
+        /// The tombstone left by a deleted DID.
+        pub fn get_did_tombstone(did_id: Vec<u8>) -> Result<DidTombstone, Error<T>> {
+            let did_id = Self::decode_did_id(&did_id)?;
+            DidTombstones::<T>::get(did_id).ok_or(Error::<T>::DidNotFound)
+        }
+
+        /// Whether a key of the DID, given by its id or public key, is valid in the current
+        /// block. Its status at a past time is the one in the block made at that time.
+        pub fn get_key_status(did_id: Vec<u8>, key: Vec<u8>) -> Result<KeyStatus, Error<T>> {
+            let details = Self::get_did(did_id)?;
+            let key = &details.keys[Self::key_index(&details, &key)?];
+            let (block, now) = Self::instant();
+            Ok(Self::key_status_at(key, block, now))
+        }
+
+        /// Unix time in milliseconds of the current block, which time bounds of keys are
+        /// checked against.
+        pub fn get_block_time() -> u64 {
+            Self::now()
+        }
+
+        /// DIDs holding `public_key`, with the status of the key in each of them in the
+        /// current block. Revoked and expired keys are included.
+        pub fn find_dids_by_public_key(public_key: Vec<u8>) -> Vec<(Vec<u8>, KeyStatus)> {
+            let Ok(public_key) = BoundedVec::<u8, T::MaxKeyLength>::try_from(public_key) else {
+                return Vec::new();
+            };
+            let (block, now) = Self::instant();
+            KeyToDid::<T>::iter_key_prefix(&public_key)
+                .filter_map(|did_id| {
+                    let details = DidRecords::<T>::get(did_id)?;
+                    let key = details
+                        .keys
+                        .iter()
+                        .find(|key| key.public_key == public_key)?;
+                    Some((
+                        Self::did_string_from_did_id(&did_id),
+                        Self::key_status_at(key, block, now),
+                    ))
+                })
+                .collect()
+        }
+
+        /// Network segment of the DIDs of this chain, if it has one.
+        pub fn get_network() -> Option<Vec<u8>> {
+            DidNetwork::<T>::get().map(|network| network.into_inner())
+        }
+
+        /// The DID an account is linked to.
+        pub fn get_did_of_account(account: T::AccountId) -> Option<Vec<u8>> {
+            AccountDids::<T>::get(account).map(|link| Self::did_string_from_did_id(&link.did_id))
+        }
+
+        /// Accounts linked to a DID, in the order they were linked.
+        pub fn get_linked_accounts(did_id: Vec<u8>) -> Result<Vec<T::AccountId>, Error<T>> {
+            let did_id = Self::decode_did_id(&did_id)?;
+            Ok(LinkedAccounts::<T>::get(did_id).into_inner())
+        }
+
+        /// The change log of a DID, ordered by version.
+        pub fn get_did_history(did_id: Vec<u8>) -> Result<Vec<DidChange<T>>, Error<T>> {
+            let did_id = Self::decode_did_id(&did_id)?;
+            let mut history: Vec<_> = DidHistory::<T>::iter_prefix_values(did_id).collect();
+            history.sort_by_key(|change| change.version);
+            Ok(history)
+        }
+
+        /// Proposals of a DID that can still be approved, ordered by id.
+        pub fn get_pending_proposals(did_id: Vec<u8>) -> Result<Vec<DidProposal<T>>, Error<T>> {
+            let did_id = Self::decode_did_id(&did_id)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            let mut proposals: Vec<_> = Proposals::<T>::iter_prefix_values(did_id)
+                .filter(|proposal| now < proposal.expires_at)
+                .collect();
+            proposals.sort_by_key(|proposal| proposal.id);
+            Ok(proposals)
+        }
+    }
+
+    #[cfg(feature = "try-runtime")]
+    impl<T: Config> Pallet<T> {
+        /// Checks the invariants of the pallet storage:
+        ///
+        /// - an active DID has an unrevoked `CapabilityInvocation` key, and all its keys are
+        ///   indexed in [`KeyToDid`] and numbered in the order they were added;
+        /// - a DID with recorded history has an entry for its current version, and none for a
+        ///   later one;
+        /// - deposits only exist for stored DIDs;
+        /// - [`AccountDids`] and [`LinkedAccounts`] describe the same links.
+        pub(crate) fn do_try_state() -> Result<(), TryRuntimeError> {
+            // Records not migrated yet do not decode, so the invariants only hold once the
+            // migration is over.
+            if MigrationCursor::<T>::exists() {
+                return Ok(());
+            }
+            for (did_id, details) in DidRecords::<T>::iter() {
+                // DIDs migrated without a key the chain can verify are controlled by their
+                // controllers until they are given one.
+                let migrated = !details
+                    .keys
+                    .iter()
+                    .any(|key| !key.revoked && key.key_type.is_verifiable());
+                ensure!(
+                    details.deactivated
+                        || migrated
+                        || Self::ensure_capability_invocation(&details).is_ok(),
+                    "active DID without an unrevoked CapabilityInvocation key"
+                );
+                ensure!(
+                    details
+                        .keys
+                        .iter()
+                        .all(|key| KeyToDid::<T>::contains_key(&key.public_key, did_id)),
+                    "DID key missing from the public key index"
+                );
+                let mut added = (0..details.keys_added).map(Self::key_id);
+                ensure!(
+                    details.keys.iter().all(|key| added.any(|id| id == key.id)),
+                    "DID key id out of order"
+                );
+
+                let recorded: Vec<_> = DidHistory::<T>::iter_prefix(did_id).collect();
+                ensure!(
+                    recorded.len() == details.history_len as usize,
+                    "DID history length differs from the one its deposit covers"
+                );
+                ensure!(
+                    DidHistory::<T>::contains_key(did_id, details.version),
+                    "current DID version missing from its history"
+                );
+                ensure!(
+                    recorded
+                        .iter()
+                        .all(|(version, change)| *version == change.version
+                            && *version <= details.version),
+                    "DID history ahead of the DID version"
+                );
+            }
+
+            ensure!(
+                DidDeposits::<T>::iter_keys().all(DidRecords::<T>::contains_key),
+                "deposit held for a missing DID"
+            );
+
+            let mut links = 0usize;
+            for (did_id, accounts) in LinkedAccounts::<T>::iter() {
+                links = links.saturating_add(accounts.len());
+                ensure!(
+                    accounts.iter().all(|account| {
+                        AccountDids::<T>::get(account).map_or(false, |link| link.did_id == did_id)
+                    }),
+                    "linked account not pointing back to its DID"
+                );
+            }
+            ensure!(
+                AccountDids::<T>::iter_keys().count() == links,
+                "account linked to a DID that does not list it"
+            );
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Checks `signature` over `payload` followed by the nonce of the DID against its valid
+        /// keys holding `role`, and consumes the nonce.
+        fn verify_signature(
+            did_id: &[u8; 32],
+            role: &KeyRole,
+            payload: &[u8],
+            signature: &[u8],
+        ) -> DispatchResult {
+            DidRecords::<T>::try_mutate(did_id, |maybe_details| -> DispatchResult {
+                let details = maybe_details.as_mut().ok_or(Error::<T>::DidNotFound)?;
+                ensure!(!details.deactivated, Error::<T>::DidDeactivated);
+
+                let mut message = Vec::with_capacity(payload.len() + 8);
+                message.extend_from_slice(payload);
+                message.extend_from_slice(&details.nonce.to_le_bytes());
+                ensure!(
+                    details.keys.iter().any(|key| Self::is_key_valid(key)
+                        && key.roles.contains(role)
+                        && Self::verify_key_signature(
+                            key.key_type,
+                            &key.public_key,
+                            &message,
+                            signature
+                        )),
+                    Error::<T>::InvalidSignature
+                );
+
+                details.nonce = details.nonce.saturating_add(1);
+                Ok(())
+            })
+        }
+    }
+
+    impl<T: Config> DidSignatureVerifier for Pallet<T> {
+        fn verify_did_signature(
+            did: &[u8],
+            role: KeyRole,
+            payload: &[u8],
+            signature: &[u8],
+        ) -> DispatchResult {
+            let did_id = Self::decode_did_id(did)?;
+            Self::verify_signature(&did_id, &role, payload, signature)
+        }
+
+        #[cfg(feature = "runtime-benchmarks")]
+        fn create_benchmark_did() -> Vec<u8> {
+            let owner = frame_benchmarking::account("did_owner", 0, 0);
+            crate::benchmarking::register_did::<T>(&owner).0
+        }
+
+        #[cfg(feature = "runtime-benchmarks")]
+        fn sign_for_benchmark(did: &[u8], payload: &[u8]) -> Vec<u8> {
+            crate::benchmarking::sign::<T>(did, payload)
+        }
+    }
+
+    fn did_string(did_id: &[u8; 32], network: Option<&[u8]>) -> Vec<u8> {
+        let did_id_b58 = bs58::encode(did_id).into_string();
+        let network_len = network.map_or(0, |network| network.len() + 1);
+        let mut did = Vec::with_capacity(DID_PREFIX.len() + network_len + did_id_b58.len());
+        did.extend_from_slice(DID_PREFIX);
+        if let Some(network) = network {
+            did.extend_from_slice(network);
+            did.push(b':');
+        }
+        did.extend_from_slice(did_id_b58.as_bytes());
+        did
+    }
+
+    /// Whether `uri` is an absolute URI as defined by RFC 3986: a scheme, `:` and a
+    /// non-empty remainder.
+    fn is_absolute_uri(uri: &[u8]) -> bool {
+        let Some(colon) = uri.iter().position(|c| *c == b':') else {
+            return false;
+        };
+        let (scheme, rest) = (&uri[..colon], &uri[colon + 1..]);
+        scheme.first().map_or(false, u8::is_ascii_alphabetic)
+            && scheme
+                .iter()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, b'+' | b'-' | b'.'))
+            && !rest.is_empty()
+            && is_uri_text(rest, b":/?#[]@")
+    }
+
+    /// Whether `text` is made of unreserved characters, sub-delimiters, percent escapes and
+    /// the characters in `extra`.
+    fn is_uri_text(text: &[u8], extra: &[u8]) -> bool {
+        let mut index = 0;
+        while index < text.len() {
+            let c = text[index];
+            if c == b'%' {
+                let escape = text.get(index + 1..index + 3);
+                if !escape.map_or(false, |hex| hex.iter().all(u8::is_ascii_hexdigit)) {
+                    return false;
+                }
+                index += 3;
+                continue;
+            }
+            if !(c.is_ascii_alphanumeric() || b"-._~!$&'()*+,;=".contains(&c) || extra.contains(&c))
+            {
+                return false;
+            }
+            index += 1;
+        }
+        true
     }
 }
@@ -0,0 +1,352 @@
+//! Storage migrations of the DID pallet.
+
+use crate::{
+    pallet::{DidRecords, MigrationCursor},
+    Call, Config, DidChangeKind, DidDetails, DidKey, Endpoint, KeyRole, KeyType, MetadataEntry,
+    Pallet, ServiceEndpoint, WeightInfo,
+};
+use codec::{Decode, Encode};
+#[cfg(feature = "try-runtime")]
+use frame_support::ensure;
+use frame_support::{
+    storage::unhashed,
+    traits::{Contains, Get, GetStorageVersion, IsSubType, OnRuntimeUpgrade, StorageVersion},
+    weights::Weight,
+    BoundedVec, RuntimeDebug,
+};
+use sp_runtime::Perbill;
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
+use sp_std::{marker::PhantomData, vec::Vec};
+
+const LOG_TARGET: &str = "runtime::did";
+
+/// Migrates DID records from the original unbounded layout to bounded storage, filling in
+/// the fields introduced since then.
+///
+/// The number of records is not bounded, so they are migrated in batches: the upgrade only
+/// starts the migration, and every block then migrates as many records as [`batch_limit`]
+/// allows from `on_initialize`, carrying on from [`MigrationCursor`]. The storage version is
+/// bumped with the last batch. Records not migrated yet do not decode, so resolution reports
+/// them as not found until then, and [`PausedWhileMigrating`] keeps the calls of the pallet
+/// out of the runtime meanwhile.
+pub mod v1 {
+    use super::*;
+
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+    pub struct OldDidKey {
+        pub public_key: Vec<u8>,
+        pub roles: Vec<KeyRole>,
+        pub revoked: bool,
+    }
+
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+    pub struct OldServiceEndpoint {
+        pub id: Vec<u8>,
+        pub service_type: Vec<u8>,
+        pub endpoint: Vec<u8>,
+    }
+
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+    pub struct OldMetadataEntry {
+        pub key: Vec<u8>,
+        pub value: Vec<u8>,
+    }
+
+    /// DID record in the original layout, which version 1 of the runtime API still returns.
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+    pub struct OldDidDetails {
+        pub version: u64,
+        pub deactivated: bool,
+        pub keys: Vec<OldDidKey>,
+        pub services: Vec<OldServiceEndpoint>,
+        pub metadata: Vec<OldMetadataEntry>,
+    }
+
+    pub struct MigrateToV1<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+        /// Starts the migration, which `on_initialize` carries out from this block on.
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain = Pallet::<T>::on_chain_storage_version();
+            if on_chain != 0 {
+                log::info!(
+                    target: LOG_TARGET,
+                    "skipping v1 migration, on-chain storage version is {:?}",
+                    on_chain
+                );
+                return T::DbWeight::get().reads(1);
+            }
+            if MigrationCursor::<T>::exists() {
+                log::info!(target: LOG_TARGET, "v1 migration already in progress");
+                return T::DbWeight::get().reads(2);
+            }
+
+            MigrationCursor::<T>::put(None::<[u8; 32]>);
+            log::info!(target: LOG_TARGET, "starting v1 migration of DID records");
+            T::DbWeight::get().reads_writes(2, 1)
+        }
+
+        /// Records the on-chain storage version and the number of stored DID records.
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            let on_chain = Pallet::<T>::on_chain_storage_version();
+            let stored = DidRecords::<T>::iter_keys().count() as u64;
+            Ok((on_chain, stored).encode())
+        }
+
+        /// Checks that the migration is under way if it was due, and that no DID record was
+        /// dropped. The migrated records are checked by `try_state` once the last batch ran.
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let (on_chain, stored): (StorageVersion, u64) =
+                Decode::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+            if on_chain == 0 {
+                ensure!(MigrationCursor::<T>::exists(), "v1 migration not started");
+            }
+            ensure!(
+                DidRecords::<T>::iter_keys().count() as u64 == stored,
+                "DID records dropped by the migration"
+            );
+            Ok(())
+        }
+    }
+
+    /// Weight `on_initialize` gives each batch of the migration: a quarter of the block.
+    pub fn batch_limit<T: Config>() -> Weight {
+        Perbill::from_percent(25) * T::BlockWeights::get().max_block
+    }
+
+    /// Migrates as many DID records as `limit` allows, each counted at its worst until it is
+    /// read, from the one after [`MigrationCursor`]. Bumps the storage version once no record
+    /// is left. Returns the weight used, counting the records actually migrated.
+    pub fn migrate_batch<T: Config>(limit: Weight) -> Weight {
+        let mut used = T::DbWeight::get().reads(1);
+        let Some(cursor) = MigrationCursor::<T>::get() else {
+            return used;
+        };
+
+        let per_did = T::WeightInfo::migrate_did_v1(T::MaxKeys::get());
+        let dids = match cursor {
+            Some(cursor) => {
+                DidRecords::<T>::iter_keys_from(DidRecords::<T>::hashed_key_for(cursor))
+            }
+            None => DidRecords::<T>::iter_keys(),
+        };
+        let mut last = None;
+        let mut exhausted = true;
+        for did_id in dids {
+            if used.saturating_add(per_did).any_gt(limit) {
+                exhausted = false;
+                break;
+            }
+            used.saturating_accrue(migrate_did::<T>(&did_id));
+            last = Some(did_id);
+        }
+
+        if exhausted {
+            MigrationCursor::<T>::kill();
+            StorageVersion::new(1).put::<Pallet<T>>();
+            log::info!(target: LOG_TARGET, "migrated DID records to v1");
+            used.saturating_accrue(T::DbWeight::get().writes(2));
+        } else if last.is_some() {
+            MigrationCursor::<T>::put(last);
+            used.saturating_accrue(T::DbWeight::get().writes(1));
+        }
+        used
+    }
+
+    /// Migrates the record of `did_id`, indexes its keys and starts its change log. Records
+    /// that do not decode in the old layout either are dropped. Returns the weight used.
+    pub(crate) fn migrate_did<T: Config>(did_id: &[u8; 32]) -> Weight {
+        let key = DidRecords::<T>::hashed_key_for(did_id);
+        let Some(old) = unhashed::get::<OldDidDetails>(&key) else {
+            log::warn!(
+                target: LOG_TARGET,
+                "dropping undecodable DID record {:?}",
+                did_id
+            );
+            unhashed::kill(&key);
+            return T::WeightInfo::migrate_did_v1(0);
+        };
+
+        let mut details = migrate_details::<T>(did_id, old);
+        Pallet::<T>::index_keys(did_id, &details.keys);
+        Pallet::<T>::record_change(did_id, DidChangeKind::Migrate, &mut details, None);
+        let keys = details.keys.len() as u32;
+        DidRecords::<T>::insert(did_id, details);
+        T::WeightInfo::migrate_did_v1(keys)
+    }
+
+    /// Call filter for the `BaseCallFilter` of the runtime that refuses the calls of the
+    /// pallet while [`MigrateToV1`] is migrating DID records, and lets every other call
+    /// through.
+    pub struct PausedWhileMigrating<T>(PhantomData<T>);
+
+    impl<T: Config, C: IsSubType<Call<T>>> Contains<C> for PausedWhileMigrating<T> {
+        fn contains(call: &C) -> bool {
+            call.is_sub_type().is_none() || !MigrationCursor::<T>::exists()
+        }
+    }
+
+    /// Old records carry no key type, so it is inferred from the key length where only one
+    /// supported type has it. Other keys, 32-byte ones among them since ed25519, sr25519
+    /// and SLH-DSA keys share that length, are kept as `Legacy` keys without roles. Those
+    /// of a length no supported type has are revoked as well. Keys are given the ids they
+    /// were resolved under, numbered by position.
+    ///
+    /// Controllers were not recorded. The accounts of unrevoked 32-byte legacy keys, which
+    /// any of the three schemes derives the same way, become controllers, so the owner
+    /// keeps control of the DID without the scheme being guessed. They can then add a
+    /// typed key and revoke the legacy ones.
+    ///
+    /// Keys of a type that does not sign keep only the `KeyAgreement` role. Any key used to
+    /// be able to change its DID, which now takes a `CapabilityInvocation` key the chain can
+    /// verify. If no unrevoked verifiable key holds that role, all unrevoked verifiable keys
+    /// are given it.
+    ///
+    /// Keys longer than the bound and keys beyond the key limit, revoked ones first, are
+    /// dropped, as are services and metadata entries that exceed the bounds. Records are
+    /// always kept, and each starts its change log with the migrated document. Clients
+    /// convert records read from runtimes before the migration the same way.
+    pub fn migrate_details<T: Config>(did_id: &[u8; 32], old: OldDidDetails) -> DidDetails<T> {
+        let added = old.keys.len() as u32;
+        let mut keys = Vec::with_capacity(old.keys.len());
+        for (position, key) in old.keys.into_iter().enumerate() {
+            let len = key.public_key.len();
+            let Ok(public_key) = key.public_key.try_into() else {
+                log::warn!(
+                    target: LOG_TARGET,
+                    "dropping {}-byte key {} of DID {:?}, longer than the key bound",
+                    len,
+                    position,
+                    did_id
+                );
+                continue;
+            };
+            let key_type = key_type_from_len(len);
+            if key_type.is_none() {
+                log::warn!(
+                    target: LOG_TARGET,
+                    "keeping {}-byte key {} of DID {:?} as a legacy key",
+                    len,
+                    position,
+                    did_id
+                );
+            }
+            keys.push(DidKey::<T> {
+                id: Pallet::<T>::key_id(position as u32),
+                key_type: key_type.unwrap_or(KeyType::Legacy),
+                public_key,
+                roles: match key_type {
+                    Some(key_type) => Pallet::<T>::bounded_roles(
+                        key.roles
+                            .into_iter()
+                            .filter(|role| key_type.is_signing() || *role == KeyRole::KeyAgreement)
+                            .collect(),
+                    ),
+                    None => BoundedVec::new(),
+                },
+                controller: None,
+                revoked: key.revoked || (key_type.is_none() && len != 32),
+                valid_from: None,
+                valid_until: None,
+                expired: false,
+            });
+        }
+
+        let max_keys = T::MaxKeys::get() as usize;
+        let mut excess = keys.len().saturating_sub(max_keys);
+        keys.retain(|key| {
+            let drop = key.revoked && excess > 0;
+            excess -= drop as usize;
+            !drop
+        });
+        if keys.len() > max_keys {
+            log::warn!(
+                target: LOG_TARGET,
+                "dropping {} keys of DID {:?} beyond the key limit",
+                keys.len() - max_keys,
+                did_id
+            );
+            keys.truncate(max_keys);
+        }
+
+        let mut controllers = Vec::new();
+        for key in keys
+            .iter()
+            .filter(|key| !key.revoked && key.key_type == KeyType::Legacy)
+        {
+            if let Ok(account) = T::AccountId::decode(&mut &key.public_key[..]) {
+                if !controllers.contains(&account) {
+                    controllers.push(account);
+                }
+            }
+        }
+
+        let verifiable = |key: &&mut DidKey<T>| !key.revoked && key.key_type.is_verifiable();
+        if !keys
+            .iter_mut()
+            .filter(verifiable)
+            .any(|key| key.roles.contains(&KeyRole::CapabilityInvocation))
+        {
+            for key in keys.iter_mut().filter(verifiable) {
+                let _ = key.roles.try_push(KeyRole::CapabilityInvocation);
+            }
+        }
+
+        let services = old
+            .services
+            .into_iter()
+            .filter_map(|service| {
+                Some(ServiceEndpoint::<T> {
+                    id: service.id.try_into().ok()?,
+                    service_type: service.service_type.try_into().ok()?,
+                    endpoint: Endpoint::Uri(service.endpoint.try_into().ok()?),
+                })
+            })
+            .collect::<Vec<_>>();
+        let metadata = old
+            .metadata
+            .into_iter()
+            .filter_map(|entry| {
+                Some(MetadataEntry::<T> {
+                    key: entry.key.try_into().ok()?,
+                    value: entry.value.try_into().ok()?,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        DidDetails {
+            version: old.version,
+            nonce: 0,
+            deactivated: old.deactivated,
+            created: 0,
+            updated: 0,
+            controllers: BoundedVec::truncate_from(controllers),
+            threshold: 1,
+            keys_added: added,
+            keys: BoundedVec::truncate_from(keys),
+            services: BoundedVec::truncate_from(services),
+            metadata: BoundedVec::truncate_from(metadata),
+            recovery: None,
+            history_len: 0,
+        }
+    }
+
+    /// The supported key type of keys of length `len`, if no other type shares it.
+    fn key_type_from_len(len: usize) -> Option<KeyType> {
+        [
+            KeyType::EcdsaSecp256k1,
+            KeyType::MlDsa44,
+            KeyType::MlDsa65,
+            KeyType::MlDsa87,
+            KeyType::Falcon512,
+            KeyType::MlKem512,
+            KeyType::MlKem768,
+            KeyType::MlKem1024,
+        ]
+        .into_iter()
+        .find(|key_type| key_type.public_key_len() == len)
+    }
+}
@@ -0,0 +1,17 @@
+//! Test runtime of the DID pallet.
+
+use crate as did;
+use frame_support::construct_runtime;
+
+pub use did_test_utils::*;
+
+construct_runtime!(
+    pub struct Test {
+        System: frame_system,
+        Balances: pallet_balances,
+        Timestamp: pallet_timestamp,
+        Did: did,
+    }
+);
+
+impl_identity_test_runtime!(Test, ());
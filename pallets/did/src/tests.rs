@@ -0,0 +1,1701 @@
+use crate::{
+    migrations::v1::{
+        batch_limit, migrate_batch, MigrateToV1, OldDidDetails, OldDidKey, PausedWhileMigrating,
+    },
+    mock::*,
+    pallet::{DidHistory, DidNetwork, DidTombstones, ExpiringDids, KeyToDid, MigrationCursor},
+    Call as DidCall, DidActor, DidChangeKind, DidDeposits, DidRecords, DocumentUpdate, Endpoint,
+    EndpointInput, Error, Event as DidEvent, GenesisConfig, GenesisDid, KeyRole, KeyStatus,
+    KeyType, KeyValidityBound, WeightInfo,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+    assert_noop, assert_ok,
+    dispatch::DispatchResult,
+    storage::unhashed,
+    traits::{
+        BuildGenesisConfig, Contains, Get, GetStorageVersion, Hooks, OnRuntimeUpgrade,
+        StorageVersion,
+    },
+    weights::Weight,
+};
+use sp_core::ed25519;
+use sp_runtime::{
+    traits::ValidateUnsigned,
+    transaction_validity::{InvalidTransaction, TransactionSource},
+    DispatchError,
+};
+
+fn did_id(did: &[u8]) -> [u8; 32] {
+    Did::decode_did_id(did).unwrap()
+}
+
+fn nonce(did: &[u8]) -> u64 {
+    Did::get_did(did.to_vec()).unwrap().nonce
+}
+
+fn add_key(owner: u64, did: &[u8], public_key: Vec<u8>, roles: Vec<KeyRole>) -> DispatchResult {
+    Did::add_key(
+        RuntimeOrigin::signed(owner),
+        did.to_vec(),
+        KeyType::Ed25519,
+        public_key,
+        roles,
+        None,
+        None,
+        None,
+    )
+}
+
+/// The deposit held for a DID, which must be all its owner has reserved.
+fn assert_deposit_held(owner: u64, did: &[u8]) {
+    let deposit = DidDeposits::<Test>::get(did_id(did)).unwrap();
+    assert_eq!(deposit.owner, owner);
+    assert_eq!(Balances::reserved_balance(owner), deposit.amount);
+}
+
+/// Has `submitter` dispatch `call` as `did` with `key` holding `role`, and returns the result
+/// of the call.
+fn dispatch_as(
+    submitter: u64,
+    did: &[u8],
+    key: &ed25519::Pair,
+    role: KeyRole,
+    call: DidCall<Test>,
+) -> DispatchResult {
+    let call = RuntimeCall::Did(call);
+    let payload = Did::dispatch_payload(&did_id(did), &role, &submitter, &call);
+    assert_ok!(Did::dispatch_as_did(
+        RuntimeOrigin::signed(submitter),
+        did.to_vec(),
+        role,
+        Box::new(call),
+        sign(key, &payload, nonce(did)),
+    ));
+    match System::events().pop().map(|record| record.event) {
+        Some(RuntimeEvent::Did(DidEvent::DidDispatched { result, .. })) => result,
+        event => panic!("expected DidDispatched, got {event:?}"),
+    }
+}
+
+fn set_metadata(did: &[u8]) -> DidCall<Test> {
+    DidCall::set_metadata {
+        did_id: did.to_vec(),
+        key: b"name".to_vec(),
+        value: b"alice".to_vec(),
+    }
+}
+
+#[test]
+fn only_authorized_accounts_change_a_did() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+
+        assert_noop!(
+            Did::set_metadata(
+                RuntimeOrigin::signed(BOB),
+                did.clone(),
+                b"name".to_vec(),
+                b"alice".to_vec(),
+            ),
+            Error::<Test>::NotController
+        );
+        assert_noop!(
+            add_key(BOB, &did, public(&pair(2)), vec![KeyRole::AssertionMethod]),
+            Error::<Test>::NotController
+        );
+        assert_noop!(
+            Did::deactivate_did(RuntimeOrigin::signed(BOB), did.clone()),
+            Error::<Test>::NotController
+        );
+
+        assert_ok!(Did::add_controller(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            BOB
+        ));
+        assert_ok!(Did::set_metadata(
+            RuntimeOrigin::signed(BOB),
+            did.clone(),
+            b"name".to_vec(),
+            b"alice".to_vec(),
+        ));
+    });
+}
+
+#[test]
+fn keys_change_their_did_only_through_dispatch_as_did() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let did = create_did(ALICE, &key);
+        let other = create_did(BOB, &pair(2));
+
+        // Any account can submit what a CapabilityInvocation key of the DID signed.
+        assert_ok!(dispatch_as(
+            CHARLIE,
+            &did,
+            &key,
+            KeyRole::CapabilityInvocation,
+            set_metadata(&did)
+        ));
+        assert_eq!(Did::get_did(did.clone()).unwrap().metadata.len(), 1);
+
+        // Other roles, and other DIDs, are not authorized.
+        assert_eq!(
+            dispatch_as(
+                CHARLIE,
+                &did,
+                &key,
+                KeyRole::Authentication,
+                set_metadata(&did)
+            ),
+            Err(Error::<Test>::NotController.into())
+        );
+        assert_eq!(
+            dispatch_as(
+                BOB,
+                &other,
+                &pair(2),
+                KeyRole::CapabilityInvocation,
+                set_metadata(&did)
+            ),
+            Err(Error::<Test>::NotController.into())
+        );
+    });
+}
+
+#[test]
+fn capability_delegation_keys_only_manage_controllers() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+        let delegate = pair(2);
+        assert_ok!(add_key(
+            ALICE,
+            &did,
+            public(&delegate),
+            vec![KeyRole::CapabilityDelegation]
+        ));
+
+        assert_ok!(dispatch_as(
+            CHARLIE,
+            &did,
+            &delegate,
+            KeyRole::CapabilityDelegation,
+            DidCall::add_controller {
+                did_id: did.clone(),
+                controller: BOB,
+            }
+        ));
+        assert_eq!(
+            Did::get_did(did.clone()).unwrap().controllers.to_vec(),
+            vec![ALICE, BOB]
+        );
+        assert_eq!(
+            dispatch_as(
+                CHARLIE,
+                &did,
+                &delegate,
+                KeyRole::CapabilityDelegation,
+                set_metadata(&did)
+            ),
+            Err(Error::<Test>::NotController.into())
+        );
+    });
+}
+
+#[test]
+fn keys_cannot_propose_once_a_threshold_is_set() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let did = create_did(ALICE, &key);
+        assert_ok!(Did::add_controller(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            BOB
+        ));
+        assert_ok!(Did::set_threshold(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            2
+        ));
+
+        assert_eq!(
+            dispatch_as(
+                CHARLIE,
+                &did,
+                &key,
+                KeyRole::CapabilityInvocation,
+                DidCall::revoke_key {
+                    did_id: did.clone(),
+                    key: b"#key-1".to_vec(),
+                }
+            ),
+            Err(Error::<Test>::NotController.into())
+        );
+        // Changes that need no approval are still made at once.
+        assert_ok!(dispatch_as(
+            CHARLIE,
+            &did,
+            &key,
+            KeyRole::CapabilityInvocation,
+            set_metadata(&did)
+        ));
+    });
+}
+
+#[test]
+fn revoked_keys_stay_in_the_document_and_keep_resolving() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+        let revoked = public(&pair(2));
+        assert_ok!(add_key(
+            ALICE,
+            &did,
+            revoked.clone(),
+            vec![KeyRole::AssertionMethod]
+        ));
+        assert_ok!(Did::revoke_key(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            revoked.clone()
+        ));
+        for seed in 3..=16 {
+            assert_ok!(add_key(
+                ALICE,
+                &did,
+                public(&pair(seed)),
+                vec![KeyRole::AssertionMethod]
+            ));
+        }
+
+        // The revoked key is not removed to make room for another one.
+        assert_noop!(
+            add_key(
+                ALICE,
+                &did,
+                public(&pair(17)),
+                vec![KeyRole::AssertionMethod]
+            ),
+            Error::<Test>::TooManyKeys
+        );
+        assert_noop!(
+            add_key(ALICE, &did, revoked.clone(), vec![KeyRole::AssertionMethod]),
+            Error::<Test>::KeyAlreadyExists
+        );
+        assert_eq!(
+            Did::find_dids_by_public_key(revoked),
+            vec![(did, KeyStatus::Revoked)]
+        );
+    });
+}
+
+/// Builds the genesis of chain `chain_id` with a DID of the key of `pair`, controlled by
+/// `ALICE`, and returns the identifier of the DID.
+fn build_genesis_did(chain_id: &[u8], pair: &ed25519::Pair) -> [u8; 32] {
+    GenesisConfig::<Test> {
+        chain_id: chain_id.to_vec(),
+        dids: vec![GenesisDid {
+            controllers: vec![ALICE],
+            keys: vec![(
+                KeyType::Ed25519.name().into(),
+                public(pair),
+                vec![KeyRole::CapabilityInvocation.name().into()],
+            )],
+            services: vec![],
+        }],
+        ..Default::default()
+    }
+    .build();
+    Did::genesis_did_id(chain_id, &public(pair))
+}
+
+#[test]
+fn genesis_dids_are_told_apart_by_chain_and_pay_their_deposit() {
+    let dev = new_test_ext().execute_with(|| {
+        let did_id = build_genesis_did(b"dev", &pair(1));
+        assert!(DidRecords::<Test>::contains_key(did_id));
+        let deposit = DidDeposits::<Test>::get(did_id).unwrap();
+        assert_eq!(deposit.owner, ALICE);
+        assert_eq!(Balances::reserved_balance(ALICE), deposit.amount);
+        did_id
+    });
+    let local = new_test_ext().execute_with(|| build_genesis_did(b"local_testnet", &pair(1)));
+
+    assert_ne!(dev, local);
+    // The chain id is length-prefixed, so it cannot run into the key.
+    assert_ne!(
+        Did::genesis_did_id(b"dev", &[1, 2]),
+        Did::genesis_did_id(b"dev\x01", &[2])
+    );
+}
+
+fn old_key(len: usize, fill: u8, roles: Vec<KeyRole>) -> OldDidKey {
+    OldDidKey {
+        public_key: vec![fill; len],
+        roles,
+        revoked: false,
+    }
+}
+
+/// Stores `old` under `did_id` in the original layout.
+fn put_old_record(did_id: [u8; 32], old: &OldDidDetails) {
+    unhashed::put(&DidRecords::<Test>::hashed_key_for(did_id), old);
+}
+
+/// Runs the v1 migration from the upgrade to its last batch.
+fn migrate_to_v1() {
+    MigrateToV1::<Test>::on_runtime_upgrade();
+    while MigrationCursor::<Test>::exists() {
+        Did::on_initialize(System::block_number());
+    }
+}
+
+#[test]
+fn migration_to_v1_types_keys_by_length() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<Did>();
+        let did_id = [7u8; 32];
+        put_old_record(
+            did_id,
+            &OldDidDetails {
+                version: 3,
+                deactivated: false,
+                keys: vec![
+                    old_key(32, 1, vec![KeyRole::Authentication]),
+                    old_key(33, 2, vec![KeyRole::Authentication]),
+                    // No supported key type is 7 bytes long.
+                    old_key(7, 3, vec![KeyRole::CapabilityInvocation]),
+                    old_key(
+                        KeyType::MlDsa44.public_key_len(),
+                        4,
+                        vec![KeyRole::AssertionMethod],
+                    ),
+                    old_key(
+                        KeyType::MlKem512.public_key_len(),
+                        5,
+                        vec![KeyRole::Authentication, KeyRole::KeyAgreement],
+                    ),
+                    // Longer than the key bound.
+                    old_key(3000, 6, vec![KeyRole::Authentication]),
+                ],
+                services: vec![],
+                metadata: vec![],
+            },
+        );
+
+        migrate_to_v1();
+        assert_eq!(Did::on_chain_storage_version(), 1);
+
+        let details = DidRecords::<Test>::get(did_id).unwrap();
+        assert_eq!(details.version, 3);
+        assert_eq!(details.keys_added, 6);
+        let types: Vec<_> = details.keys.iter().map(|key| key.key_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                KeyType::Legacy,
+                KeyType::EcdsaSecp256k1,
+                KeyType::Legacy,
+                KeyType::MlDsa44,
+                KeyType::MlKem512,
+            ]
+        );
+        let revoked: Vec<_> = details.keys.iter().map(|key| key.revoked).collect();
+        assert_eq!(revoked, vec![false, false, true, false, false]);
+        assert!(details.keys[0].roles.is_empty());
+        assert!(details.keys[2].roles.is_empty());
+        // The only verifiable key is given the role that changes the DID.
+        assert_eq!(
+            details.keys[1].roles.to_vec(),
+            vec![KeyRole::Authentication, KeyRole::CapabilityInvocation]
+        );
+        assert_eq!(
+            details.keys[3].roles.to_vec(),
+            vec![KeyRole::AssertionMethod]
+        );
+        assert_eq!(details.keys[4].roles.to_vec(), vec![KeyRole::KeyAgreement]);
+        assert_eq!(&details.keys[4].id[..], b"#key-5");
+
+        let legacy_account = u64::decode(&mut &[1u8; 32][..]).unwrap();
+        assert_eq!(details.controllers.to_vec(), vec![legacy_account]);
+        assert!(details
+            .keys
+            .iter()
+            .all(|key| KeyToDid::<Test>::contains_key(&key.public_key, did_id)));
+        assert_eq!(details.history_len, 1);
+        assert_eq!(
+            DidHistory::<Test>::get(did_id, 3).unwrap().kind,
+            DidChangeKind::Migrate
+        );
+        #[cfg(feature = "try-runtime")]
+        assert_ok!(Did::do_try_state());
+
+        // Once the storage version is bumped the migration does nothing.
+        let migrated = DidRecords::<Test>::get(did_id).unwrap().encode();
+        migrate_to_v1();
+        assert_eq!(DidRecords::<Test>::get(did_id).unwrap().encode(), migrated);
+    });
+}
+
+#[test]
+fn migration_to_v1_grants_capability_invocation_to_verifiable_keys_only() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<Did>();
+        let did_id = [8u8; 32];
+        put_old_record(
+            did_id,
+            &OldDidDetails {
+                version: 0,
+                deactivated: false,
+                keys: vec![
+                    old_key(33, 1, vec![]),
+                    old_key(
+                        KeyType::Falcon512.public_key_len(),
+                        2,
+                        vec![KeyRole::Authentication],
+                    ),
+                ],
+                services: vec![],
+                metadata: vec![],
+            },
+        );
+
+        migrate_to_v1();
+
+        let details = DidRecords::<Test>::get(did_id).unwrap();
+        assert!(details.controllers.is_empty());
+        assert_eq!(
+            details.keys[0].roles.to_vec(),
+            vec![KeyRole::CapabilityInvocation]
+        );
+        assert_eq!(
+            details.keys[1].roles.to_vec(),
+            vec![KeyRole::Authentication]
+        );
+    });
+}
+
+#[test]
+fn migration_to_v1_runs_in_bounded_batches_and_pauses_the_pallet() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<Did>();
+        let old = OldDidDetails {
+            version: 0,
+            deactivated: false,
+            keys: vec![old_key(33, 1, vec![KeyRole::CapabilityInvocation])],
+            services: vec![],
+            metadata: vec![],
+        };
+        for seed in 1..=3 {
+            put_old_record([seed; 32], &old);
+        }
+        let did_call = RuntimeCall::Did(set_metadata(b"did:qsb:any"));
+        let other_call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+        let db = <Test as frame_system::Config>::DbWeight::get();
+
+        // The upgrade only starts the migration, and pauses the calls of the pallet.
+        MigrateToV1::<Test>::on_runtime_upgrade();
+        assert_eq!(Did::on_chain_storage_version(), 0);
+        assert!(!PausedWhileMigrating::<Test>::contains(&did_call));
+        assert!(PausedWhileMigrating::<Test>::contains(&other_call));
+
+        // A batch takes as many records as fit in the limit, each counted at its worst, and
+        // reports the weight of the records it actually migrated.
+        let worst = <() as WeightInfo>::migrate_did_v1(16);
+        let used = migrate_batch::<Test>(db.reads(1).saturating_add(worst));
+        assert_eq!(
+            used,
+            db.reads_writes(1, 1)
+                .saturating_add(<() as WeightInfo>::migrate_did_v1(1))
+        );
+        let migrated = (1..=3u8)
+            .filter(|seed| DidRecords::<Test>::get([*seed; 32]).is_some())
+            .count();
+        assert_eq!(migrated, 1);
+        assert_eq!(Did::on_chain_storage_version(), 0);
+
+        // Later blocks finish it and lift the pause.
+        assert!(batch_limit::<Test>().all_gte(worst.saturating_mul(2)));
+        migrate_to_v1();
+        assert_eq!(Did::on_chain_storage_version(), 1);
+        assert!((1..=3u8).all(|seed| DidRecords::<Test>::get([seed; 32]).is_some()));
+        assert!(PausedWhileMigrating::<Test>::contains(&did_call));
+        assert_eq!(migrate_batch::<Test>(Weight::MAX), db.reads(1));
+    });
+}
+
+#[test]
+fn create_did_registers_the_creation_key() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let did = create_did(ALICE, &key);
+
+        let details = Did::get_did(did.clone()).unwrap();
+        assert_eq!(details.nonce, 1);
+        assert_eq!(details.controllers.to_vec(), vec![ALICE]);
+        assert_eq!(details.keys.len(), 1);
+        assert_eq!(&details.keys[0].id[..], b"#key-1");
+        assert_eq!(
+            details.keys[0].roles.to_vec(),
+            vec![KeyRole::Authentication, KeyRole::CapabilityInvocation]
+        );
+        assert_eq!(details.created, NOW);
+        assert!(KeyToDid::<Test>::contains_key(
+            &details.keys[0].public_key,
+            did_id(&did)
+        ));
+        assert_eq!(
+            DidHistory::<Test>::get(did_id(&did), 0).unwrap().kind,
+            DidChangeKind::Create
+        );
+        assert_deposit_held(ALICE, &did);
+    });
+}
+
+#[test]
+fn create_did_rejects_signatures_not_made_for_the_creator() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let payload = Did::create_payload(&public(&key), None, &ALICE);
+        let signature = sign(&key, &payload, 0);
+
+        // A signature observed in the pool cannot be submitted by another account.
+        assert_noop!(
+            Did::create_did(
+                RuntimeOrigin::signed(BOB),
+                KeyType::Ed25519,
+                public(&key),
+                None,
+                signature.clone(),
+            ),
+            Error::<Test>::InvalidSignature
+        );
+        assert_noop!(
+            Did::create_did(
+                RuntimeOrigin::signed(ALICE),
+                KeyType::Ed25519,
+                public(&key),
+                None,
+                sign(&pair(2), &payload, 0),
+            ),
+            Error::<Test>::InvalidSignature
+        );
+        assert_ok!(Did::create_did(
+            RuntimeOrigin::signed(ALICE),
+            KeyType::Ed25519,
+            public(&key),
+            None,
+            signature.clone(),
+        ));
+        assert_noop!(
+            Did::create_did(
+                RuntimeOrigin::signed(ALICE),
+                KeyType::Ed25519,
+                public(&key),
+                None,
+                signature,
+            ),
+            Error::<Test>::DidAlreadyExists
+        );
+    });
+}
+
+#[test]
+fn create_did_requires_a_key_the_chain_can_verify() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Did::create_did(
+                RuntimeOrigin::signed(ALICE),
+                KeyType::MlDsa44,
+                vec![1; KeyType::MlDsa44.public_key_len()],
+                None,
+                vec![0; 64],
+            ),
+            Error::<Test>::UnsupportedKeyType
+        );
+    });
+}
+
+#[test]
+fn keys_are_stored_with_their_type_and_checked_against_its_length() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+
+        assert_noop!(
+            Did::add_key(
+                RuntimeOrigin::signed(ALICE),
+                did.clone(),
+                KeyType::MlDsa44,
+                vec![2; KeyType::MlDsa44.public_key_len() - 1],
+                vec![KeyRole::AssertionMethod],
+                None,
+                None,
+                None,
+            ),
+            Error::<Test>::InvalidKeyLength
+        );
+        assert_ok!(Did::add_key(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            KeyType::MlDsa44,
+            vec![2; KeyType::MlDsa44.public_key_len()],
+            vec![KeyRole::AssertionMethod],
+            None,
+            None,
+            None,
+        ));
+
+        let details = Did::get_did(did).unwrap();
+        assert_eq!(details.keys[0].key_type, KeyType::Ed25519);
+        assert_eq!(details.keys[1].key_type, KeyType::MlDsa44);
+        assert_eq!(
+            details.keys[1].public_key.len(),
+            KeyType::MlDsa44.public_key_len()
+        );
+    });
+}
+
+#[test]
+fn every_change_is_logged_with_its_block_and_time() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+        System::set_block_number(5);
+        Timestamp::set_timestamp(NOW + 6_000);
+        assert_ok!(add_key(
+            ALICE,
+            &did,
+            public(&pair(2)),
+            vec![KeyRole::AssertionMethod]
+        ));
+
+        let history = Did::get_did_history(did.clone()).unwrap();
+        let entries: Vec<_> = history
+            .iter()
+            .map(|change| {
+                (
+                    change.version,
+                    change.block_number,
+                    change.timestamp,
+                    change.kind,
+                    change.key.as_ref().map(|key| key.to_vec()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (0, 1, NOW, DidChangeKind::Create, Some(b"#key-1".to_vec())),
+                (
+                    1,
+                    5,
+                    NOW + 6_000,
+                    DidChangeKind::AddKey,
+                    Some(b"#key-2".to_vec())
+                ),
+            ]
+        );
+        let details = Did::get_did(did).unwrap();
+        assert_eq!(details.version, 1);
+        assert_eq!(details.history_len, 2);
+    });
+}
+
+#[test]
+fn documents_stay_within_their_bounds() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+        let set = |key: Vec<u8>, value: Vec<u8>| {
+            Did::set_metadata(RuntimeOrigin::signed(ALICE), did.clone(), key, value)
+        };
+
+        assert_noop!(
+            set(b"name".to_vec(), vec![b'v'; 257]),
+            Error::<Test>::MetadataTooLong
+        );
+        for entry in 0..16u8 {
+            assert_ok!(set(vec![b'k', entry], b"value".to_vec()));
+        }
+        assert_noop!(
+            set(b"one-more".to_vec(), b"value".to_vec()),
+            Error::<Test>::TooManyMetadataEntries
+        );
+        assert_eq!(Did::get_did(did.clone()).unwrap().metadata.len(), 16);
+    });
+}
+
+#[test]
+fn deposits_follow_the_size_of_the_document() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+        let initial = Balances::reserved_balance(ALICE);
+
+        assert_ok!(Did::add_service(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            b"#linked-domain".to_vec(),
+            b"LinkedDomains".to_vec(),
+            EndpointInput::Uri(b"https://example.com".to_vec()),
+        ));
+        assert!(Balances::reserved_balance(ALICE) > initial);
+        assert_deposit_held(ALICE, &did);
+
+        // Changes made by other controllers resize the deposit held from the creator.
+        assert_ok!(Did::add_controller(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            BOB
+        ));
+        assert_ok!(Did::remove_service(
+            RuntimeOrigin::signed(BOB),
+            did.clone(),
+            b"#linked-domain".to_vec(),
+        ));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_deposit_held(ALICE, &did);
+    });
+}
+
+#[test]
+fn key_changes_wait_for_the_threshold_of_controllers() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+        assert_ok!(Did::add_controller(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            BOB
+        ));
+        assert_noop!(
+            Did::set_threshold(RuntimeOrigin::signed(ALICE), did.clone(), 3),
+            Error::<Test>::InvalidThreshold
+        );
+        assert_ok!(Did::set_threshold(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            2
+        ));
+        let document_deposit = Balances::reserved_balance(ALICE);
+
+        assert_noop!(
+            add_key(
+                CHARLIE,
+                &did,
+                public(&pair(2)),
+                vec![KeyRole::AssertionMethod]
+            ),
+            Error::<Test>::NotController
+        );
+        assert_ok!(add_key(
+            ALICE,
+            &did,
+            public(&pair(2)),
+            vec![KeyRole::AssertionMethod]
+        ));
+        assert_eq!(Did::get_did(did.clone()).unwrap().keys.len(), 1);
+        assert_eq!(Did::get_pending_proposals(did.clone()).unwrap().len(), 1);
+        assert!(Balances::reserved_balance(ALICE) > document_deposit);
+
+        assert_noop!(
+            Did::approve_proposal(RuntimeOrigin::signed(ALICE), did.clone(), 0),
+            Error::<Test>::AlreadyApproved
+        );
+        assert_noop!(
+            Did::approve_proposal(RuntimeOrigin::signed(CHARLIE), did.clone(), 0),
+            Error::<Test>::NotController
+        );
+        assert_ok!(Did::approve_proposal(
+            RuntimeOrigin::signed(BOB),
+            did.clone(),
+            0
+        ));
+
+        let details = Did::get_did(did.clone()).unwrap();
+        assert_eq!(details.keys.len(), 2);
+        assert_eq!(&details.keys[1].id[..], b"#key-2");
+        assert!(Did::get_pending_proposals(did.clone()).unwrap().is_empty());
+        assert_deposit_held(ALICE, &did);
+    });
+}
+
+#[test]
+fn expired_proposals_cannot_be_approved() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+        assert_ok!(Did::add_controller(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            BOB
+        ));
+        assert_ok!(Did::set_threshold(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            2
+        ));
+        let document_deposit = Balances::reserved_balance(ALICE);
+        assert_ok!(Did::deactivate_did(
+            RuntimeOrigin::signed(ALICE),
+            did.clone()
+        ));
+
+        System::set_block_number(1 + PROPOSAL_LIFETIME);
+        assert_noop!(
+            Did::approve_proposal(RuntimeOrigin::signed(BOB), did.clone(), 0),
+            Error::<Test>::ProposalExpired
+        );
+        // Anyone can clear an expired proposal, which releases the deposit of its proposer.
+        assert_ok!(Did::cancel_proposal(
+            RuntimeOrigin::signed(CHARLIE),
+            did.clone(),
+            0
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), document_deposit);
+        assert!(!Did::get_did(did).unwrap().deactivated);
+    });
+}
+
+#[test]
+fn guardians_recover_a_did_after_the_delay() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+        let new_key = pair(2);
+        let new_keys = vec![(
+            KeyType::Ed25519,
+            public(&new_key),
+            vec![KeyRole::Authentication, KeyRole::CapabilityInvocation],
+        )];
+
+        assert_noop!(
+            Did::set_recovery(
+                RuntimeOrigin::signed(ALICE),
+                did.clone(),
+                vec![BOB, CHARLIE],
+                vec![],
+                2,
+                MIN_RECOVERY_DELAY - 1,
+            ),
+            Error::<Test>::RecoveryDelayTooShort
+        );
+        assert_ok!(Did::set_recovery(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            vec![BOB, CHARLIE],
+            vec![],
+            2,
+            MIN_RECOVERY_DELAY,
+        ));
+
+        assert_noop!(
+            Did::initiate_recovery(
+                RuntimeOrigin::signed(DAVE),
+                did.clone(),
+                None,
+                new_keys.clone()
+            ),
+            Error::<Test>::NotGuardian
+        );
+        assert_ok!(Did::initiate_recovery(
+            RuntimeOrigin::signed(BOB),
+            did.clone(),
+            None,
+            new_keys
+        ));
+        assert!(Balances::reserved_balance(BOB) > 0);
+        assert_noop!(
+            Did::complete_recovery(RuntimeOrigin::signed(DAVE), did.clone()),
+            Error::<Test>::RecoveryNotReady
+        );
+        assert_noop!(
+            Did::vouch_recovery(RuntimeOrigin::signed(BOB), did.clone(), None),
+            Error::<Test>::AlreadyVouched
+        );
+        assert_ok!(Did::vouch_recovery(
+            RuntimeOrigin::signed(CHARLIE),
+            did.clone(),
+            None
+        ));
+        assert_noop!(
+            Did::complete_recovery(RuntimeOrigin::signed(DAVE), did.clone()),
+            Error::<Test>::RecoveryNotReady
+        );
+
+        System::set_block_number(1 + MIN_RECOVERY_DELAY);
+        assert_ok!(Did::complete_recovery(
+            RuntimeOrigin::signed(DAVE),
+            did.clone()
+        ));
+
+        let details = Did::get_did(did.clone()).unwrap();
+        assert!(details.keys[0].revoked);
+        assert_eq!(&details.keys[1].id[..], b"#key-2");
+        assert_eq!(details.keys[1].public_key.to_vec(), public(&new_key));
+        assert!(!details.keys[1].revoked);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(
+            DidHistory::<Test>::get(did_id(&did), details.version)
+                .unwrap()
+                .kind,
+            DidChangeKind::Recover
+        );
+    });
+}
+
+#[test]
+fn a_cancelled_recovery_releases_its_deposit() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+        assert_ok!(Did::set_recovery(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            vec![BOB],
+            vec![],
+            1,
+            MIN_RECOVERY_DELAY,
+        ));
+        assert_ok!(Did::initiate_recovery(
+            RuntimeOrigin::signed(BOB),
+            did.clone(),
+            None,
+            vec![(
+                KeyType::Ed25519,
+                public(&pair(2)),
+                vec![KeyRole::CapabilityInvocation]
+            )],
+        ));
+
+        assert_noop!(
+            Did::cancel_recovery(RuntimeOrigin::signed(BOB), did.clone()),
+            Error::<Test>::NotController
+        );
+        assert_ok!(Did::cancel_recovery(
+            RuntimeOrigin::signed(ALICE),
+            did.clone()
+        ));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        System::set_block_number(1 + MIN_RECOVERY_DELAY);
+        assert_noop!(
+            Did::complete_recovery(RuntimeOrigin::signed(BOB), did),
+            Error::<Test>::RecoveryNotActive
+        );
+    });
+}
+
+#[test]
+fn deleting_a_did_releases_its_deposits_and_leaves_a_tombstone() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let did = create_did(ALICE, &key);
+        let payload = Did::link_account_payload(&did_id(&did), &BOB);
+        assert_ok!(Did::link_account(
+            RuntimeOrigin::signed(BOB),
+            did.clone(),
+            sign(&key, &payload, 1)
+        ));
+
+        assert_noop!(
+            Did::delete_did(RuntimeOrigin::signed(ALICE), did.clone(), 10),
+            Error::<Test>::DidNotDeactivated
+        );
+        assert_ok!(Did::deactivate_did(
+            RuntimeOrigin::signed(ALICE),
+            did.clone()
+        ));
+        let history_len = Did::get_did(did.clone()).unwrap().history_len;
+        assert_noop!(
+            Did::delete_did(RuntimeOrigin::signed(ALICE), did.clone(), history_len - 1),
+            Error::<Test>::InvalidHistoryLength
+        );
+        assert_noop!(
+            Did::delete_did(RuntimeOrigin::signed(BOB), did.clone(), history_len),
+            Error::<Test>::NotController
+        );
+        assert_ok!(Did::delete_did(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            history_len
+        ));
+
+        let did_id = did_id(&did);
+        assert!(!DidRecords::<Test>::contains_key(did_id));
+        assert!(!DidDeposits::<Test>::contains_key(did_id));
+        assert_eq!(DidHistory::<Test>::iter_prefix(did_id).count(), 0);
+        assert!(DidTombstones::<Test>::contains_key(did_id));
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Did::get_did_of_account(BOB), None);
+
+        let payload = Did::create_payload(&public(&key), None, &ALICE);
+        assert_noop!(
+            Did::create_did(
+                RuntimeOrigin::signed(ALICE),
+                KeyType::Ed25519,
+                public(&key),
+                None,
+                sign(&key, &payload, 0),
+            ),
+            Error::<Test>::DidDeleted
+        );
+    });
+}
+
+#[test]
+fn time_bounded_keys_expire_and_are_flagged_when_idle() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+        assert_noop!(
+            Did::add_key(
+                RuntimeOrigin::signed(ALICE),
+                did.clone(),
+                KeyType::Ed25519,
+                public(&pair(2)),
+                vec![KeyRole::AssertionMethod],
+                None,
+                Some(KeyValidityBound::Block(5)),
+                Some(KeyValidityBound::Block(5)),
+            ),
+            Error::<Test>::InvalidKeyValidity
+        );
+        assert_ok!(Did::add_key(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            KeyType::Ed25519,
+            public(&pair(2)),
+            vec![KeyRole::AssertionMethod],
+            None,
+            Some(KeyValidityBound::Block(3)),
+            Some(KeyValidityBound::Block(5)),
+        ));
+        assert!(ExpiringDids::<Test>::contains_key(did_id(&did)));
+        assert_eq!(
+            Did::get_key_status(did.clone(), b"#key-2".to_vec()).unwrap(),
+            KeyStatus::NotYetValid
+        );
+
+        System::set_block_number(3);
+        assert_eq!(
+            Did::get_key_status(did.clone(), b"#key-2".to_vec()).unwrap(),
+            KeyStatus::Valid
+        );
+
+        System::set_block_number(5);
+        assert_eq!(
+            Did::get_key_status(did.clone(), b"#key-2".to_vec()).unwrap(),
+            KeyStatus::Expired
+        );
+        let version = Did::get_did(did.clone()).unwrap().version;
+        Did::on_idle(5, Weight::MAX);
+        let details = Did::get_did(did.clone()).unwrap();
+        assert!(details.keys[1].expired);
+        assert_eq!(details.version, version + 1);
+        assert!(!ExpiringDids::<Test>::contains_key(did_id(&did)));
+        assert_eq!(
+            Did::get_key_status(did.clone(), public(&pair(2))).unwrap(),
+            KeyStatus::Expired
+        );
+    });
+}
+
+#[test]
+fn emergency_revocations_are_validated_and_not_replayable() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let signer = pair(2);
+        let did = create_did(ALICE, &key);
+        assert_ok!(add_key(
+            ALICE,
+            &did,
+            public(&signer),
+            vec![KeyRole::CapabilityInvocation]
+        ));
+
+        let valid_until = 10;
+        let payload = Did::emergency_revoke_payload(&did_id(&did), &public(&key), valid_until);
+        let call = |signature: Vec<u8>| DidCall::<Test>::emergency_revoke_key {
+            did_id: did.clone(),
+            public_key: public(&key),
+            signer_public_key: public(&signer),
+            valid_until,
+            signature,
+        };
+
+        assert_eq!(
+            Did::validate_unsigned(TransactionSource::External, &call(sign(&key, &payload, 1))),
+            Err(InvalidTransaction::BadProof.into())
+        );
+        let signature = sign(&signer, &payload, 1);
+        assert_ok!(Did::validate_unsigned(
+            TransactionSource::External,
+            &call(signature.clone())
+        ));
+
+        assert_ok!(Did::emergency_revoke_key(
+            RuntimeOrigin::none(),
+            did.clone(),
+            public(&key),
+            public(&signer),
+            valid_until,
+            signature.clone(),
+        ));
+        let details = Did::get_did(did.clone()).unwrap();
+        assert!(details.keys[0].revoked);
+        assert_eq!(details.nonce, 2);
+        assert_noop!(
+            Did::emergency_revoke_key(
+                RuntimeOrigin::none(),
+                did.clone(),
+                public(&key),
+                public(&signer),
+                valid_until,
+                signature,
+            ),
+            Error::<Test>::KeyAlreadyRevoked
+        );
+
+        System::set_block_number(valid_until + 1);
+        assert_eq!(
+            Did::validate_unsigned(TransactionSource::External, &call(vec![0; 64])),
+            Err(InvalidTransaction::Stale.into())
+        );
+    });
+}
+
+#[test]
+fn operations_are_applied_all_together_or_not_at_all() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+        let add_service = DocumentUpdate::AddService {
+            service_id: b"#linked-domain".to_vec(),
+            service_type: b"LinkedDomains".to_vec(),
+            endpoint: EndpointInput::Uri(b"https://example.com".to_vec()),
+        };
+        let set_metadata = DocumentUpdate::SetMetadata {
+            key: b"name".to_vec(),
+            value: b"alice".to_vec(),
+        };
+
+        assert_noop!(
+            Did::apply_operations(RuntimeOrigin::signed(ALICE), did.clone(), vec![]),
+            Error::<Test>::NoOperations
+        );
+        assert_noop!(
+            Did::apply_operations(
+                RuntimeOrigin::signed(ALICE),
+                did.clone(),
+                vec![
+                    add_service.clone(),
+                    set_metadata.clone(),
+                    DocumentUpdate::RemoveService {
+                        service_id: b"#missing".to_vec(),
+                    },
+                ],
+            ),
+            Error::<Test>::ServiceNotFound
+        );
+
+        let version = Did::get_did(did.clone()).unwrap().version;
+        assert_ok!(Did::apply_operations(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            vec![
+                DocumentUpdate::AddKey {
+                    key_type: KeyType::Ed25519,
+                    public_key: public(&pair(2)),
+                    roles: vec![KeyRole::AssertionMethod],
+                    controller: None,
+                    valid_from: None,
+                    valid_until: None,
+                },
+                add_service,
+                set_metadata,
+            ],
+        ));
+        let details = Did::get_did(did.clone()).unwrap();
+        assert_eq!(details.version, version + 1);
+        assert_eq!(details.keys.len(), 2);
+        assert_eq!(details.services.len(), 1);
+        assert_eq!(details.metadata.len(), 1);
+        assert_deposit_held(ALICE, &did);
+        assert_eq!(
+            System::events().pop().map(|record| record.event),
+            Some(RuntimeEvent::Did(DidEvent::OperationsApplied {
+                did: did.clone(),
+                did_id: did_id(&did),
+                version: version + 1,
+                actor: DidActor::Account(ALICE),
+                count: 3,
+            }))
+        );
+    });
+}
+
+#[test]
+fn link_account_rejects_bad_and_replayed_signatures() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let did = create_did(ALICE, &key);
+        let payload = Did::link_account_payload(&did_id(&did), &BOB);
+
+        assert_noop!(
+            Did::link_account(
+                RuntimeOrigin::signed(BOB),
+                did.clone(),
+                sign(&pair(2), &payload, 1)
+            ),
+            Error::<Test>::InvalidSignature
+        );
+        // The signature names the account it links.
+        assert_noop!(
+            Did::link_account(
+                RuntimeOrigin::signed(CHARLIE),
+                did.clone(),
+                sign(&key, &payload, 1)
+            ),
+            Error::<Test>::InvalidSignature
+        );
+
+        let signature = sign(&key, &payload, 1);
+        assert_ok!(Did::link_account(
+            RuntimeOrigin::signed(BOB),
+            did.clone(),
+            signature.clone()
+        ));
+        assert_eq!(nonce(&did), 2);
+        assert_eq!(Did::get_did_of_account(BOB), Some(did.clone()));
+        assert!(Balances::reserved_balance(BOB) > 0);
+
+        assert_ok!(Did::unlink_account(RuntimeOrigin::signed(BOB), BOB));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_noop!(
+            Did::link_account(RuntimeOrigin::signed(BOB), did, signature),
+            Error::<Test>::InvalidSignature
+        );
+    });
+}
+
+#[test]
+fn services_are_updated_in_place_and_checked_against_the_allowed_types() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+        for service_id in [&b"#hub"[..], b"#linked-domain"] {
+            assert_ok!(Did::add_service(
+                RuntimeOrigin::signed(ALICE),
+                did.clone(),
+                service_id.to_vec(),
+                b"LinkedDomains".to_vec(),
+                EndpointInput::Uri(b"https://example.com".to_vec()),
+            ));
+        }
+        for endpoint in [
+            EndpointInput::Uri(b"example.com".to_vec()),
+            EndpointInput::Set(vec![]),
+            EndpointInput::Map(vec![(b"origins".to_vec(), b"example.com".to_vec())]),
+            EndpointInput::Map(vec![(b"a b".to_vec(), b"https://example.com".to_vec())]),
+        ] {
+            assert_noop!(
+                Did::update_service(
+                    RuntimeOrigin::signed(ALICE),
+                    did.clone(),
+                    b"#hub".to_vec(),
+                    None,
+                    Some(endpoint),
+                ),
+                Error::<Test>::InvalidServiceEndpoint
+            );
+        }
+        assert_noop!(
+            Did::update_service(
+                RuntimeOrigin::signed(ALICE),
+                did.clone(),
+                b"#missing".to_vec(),
+                None,
+                None,
+            ),
+            Error::<Test>::ServiceNotFound
+        );
+
+        assert_ok!(Did::update_service(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            b"#hub".to_vec(),
+            Some(b"IdentityHub".to_vec()),
+            Some(EndpointInput::Set(vec![
+                b"https://hub.example.com".to_vec(),
+                b"https://backup.example.com".to_vec(),
+            ])),
+        ));
+        let services = Did::get_did(did.clone()).unwrap().services;
+        assert_eq!(&services[0].id[..], b"#hub");
+        assert_eq!(&services[0].service_type[..], b"IdentityHub");
+        assert!(matches!(&services[0].endpoint, Endpoint::Set(uris) if uris.len() == 2));
+        assert_eq!(&services[1].id[..], b"#linked-domain");
+
+        assert_noop!(
+            Did::set_allowed_service_types(
+                RuntimeOrigin::signed(ALICE),
+                Some(vec![b"LinkedDomains".to_vec()])
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(Did::set_allowed_service_types(
+            RuntimeOrigin::root(),
+            Some(vec![b"LinkedDomains".to_vec()])
+        ));
+        assert_noop!(
+            Did::update_service(
+                RuntimeOrigin::signed(ALICE),
+                did.clone(),
+                b"#linked-domain".to_vec(),
+                Some(b"IdentityHub".to_vec()),
+                None,
+            ),
+            Error::<Test>::ServiceTypeNotAllowed
+        );
+        // Services already in the document keep their type, and their endpoint can change.
+        assert_ok!(Did::update_service(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            b"#hub".to_vec(),
+            None,
+            Some(EndpointInput::Uri(b"https://hub.example.com".to_vec())),
+        ));
+
+        assert_ok!(Did::set_allowed_service_types(RuntimeOrigin::root(), None));
+        assert_ok!(Did::update_service(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            b"#linked-domain".to_vec(),
+            Some(b"IdentityHub".to_vec()),
+            None,
+        ));
+        assert_deposit_held(ALICE, &did);
+    });
+}
+
+#[test]
+fn dispatch_as_did_consumes_the_nonce() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let did = create_did(ALICE, &key);
+        let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+        let payload = Did::dispatch_payload(&did_id(&did), &KeyRole::Authentication, &ALICE, &call);
+        let signature = sign(&key, &payload, 1);
+
+        assert_noop!(
+            Did::dispatch_as_did(
+                RuntimeOrigin::signed(BOB),
+                did.clone(),
+                KeyRole::Authentication,
+                Box::new(call.clone()),
+                signature.clone(),
+            ),
+            Error::<Test>::InvalidSignature
+        );
+        // The key does not hold the role the call is dispatched with.
+        assert_noop!(
+            Did::dispatch_as_did(
+                RuntimeOrigin::signed(ALICE),
+                did.clone(),
+                KeyRole::AssertionMethod,
+                Box::new(call.clone()),
+                signature.clone(),
+            ),
+            Error::<Test>::InvalidSignature
+        );
+        assert_ok!(Did::dispatch_as_did(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            KeyRole::Authentication,
+            Box::new(call.clone()),
+            signature.clone(),
+        ));
+        assert_noop!(
+            Did::dispatch_as_did(
+                RuntimeOrigin::signed(ALICE),
+                did,
+                KeyRole::Authentication,
+                Box::new(call),
+                signature,
+            ),
+            Error::<Test>::InvalidSignature
+        );
+    });
+}
+
+#[test]
+fn keys_that_do_not_sign_only_agree_on_keys() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+
+        assert_noop!(
+            Did::add_key(
+                RuntimeOrigin::signed(ALICE),
+                did.clone(),
+                KeyType::MlKem512,
+                vec![2; KeyType::MlKem512.public_key_len()],
+                vec![KeyRole::Authentication],
+                None,
+                None,
+                None,
+            ),
+            Error::<Test>::RoleNotSupported
+        );
+        assert_ok!(Did::add_key(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            KeyType::MlKem512,
+            vec![2; KeyType::MlKem512.public_key_len()],
+            vec![KeyRole::KeyAgreement],
+            None,
+            None,
+            None,
+        ));
+    });
+}
+
+#[test]
+fn the_last_capability_invocation_key_cannot_be_revoked() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+
+        assert_noop!(
+            Did::revoke_key(
+                RuntimeOrigin::signed(ALICE),
+                did.clone(),
+                b"#key-1".to_vec()
+            ),
+            Error::<Test>::LastCapabilityInvocationKey
+        );
+        assert_noop!(
+            Did::update_roles(
+                RuntimeOrigin::signed(ALICE),
+                did.clone(),
+                b"#key-1".to_vec(),
+                vec![KeyRole::Authentication],
+            ),
+            Error::<Test>::LastCapabilityInvocationKey
+        );
+        assert_ok!(add_key(
+            ALICE,
+            &did,
+            public(&pair(2)),
+            vec![KeyRole::CapabilityInvocation]
+        ));
+        assert_ok!(Did::revoke_key(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            b"#key-1".to_vec()
+        ));
+        assert!(Did::get_did(did).unwrap().keys[0].revoked);
+    });
+}
+
+#[test]
+fn keys_are_addressed_by_id_and_may_be_controlled_by_another_did() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+        let other = create_did(BOB, &pair(2));
+        let add_controlled_key = |public_key: Vec<u8>, controller: Vec<u8>| {
+            Did::add_key(
+                RuntimeOrigin::signed(ALICE),
+                did.clone(),
+                KeyType::Ed25519,
+                public_key,
+                vec![KeyRole::AssertionMethod],
+                Some(controller),
+                None,
+                None,
+            )
+        };
+
+        assert_noop!(
+            add_controlled_key(public(&pair(3)), b"did:qsb:unknown".to_vec()),
+            Error::<Test>::InvalidDidId
+        );
+        assert_noop!(
+            add_controlled_key(public(&pair(3)), Did::did_string_from_did_id(&[9; 32])),
+            Error::<Test>::KeyControllerNotFound
+        );
+        assert_ok!(add_controlled_key(public(&pair(3)), other.clone()));
+        let key = &Did::get_did(did.clone()).unwrap().keys[1];
+        assert_eq!(&key.id[..], b"#key-2");
+        assert_eq!(key.controller, Some(did_id(&other)));
+
+        assert_ok!(Did::update_roles(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            b"#key-2".to_vec(),
+            vec![KeyRole::AssertionMethod, KeyRole::Authentication],
+        ));
+        assert_ok!(Did::revoke_key(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            public(&pair(3))
+        ));
+        assert_noop!(
+            Did::revoke_key(
+                RuntimeOrigin::signed(ALICE),
+                did.clone(),
+                b"#key-9".to_vec()
+            ),
+            Error::<Test>::KeyNotFound
+        );
+
+        // Ids are never reused, even once the key holding one is revoked.
+        assert_ok!(add_key(
+            ALICE,
+            &did,
+            public(&pair(4)),
+            vec![KeyRole::KeyAgreement]
+        ));
+        let keys = Did::get_did(did.clone()).unwrap().keys;
+        assert!(keys[1].revoked);
+        assert_eq!(keys[1].roles.len(), 2);
+        assert_eq!(&keys[2].id[..], b"#key-3");
+        assert_eq!(keys[2].controller, None);
+    });
+}
+
+#[test]
+fn salts_give_a_key_several_dids_and_dids_carry_the_network_of_the_chain() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let did = create_did(ALICE, &key);
+        let salt = [7; 32];
+        let create_salted = |signed_salt: Option<&[u8; 32]>| {
+            let payload = Did::create_payload(&public(&key), signed_salt, &ALICE);
+            Did::create_did(
+                RuntimeOrigin::signed(ALICE),
+                KeyType::Ed25519,
+                public(&key),
+                Some(salt),
+                sign(&key, &payload, 0),
+            )
+        };
+
+        // The signature covers the salt.
+        assert_noop!(create_salted(None), Error::<Test>::InvalidSignature);
+        assert_ok!(create_salted(Some(&salt)));
+        assert_noop!(create_salted(Some(&salt)), Error::<Test>::DidAlreadyExists);
+        let dids: Vec<_> = Did::find_dids_by_public_key(public(&key))
+            .into_iter()
+            .map(|(did, _)| did)
+            .collect();
+        assert_eq!(dids.len(), 2);
+        assert!(dids.contains(&did));
+
+        let id = did_id(&did);
+        DidNetwork::<Test>::put(Did::checked_network(b"testnet").unwrap());
+        let qualified = Did::did_string_from_did_id(&id);
+        assert_eq!(
+            qualified,
+            [&b"did:qsb:testnet:"[..], &did[b"did:qsb:".len()..]].concat()
+        );
+        assert!(Did::get_did(qualified).is_ok());
+        assert!(matches!(
+            Did::get_did(did.clone()),
+            Err(Error::<Test>::WrongDidNetwork)
+        ));
+        let other_network = [&b"did:qsb:mainnet:"[..], &did[b"did:qsb:".len()..]].concat();
+        assert!(matches!(
+            Did::get_did(other_network),
+            Err(Error::<Test>::WrongDidNetwork)
+        ));
+        assert!(Did::checked_network(b"name").is_err());
+    });
+}
+
+#[test]
+fn events_carry_the_change_and_are_indexed_by_did() {
+    new_test_ext().execute_with(|| {
+        let did = create_did(ALICE, &pair(1));
+        let id = did_id(&did);
+        assert_ok!(add_key(
+            ALICE,
+            &did,
+            public(&pair(2)),
+            vec![KeyRole::AssertionMethod]
+        ));
+
+        let events: Vec<_> = System::events()
+            .into_iter()
+            .filter(|record| matches!(record.event, RuntimeEvent::Did(_)))
+            .map(|record| {
+                assert_eq!(record.topics, vec![Did::did_topic(&id)]);
+                record.event
+            })
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                RuntimeEvent::Did(DidEvent::DidCreated {
+                    did: did.clone(),
+                    did_id: id,
+                    version: 0,
+                    actor: DidActor::Account(ALICE),
+                    key_id: Did::key_id(0),
+                    key_type: KeyType::Ed25519,
+                    public_key: public(&pair(1)),
+                }),
+                RuntimeEvent::Did(DidEvent::KeyAdded {
+                    did: did.clone(),
+                    did_id: id,
+                    version: 1,
+                    actor: DidActor::Account(ALICE),
+                    key_id: Did::key_id(1),
+                    key_type: KeyType::Ed25519,
+                    public_key: public(&pair(2)),
+                    roles: vec![KeyRole::AssertionMethod],
+                    controller: None,
+                }),
+            ]
+        );
+
+        // Changes made by the DID itself name the key it signed with.
+        System::reset_events();
+        let payload = Did::emergency_revoke_payload(&id, &public(&pair(2)), 10);
+        assert_ok!(Did::emergency_revoke_key(
+            RuntimeOrigin::none(),
+            did.clone(),
+            public(&pair(2)),
+            public(&pair(1)),
+            10,
+            sign(&pair(1), &payload, nonce(&did)),
+        ));
+        assert!(matches!(
+            System::events().pop().map(|record| record.event),
+            Some(RuntimeEvent::Did(DidEvent::KeyRevoked {
+                version: 2,
+                actor: DidActor::Key(key_id),
+                ..
+            })) if key_id == Did::key_id(0)
+        ));
+    });
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn try_state_holds_after_changes_and_catches_broken_indexes() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let did = create_did(ALICE, &key);
+        assert_ok!(add_key(
+            ALICE,
+            &did,
+            public(&pair(2)),
+            vec![KeyRole::CapabilityInvocation]
+        ));
+        assert_ok!(Did::revoke_key(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            b"#key-1".to_vec()
+        ));
+        let payload = Did::link_account_payload(&did_id(&did), &BOB);
+        assert_ok!(Did::link_account(
+            RuntimeOrigin::signed(BOB),
+            did.clone(),
+            sign(&pair(2), &payload, 1)
+        ));
+        assert_ok!(Did::do_try_state());
+
+        KeyToDid::<Test>::remove(public(&key), did_id(&did));
+        assert!(Did::do_try_state().is_err());
+    });
+}
@@ -0,0 +1,1057 @@
+//! Weights for did
+//!
+//! PLACEHOLDER WEIGHTS, NOT BENCHMARK OUTPUT. No run of `benchmark pallet` produced these
+//! figures. They are rough estimates from the benchmarks in `benchmarking.rs` and the
+//! `MaxEncodedLen` of the stored records, deliberately overestimated to stay safe until
+//! real figures exist: the execution times are twice the estimate, and every call is
+//! charged one read and one write more than the storage it is known to touch. The layout
+//! follows the CLI's so that its output can replace this file as is. Generate real
+//! weights on reference hardware with:
+//!
+//! ./target/release/qsb-node benchmark pallet --chain dev --pallet did --extrinsic '*' \
+//!     --steps 50 --repeat 20 --output pallets/did/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for did.
+pub trait WeightInfo {
+	fn create_did() -> Weight;
+	fn add_key(k: u32) -> Weight;
+	fn revoke_key(k: u32) -> Weight;
+	fn deactivate_did(k: u32) -> Weight;
+	fn add_service(s: u32) -> Weight;
+	fn remove_service(s: u32) -> Weight;
+	fn set_metadata(m: u32) -> Weight;
+	fn remove_metadata(m: u32) -> Weight;
+	fn rotate_key(k: u32) -> Weight;
+	fn update_roles(k: u32) -> Weight;
+	fn add_controller(c: u32) -> Weight;
+	fn remove_controller(c: u32) -> Weight;
+	fn set_threshold(c: u32) -> Weight;
+	fn propose_operation(p: u32) -> Weight;
+	fn approve_proposal(c: u32) -> Weight;
+	fn cancel_proposal() -> Weight;
+	fn set_recovery(g: u32) -> Weight;
+	fn initiate_recovery(k: u32) -> Weight;
+	fn vouch_recovery(g: u32) -> Weight;
+	fn cancel_recovery() -> Weight;
+	fn complete_recovery(k: u32) -> Weight;
+	fn delete_did(h: u32) -> Weight;
+	fn expire_keys(k: u32) -> Weight;
+	fn emergency_revoke_key(k: u32) -> Weight;
+	fn link_account() -> Weight;
+	fn unlink_account(a: u32) -> Weight;
+	fn update_service(s: u32) -> Weight;
+	fn set_allowed_service_types(t: u32) -> Weight;
+	fn dispatch_as_did() -> Weight;
+	fn migrate_did_v1(k: u32) -> Weight;
+}
+
+/// Weights for did using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did DidTombstones (r:1 w:0)
+	/// Proof: Did DidTombstones (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:0 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// Storage: Did KeyToDid (r:0 w:1)
+	/// Proof: Did KeyToDid (max_values: None, max_size: Some(2650), added: 5125, mode: MaxEncodedLen)
+	fn create_did() -> Weight {
+		Weight::from_parts(122_408_000, 103_586)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	/// Storage: Did DidRecords (r:2 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// Storage: Did ExpiringDids (r:0 w:1)
+	/// Proof: Did ExpiringDids (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: Did KeyToDid (r:0 w:2)
+	/// Proof: Did KeyToDid (max_values: None, max_size: Some(2650), added: 5125, mode: MaxEncodedLen)
+	/// The range of component `k` is `[1, 15]`.
+	fn add_key(k: u32) -> Weight {
+		Weight::from_parts(79_608_000, 199_044)
+			.saturating_add(Weight::from_parts(2_936_000, 0).saturating_mul(k.into()))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `k` is `[2, 16]`.
+	fn revoke_key(k: u32) -> Weight {
+		Weight::from_parts(63_740_000, 101_099)
+			.saturating_add(Weight::from_parts(2_804_000, 0).saturating_mul(k.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `k` is `[1, 16]`.
+	fn deactivate_did(k: u32) -> Weight {
+		Weight::from_parts(61_842_000, 101_099)
+			.saturating_add(Weight::from_parts(2_790_000, 0).saturating_mul(k.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did AllowedServiceTypes (r:1 w:0)
+	/// Proof: Did AllowedServiceTypes (max_values: Some(1), max_size: Some(8257), added: 8752, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `s` is `[0, 15]`.
+	fn add_service(s: u32) -> Weight {
+		Weight::from_parts(132_214_000, 109_851)
+			.saturating_add(Weight::from_parts(3_788_000, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `s` is `[1, 16]`.
+	fn remove_service(s: u32) -> Weight {
+		Weight::from_parts(113_624_000, 101_099)
+			.saturating_add(Weight::from_parts(3_692_000, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `m` is `[0, 15]`.
+	fn set_metadata(m: u32) -> Weight {
+		Weight::from_parts(115_928_000, 101_099)
+			.saturating_add(Weight::from_parts(636_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `m` is `[1, 16]`.
+	fn remove_metadata(m: u32) -> Weight {
+		Weight::from_parts(112_410_000, 101_099)
+			.saturating_add(Weight::from_parts(618_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// Storage: Did ExpiringDids (r:0 w:1)
+	/// Proof: Did ExpiringDids (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: Did KeyToDid (r:0 w:2)
+	/// Proof: Did KeyToDid (max_values: None, max_size: Some(2650), added: 5125, mode: MaxEncodedLen)
+	/// The range of component `k` is `[2, 15]`.
+	fn rotate_key(k: u32) -> Weight {
+		Weight::from_parts(73_496_000, 103_614)
+			.saturating_add(Weight::from_parts(2_948_000, 0).saturating_mul(k.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `k` is `[2, 16]`.
+	fn update_roles(k: u32) -> Weight {
+		Weight::from_parts(62_910_000, 101_099)
+			.saturating_add(Weight::from_parts(2_796_000, 0).saturating_mul(k.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `c` is `[1, 7]`.
+	fn add_controller(c: u32) -> Weight {
+		Weight::from_parts(115_380_000, 101_099)
+			.saturating_add(Weight::from_parts(142_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `c` is `[2, 8]`.
+	fn remove_controller(c: u32) -> Weight {
+		Weight::from_parts(113_466_000, 101_099)
+			.saturating_add(Weight::from_parts(132_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `c` is `[1, 8]`.
+	fn set_threshold(c: u32) -> Weight {
+		Weight::from_parts(112_208_000, 101_099)
+			.saturating_add(Weight::from_parts(116_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:0)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did Proposals (r:7 w:8)
+	/// Proof: Did Proposals (max_values: None, max_size: Some(21607), added: 24082, mode: MaxEncodedLen)
+	/// Storage: Did NextProposalId (r:1 w:1)
+	/// Proof: Did NextProposalId (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: System Account (r:8 w:8)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `p` is `[0, 7]`.
+	fn propose_operation(p: u32) -> Weight {
+		Weight::from_parts(49_632_000, 100_552)
+			.saturating_add(Weight::from_parts(19_746_000, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(p.into())))
+			.saturating_add(Weight::from_parts(0, 26_685).saturating_mul(p.into()))
+	}
+	/// Storage: Did DidRecords (r:1 w:0)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did Proposals (r:1 w:1)
+	/// Proof: Did Proposals (max_values: None, max_size: Some(21607), added: 24082, mode: MaxEncodedLen)
+	/// The range of component `c` is `[3, 8]`.
+	fn approve_proposal(c: u32) -> Weight {
+		Weight::from_parts(39_084_000, 119_512)
+			.saturating_add(Weight::from_parts(192_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Did Proposals (r:1 w:1)
+	/// Proof: Did Proposals (max_values: None, max_size: Some(21607), added: 24082, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn cancel_proposal() -> Weight {
+		Weight::from_parts(42_754_000, 26_685)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did Recoveries (r:1 w:1)
+	/// Proof: Did Recoveries (max_values: None, max_size: Some(43128), added: 45603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `g` is `[1, 8]`.
+	fn set_recovery(g: u32) -> Weight {
+		Weight::from_parts(116_438_000, 146_702)
+			.saturating_add(Weight::from_parts(366_000, 0).saturating_mul(g.into()))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:0)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did Recoveries (r:1 w:1)
+	/// Proof: Did Recoveries (max_values: None, max_size: Some(43128), added: 45603, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `k` is `[1, 15]`.
+	fn initiate_recovery(k: u32) -> Weight {
+		Weight::from_parts(73_804_000, 143_636)
+			.saturating_add(Weight::from_parts(3_024_000, 0).saturating_mul(k.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: Did DidRecords (r:2 w:0)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did Recoveries (r:1 w:1)
+	/// Proof: Did Recoveries (max_values: None, max_size: Some(43128), added: 45603, mode: MaxEncodedLen)
+	/// The range of component `g` is `[2, 8]`.
+	fn vouch_recovery(g: u32) -> Weight {
+		Weight::from_parts(54_930_000, 236_463)
+			.saturating_add(Weight::from_parts(2_208_000, 0).saturating_mul(g.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:0)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did Recoveries (r:1 w:1)
+	/// Proof: Did Recoveries (max_values: None, max_size: Some(43128), added: 45603, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn cancel_recovery() -> Weight {
+		Weight::from_parts(59_742_000, 143_636)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: Did Recoveries (r:1 w:1)
+	/// Proof: Did Recoveries (max_values: None, max_size: Some(43128), added: 45603, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// Storage: Did KeyToDid (r:0 w:30)
+	/// Proof: Did KeyToDid (max_values: None, max_size: Some(2650), added: 5125, mode: MaxEncodedLen)
+	/// The range of component `k` is `[1, 15]`.
+	fn complete_recovery(k: u32) -> Weight {
+		Weight::from_parts(82_672_000, 149_305)
+			.saturating_add(Weight::from_parts(3_466_000, 0).saturating_mul(k.into()))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(k.into())))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1000)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:17 w:17)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did Proposals (r:8 w:8)
+	/// Proof: Did Proposals (max_values: None, max_size: Some(21607), added: 24082, mode: MaxEncodedLen)
+	/// Storage: Did NextProposalId (r:0 w:1)
+	/// Proof: Did NextProposalId (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: Did Recoveries (r:1 w:1)
+	/// Proof: Did Recoveries (max_values: None, max_size: Some(43128), added: 45603, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did ExpiringDids (r:0 w:1)
+	/// Proof: Did ExpiringDids (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: Did LinkedAccounts (r:1 w:1)
+	/// Proof: Did LinkedAccounts (max_values: None, max_size: Some(553), added: 3028, mode: MaxEncodedLen)
+	/// Storage: Did AccountDids (r:16 w:16)
+	/// Proof: Did AccountDids (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did KeyToDid (r:0 w:16)
+	/// Proof: Did KeyToDid (max_values: None, max_size: Some(2650), added: 5125, mode: MaxEncodedLen)
+	/// Storage: Did DidTombstones (r:0 w:1)
+	/// Proof: Did DidTombstones (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// The range of component `h` is `[4, 1000]`.
+	fn delete_did(h: u32) -> Weight {
+		Weight::from_parts(821_024_000, 433_247)
+			.saturating_add(Weight::from_parts(2_536_000, 0).saturating_mul(h.into()))
+			.saturating_add(T::DbWeight::get().reads(47_u64))
+			.saturating_add(T::DbWeight::get().writes(65_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(h.into())))
+	}
+	/// Storage: Did ExpiryCursor (r:1 w:1)
+	/// Proof: Did ExpiryCursor (max_values: Some(1), max_size: Some(32), added: 527, mode: MaxEncodedLen)
+	/// Storage: Did ExpiringDids (r:1 w:1)
+	/// Proof: Did ExpiringDids (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `k` is `[1, 15]`.
+	fn expire_keys(k: u32) -> Weight {
+		Weight::from_parts(25_294_000, 98_975)
+			.saturating_add(Weight::from_parts(2_842_000, 0).saturating_mul(k.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `k` is `[2, 16]`.
+	fn emergency_revoke_key(k: u32) -> Weight {
+		Weight::from_parts(96_654_000, 98_452)
+			.saturating_add(Weight::from_parts(2_778_000, 0).saturating_mul(k.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: Did AccountDids (r:1 w:1)
+	/// Proof: Did AccountDids (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did LinkedAccounts (r:1 w:1)
+	/// Proof: Did LinkedAccounts (max_values: None, max_size: Some(553), added: 3028, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn link_account() -> Weight {
+		Weight::from_parts(156_826_000, 106_686)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did AccountDids (r:1 w:1)
+	/// Proof: Did AccountDids (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidRecords (r:1 w:0)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did LinkedAccounts (r:1 w:1)
+	/// Proof: Did LinkedAccounts (max_values: None, max_size: Some(553), added: 3028, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `a` is `[1, 16]`.
+	fn unlink_account(a: u32) -> Weight {
+		Weight::from_parts(62_408_000, 103_664)
+			.saturating_add(Weight::from_parts(424_000, 0).saturating_mul(a.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did AllowedServiceTypes (r:1 w:0)
+	/// Proof: Did AllowedServiceTypes (max_values: Some(1), max_size: Some(8257), added: 8752, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `s` is `[1, 16]`.
+	fn update_service(s: u32) -> Weight {
+		Weight::from_parts(130_764_000, 109_851)
+			.saturating_add(Weight::from_parts(3_722_000, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did AllowedServiceTypes (r:0 w:1)
+	/// Proof: Did AllowedServiceTypes (max_values: Some(1), max_size: Some(8257), added: 8752, mode: MaxEncodedLen)
+	/// The range of component `t` is `[1, 32]`.
+	fn set_allowed_service_types(t: u32) -> Weight {
+		Weight::from_parts(19_624_000, 0)
+			.saturating_add(Weight::from_parts(624_000, 0).saturating_mul(t.into()))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	fn dispatch_as_did() -> Weight {
+		Weight::from_parts(129_436_000, 97_949)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Did DidRecords (r:2 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// Storage: Did KeyToDid (r:0 w:16)
+	/// Proof: Did KeyToDid (max_values: None, max_size: Some(2650), added: 5125, mode: MaxEncodedLen)
+	/// The range of component `k` is `[0, 16]`.
+	fn migrate_did_v1(k: u32) -> Weight {
+		Weight::from_parts(80_000_000, 95_430)
+			.saturating_add(Weight::from_parts(8_000_000, 0).saturating_mul(k.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(k.into())))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did DidTombstones (r:1 w:0)
+	/// Proof: Did DidTombstones (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:0 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// Storage: Did KeyToDid (r:0 w:1)
+	/// Proof: Did KeyToDid (max_values: None, max_size: Some(2650), added: 5125, mode: MaxEncodedLen)
+	fn create_did() -> Weight {
+		Weight::from_parts(122_408_000, 103_586)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	/// Storage: Did DidRecords (r:2 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// Storage: Did ExpiringDids (r:0 w:1)
+	/// Proof: Did ExpiringDids (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: Did KeyToDid (r:0 w:2)
+	/// Proof: Did KeyToDid (max_values: None, max_size: Some(2650), added: 5125, mode: MaxEncodedLen)
+	/// The range of component `k` is `[1, 15]`.
+	fn add_key(k: u32) -> Weight {
+		Weight::from_parts(79_608_000, 199_044)
+			.saturating_add(Weight::from_parts(2_936_000, 0).saturating_mul(k.into()))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `k` is `[2, 16]`.
+	fn revoke_key(k: u32) -> Weight {
+		Weight::from_parts(63_740_000, 101_099)
+			.saturating_add(Weight::from_parts(2_804_000, 0).saturating_mul(k.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `k` is `[1, 16]`.
+	fn deactivate_did(k: u32) -> Weight {
+		Weight::from_parts(61_842_000, 101_099)
+			.saturating_add(Weight::from_parts(2_790_000, 0).saturating_mul(k.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did AllowedServiceTypes (r:1 w:0)
+	/// Proof: Did AllowedServiceTypes (max_values: Some(1), max_size: Some(8257), added: 8752, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `s` is `[0, 15]`.
+	fn add_service(s: u32) -> Weight {
+		Weight::from_parts(132_214_000, 109_851)
+			.saturating_add(Weight::from_parts(3_788_000, 0).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `s` is `[1, 16]`.
+	fn remove_service(s: u32) -> Weight {
+		Weight::from_parts(113_624_000, 101_099)
+			.saturating_add(Weight::from_parts(3_692_000, 0).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `m` is `[0, 15]`.
+	fn set_metadata(m: u32) -> Weight {
+		Weight::from_parts(115_928_000, 101_099)
+			.saturating_add(Weight::from_parts(636_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `m` is `[1, 16]`.
+	fn remove_metadata(m: u32) -> Weight {
+		Weight::from_parts(112_410_000, 101_099)
+			.saturating_add(Weight::from_parts(618_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// Storage: Did ExpiringDids (r:0 w:1)
+	/// Proof: Did ExpiringDids (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: Did KeyToDid (r:0 w:2)
+	/// Proof: Did KeyToDid (max_values: None, max_size: Some(2650), added: 5125, mode: MaxEncodedLen)
+	/// The range of component `k` is `[2, 15]`.
+	fn rotate_key(k: u32) -> Weight {
+		Weight::from_parts(73_496_000, 103_614)
+			.saturating_add(Weight::from_parts(2_948_000, 0).saturating_mul(k.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `k` is `[2, 16]`.
+	fn update_roles(k: u32) -> Weight {
+		Weight::from_parts(62_910_000, 101_099)
+			.saturating_add(Weight::from_parts(2_796_000, 0).saturating_mul(k.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `c` is `[1, 7]`.
+	fn add_controller(c: u32) -> Weight {
+		Weight::from_parts(115_380_000, 101_099)
+			.saturating_add(Weight::from_parts(142_000, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `c` is `[2, 8]`.
+	fn remove_controller(c: u32) -> Weight {
+		Weight::from_parts(113_466_000, 101_099)
+			.saturating_add(Weight::from_parts(132_000, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `c` is `[1, 8]`.
+	fn set_threshold(c: u32) -> Weight {
+		Weight::from_parts(112_208_000, 101_099)
+			.saturating_add(Weight::from_parts(116_000, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:0)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did Proposals (r:7 w:8)
+	/// Proof: Did Proposals (max_values: None, max_size: Some(21607), added: 24082, mode: MaxEncodedLen)
+	/// Storage: Did NextProposalId (r:1 w:1)
+	/// Proof: Did NextProposalId (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: System Account (r:8 w:8)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `p` is `[0, 7]`.
+	fn propose_operation(p: u32) -> Weight {
+		Weight::from_parts(49_632_000, 100_552)
+			.saturating_add(Weight::from_parts(19_746_000, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(p.into())))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(p.into())))
+			.saturating_add(Weight::from_parts(0, 26_685).saturating_mul(p.into()))
+	}
+	/// Storage: Did DidRecords (r:1 w:0)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did Proposals (r:1 w:1)
+	/// Proof: Did Proposals (max_values: None, max_size: Some(21607), added: 24082, mode: MaxEncodedLen)
+	/// The range of component `c` is `[3, 8]`.
+	fn approve_proposal(c: u32) -> Weight {
+		Weight::from_parts(39_084_000, 119_512)
+			.saturating_add(Weight::from_parts(192_000, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: Did Proposals (r:1 w:1)
+	/// Proof: Did Proposals (max_values: None, max_size: Some(21607), added: 24082, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn cancel_proposal() -> Weight {
+		Weight::from_parts(42_754_000, 26_685)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did Recoveries (r:1 w:1)
+	/// Proof: Did Recoveries (max_values: None, max_size: Some(43128), added: 45603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `g` is `[1, 8]`.
+	fn set_recovery(g: u32) -> Weight {
+		Weight::from_parts(116_438_000, 146_702)
+			.saturating_add(Weight::from_parts(366_000, 0).saturating_mul(g.into()))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:0)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did Recoveries (r:1 w:1)
+	/// Proof: Did Recoveries (max_values: None, max_size: Some(43128), added: 45603, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `k` is `[1, 15]`.
+	fn initiate_recovery(k: u32) -> Weight {
+		Weight::from_parts(73_804_000, 143_636)
+			.saturating_add(Weight::from_parts(3_024_000, 0).saturating_mul(k.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: Did DidRecords (r:2 w:0)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did Recoveries (r:1 w:1)
+	/// Proof: Did Recoveries (max_values: None, max_size: Some(43128), added: 45603, mode: MaxEncodedLen)
+	/// The range of component `g` is `[2, 8]`.
+	fn vouch_recovery(g: u32) -> Weight {
+		Weight::from_parts(54_930_000, 236_463)
+			.saturating_add(Weight::from_parts(2_208_000, 0).saturating_mul(g.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:0)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did Recoveries (r:1 w:1)
+	/// Proof: Did Recoveries (max_values: None, max_size: Some(43128), added: 45603, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn cancel_recovery() -> Weight {
+		Weight::from_parts(59_742_000, 143_636)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: Did Recoveries (r:1 w:1)
+	/// Proof: Did Recoveries (max_values: None, max_size: Some(43128), added: 45603, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// Storage: Did KeyToDid (r:0 w:30)
+	/// Proof: Did KeyToDid (max_values: None, max_size: Some(2650), added: 5125, mode: MaxEncodedLen)
+	/// The range of component `k` is `[1, 15]`.
+	fn complete_recovery(k: u32) -> Weight {
+		Weight::from_parts(82_672_000, 149_305)
+			.saturating_add(Weight::from_parts(3_466_000, 0).saturating_mul(k.into()))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(k.into())))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1000)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:17 w:17)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did Proposals (r:8 w:8)
+	/// Proof: Did Proposals (max_values: None, max_size: Some(21607), added: 24082, mode: MaxEncodedLen)
+	/// Storage: Did NextProposalId (r:0 w:1)
+	/// Proof: Did NextProposalId (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: Did Recoveries (r:1 w:1)
+	/// Proof: Did Recoveries (max_values: None, max_size: Some(43128), added: 45603, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did ExpiringDids (r:0 w:1)
+	/// Proof: Did ExpiringDids (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: Did LinkedAccounts (r:1 w:1)
+	/// Proof: Did LinkedAccounts (max_values: None, max_size: Some(553), added: 3028, mode: MaxEncodedLen)
+	/// Storage: Did AccountDids (r:16 w:16)
+	/// Proof: Did AccountDids (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did KeyToDid (r:0 w:16)
+	/// Proof: Did KeyToDid (max_values: None, max_size: Some(2650), added: 5125, mode: MaxEncodedLen)
+	/// Storage: Did DidTombstones (r:0 w:1)
+	/// Proof: Did DidTombstones (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// The range of component `h` is `[4, 1000]`.
+	fn delete_did(h: u32) -> Weight {
+		Weight::from_parts(821_024_000, 433_247)
+			.saturating_add(Weight::from_parts(2_536_000, 0).saturating_mul(h.into()))
+			.saturating_add(RocksDbWeight::get().reads(47_u64))
+			.saturating_add(RocksDbWeight::get().writes(65_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(h.into())))
+	}
+	/// Storage: Did ExpiryCursor (r:1 w:1)
+	/// Proof: Did ExpiryCursor (max_values: Some(1), max_size: Some(32), added: 527, mode: MaxEncodedLen)
+	/// Storage: Did ExpiringDids (r:1 w:1)
+	/// Proof: Did ExpiringDids (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `k` is `[1, 15]`.
+	fn expire_keys(k: u32) -> Weight {
+		Weight::from_parts(25_294_000, 98_975)
+			.saturating_add(Weight::from_parts(2_842_000, 0).saturating_mul(k.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `k` is `[2, 16]`.
+	fn emergency_revoke_key(k: u32) -> Weight {
+		Weight::from_parts(96_654_000, 98_452)
+			.saturating_add(Weight::from_parts(2_778_000, 0).saturating_mul(k.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: Did AccountDids (r:1 w:1)
+	/// Proof: Did AccountDids (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did LinkedAccounts (r:1 w:1)
+	/// Proof: Did LinkedAccounts (max_values: None, max_size: Some(553), added: 3028, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn link_account() -> Weight {
+		Weight::from_parts(156_826_000, 106_686)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did AccountDids (r:1 w:1)
+	/// Proof: Did AccountDids (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidRecords (r:1 w:0)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did LinkedAccounts (r:1 w:1)
+	/// Proof: Did LinkedAccounts (max_values: None, max_size: Some(553), added: 3028, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `a` is `[1, 16]`.
+	fn unlink_account(a: u32) -> Weight {
+		Weight::from_parts(62_408_000, 103_664)
+			.saturating_add(Weight::from_parts(424_000, 0).saturating_mul(a.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Did AllowedServiceTypes (r:1 w:0)
+	/// Proof: Did AllowedServiceTypes (max_values: Some(1), max_size: Some(8257), added: 8752, mode: MaxEncodedLen)
+	/// Storage: Did DidDeposits (r:1 w:1)
+	/// Proof: Did DidDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// The range of component `s` is `[1, 16]`.
+	fn update_service(s: u32) -> Weight {
+		Weight::from_parts(130_764_000, 109_851)
+			.saturating_add(Weight::from_parts(3_722_000, 0).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: Did AllowedServiceTypes (r:0 w:1)
+	/// Proof: Did AllowedServiceTypes (max_values: Some(1), max_size: Some(8257), added: 8752, mode: MaxEncodedLen)
+	/// The range of component `t` is `[1, 32]`.
+	fn set_allowed_service_types(t: u32) -> Weight {
+		Weight::from_parts(19_624_000, 0)
+			.saturating_add(Weight::from_parts(624_000, 0).saturating_mul(t.into()))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	fn dispatch_as_did() -> Weight {
+		Weight::from_parts(129_436_000, 97_949)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: Did DidRecords (r:2 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92955), added: 95430, mode: MaxEncodedLen)
+	/// Storage: Did DidHistory (r:0 w:1)
+	/// Proof: Did DidHistory (max_values: None, max_size: Some(39), added: 2514, mode: MaxEncodedLen)
+	/// Storage: Did KeyToDid (r:0 w:16)
+	/// Proof: Did KeyToDid (max_values: None, max_size: Some(2650), added: 5125, mode: MaxEncodedLen)
+	/// The range of component `k` is `[0, 16]`.
+	fn migrate_did_v1(k: u32) -> Weight {
+		Weight::from_parts(80_000_000, 95_430)
+			.saturating_add(Weight::from_parts(8_000_000, 0).saturating_mul(k.into()))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(k.into())))
+	}
+}
@@ -0,0 +1,129 @@
+//! Benchmarks of the name registry pallet.
+
+use super::*;
+use frame_benchmarking::v2::*;
+use frame_support::traits::{Currency, EnsureOrigin, Get};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+use sp_std::vec;
+
+fn fund<T: Config>(who: &T::AccountId) {
+    T::Currency::make_free_balance_be(who, BalanceOf::<T>::max_value() / 2u32.into());
+}
+
+/// A valid name of `len` characters.
+fn name_of_len(len: u32) -> Vec<u8> {
+    vec![b'a'; len as usize]
+}
+
+/// Claims the longest possible name for a new DID and returns both.
+fn claim<T: Config>(caller: &T::AccountId) -> (Vec<u8>, Vec<u8>) {
+    let name = name_of_len(T::MaxNameLength::get());
+    let did = T::DidVerifier::create_benchmark_did();
+    let payload = Pallet::<T>::claim_payload(&name, &did);
+    let signature = T::DidVerifier::sign_for_benchmark(&did, &payload);
+    Pallet::<T>::claim_name(
+        RawOrigin::Signed(caller.clone()).into(),
+        name.clone(),
+        did.clone(),
+        signature,
+    )
+    .expect("a fresh name can be claimed");
+    (name, did)
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    #[benchmark]
+    fn claim_name(n: Linear<{ T::MinNameLength::get() }, { T::MaxNameLength::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+        let name = name_of_len(n);
+        let did = T::DidVerifier::create_benchmark_did();
+        let payload = Pallet::<T>::claim_payload(&name, &did);
+        let signature = T::DidVerifier::sign_for_benchmark(&did, &payload);
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            name.clone(),
+            did.clone(),
+            signature,
+        );
+
+        assert_eq!(Pallet::<T>::get_did_by_name(name), Some(did));
+    }
+
+    #[benchmark]
+    fn transfer_name() {
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+        let (name, did) = claim::<T>(&caller);
+        let new_did = T::DidVerifier::create_benchmark_did();
+        let payload = Pallet::<T>::transfer_payload(&name, &new_did);
+        let signature = T::DidVerifier::sign_for_benchmark(&did, &payload);
+        let new_signature = T::DidVerifier::sign_for_benchmark(&new_did, &payload);
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            name.clone(),
+            new_did.clone(),
+            signature,
+            new_signature,
+        );
+
+        assert_eq!(Pallet::<T>::get_did_by_name(name), Some(new_did));
+    }
+
+    #[benchmark]
+    fn release_name() {
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+        let (name, did) = claim::<T>(&caller);
+        let payload = Pallet::<T>::release_payload(&name);
+        let signature = T::DidVerifier::sign_for_benchmark(&did, &payload);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), name.clone(), signature);
+
+        assert_eq!(Pallet::<T>::get_did_by_name(name), None);
+    }
+
+    #[benchmark]
+    fn reserve_name() -> Result<(), BenchmarkError> {
+        let origin =
+            T::ReserveOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let name = name_of_len(T::MaxNameLength::get());
+        let did = T::DidVerifier::create_benchmark_did();
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, name.clone(), Some(did));
+
+        assert!(ReservedNames::<T>::contains_key(
+            Pallet::<T>::bounded_name(&name).unwrap()
+        ));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn unreserve_name() -> Result<(), BenchmarkError> {
+        let origin =
+            T::ReserveOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let name = name_of_len(T::MaxNameLength::get());
+        Pallet::<T>::reserve_name(origin.clone(), name.clone(), None)
+            .expect("a free name can be reserved");
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, name.clone());
+
+        assert!(!ReservedNames::<T>::contains_key(
+            Pallet::<T>::bounded_name(&name).unwrap()
+        ));
+        Ok(())
+    }
+
+    impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
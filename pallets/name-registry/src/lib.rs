@@ -0,0 +1,492 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Registry of human-readable names for DIDs, resolved as `did:qsb:name:<name>`.
+//!
+//! A name points at a single DID and a DID holds at most one name. Claiming, transferring
+//! and releasing a name are authorised by signatures of the DIDs involved, while the
+//! account submitting the call pays the storage deposit. The name of a DID is released
+//! when the DID is deleted.
+
+use did::{Deposit, DidSignatureVerifier, KeyRole, OnDidDeleted};
+use frame_support::ensure;
+pub use pallet::*;
+use sp_std::vec::Vec;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+    use frame_support::{
+        dispatch::DispatchResult,
+        pallet_prelude::*,
+        traits::{Currency, ReservableCurrency},
+    };
+    use frame_system::pallet_prelude::{BlockNumberFor, OriginFor};
+    #[cfg(feature = "try-runtime")]
+    use sp_runtime::TryRuntimeError;
+    use sp_runtime::{
+        traits::{Saturating, Zero},
+        SaturatedConversion,
+    };
+    /// Prefix of the DID URLs names are resolved under.
+    pub const NAME_PREFIX: &[u8] = b"did:qsb:name:";
+    const DID_PREFIX: &[u8] = b"did:qsb:";
+    const NAME_CLAIM_PREFIX: &[u8] = b"QSB_NAME_CLAIM";
+    const NAME_TRANSFER_PREFIX: &[u8] = b"QSB_NAME_TRANSFER";
+    const NAME_RELEASE_PREFIX: &[u8] = b"QSB_NAME_RELEASE";
+    /// Role of the DID keys that sign claims, transfers and releases of names.
+    const NAME_KEY_ROLE: KeyRole = KeyRole::CapabilityInvocation;
+
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+    pub type NameOf<T> = BoundedVec<u8, <T as Config>::MaxNameLength>;
+    pub type DidOf<T> = BoundedVec<u8, <T as Config>::MaxDidLength>;
+
+    /// Storage version of the pallet, bumped by any migration of its storage.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
+    #[cfg_attr(
+        feature = "std",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(bound = "")
+    )]
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct NameRecord<T: Config> {
+        pub did: DidOf<T>,
+        pub deposit: Deposit<T::AccountId, BalanceOf<T>>,
+        /// Block at which the name was claimed or last transferred.
+        pub registered: BlockNumberFor<T>,
+    }
+
+    #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        /// Verifies the signatures of the DIDs a name is claimed for or moved between.
+        type DidVerifier: DidSignatureVerifier;
+        /// Currency the deposit of a name is reserved in.
+        type Currency: ReservableCurrency<Self::AccountId>;
+        /// Deposit reserved for every registered name.
+        #[pallet::constant]
+        type DepositPerItem: Get<BalanceOf<Self>>;
+        /// Deposit reserved per byte of a name and the DID it points at.
+        #[pallet::constant]
+        type DepositPerByte: Get<BalanceOf<Self>>;
+        /// Minimum length of a name.
+        #[pallet::constant]
+        type MinNameLength: Get<u32>;
+        /// Maximum length of a name.
+        #[pallet::constant]
+        type MaxNameLength: Get<u32>;
+        /// Maximum length of the DID a name points at.
+        #[pallet::constant]
+        type MaxDidLength: Get<u32>;
+        /// Origin allowed to reserve names, such as governance.
+        type ReserveOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+        /// Weight information for the extrinsics of this pallet.
+        type WeightInfo: WeightInfo;
+    }
+
+    #[pallet::storage]
+    pub(super) type Names<T: Config> =
+        StorageMap<_, Blake2_128Concat, NameOf<T>, NameRecord<T>, OptionQuery>;
+
+    /// Name held by each DID.
+    #[pallet::storage]
+    pub(super) type NameOfDid<T: Config> =
+        StorageMap<_, Blake2_128Concat, DidOf<T>, NameOf<T>, OptionQuery>;
+
+    /// Names only the given DID can claim, or nobody when no DID is given.
+    #[pallet::storage]
+    pub(super) type ReservedNames<T: Config> =
+        StorageMap<_, Blake2_128Concat, NameOf<T>, Option<DidOf<T>>, OptionQuery>;
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_n: BlockNumberFor<T>) -> Result<(), TryRuntimeError> {
+            Self::do_try_state()
+        }
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The name is too short or too long, or is not made of lowercase letters, digits and
+        /// single hyphens between them.
+        InvalidName,
+        /// The DID is not a `did:qsb` DID or is too long.
+        InvalidDid,
+        NameTaken,
+        NameNotFound,
+        /// The name is reserved for another DID, or for nobody.
+        NameReserved,
+        NameNotReserved,
+        /// The DID already holds a name.
+        DidAlreadyNamed,
+        /// The name already points at the given DID.
+        SameDid,
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        NameClaimed {
+            name: Vec<u8>,
+            did: Vec<u8>,
+        },
+        NameTransferred {
+            name: Vec<u8>,
+            from: Vec<u8>,
+            to: Vec<u8>,
+        },
+        NameReleased {
+            name: Vec<u8>,
+            did: Vec<u8>,
+        },
+        NameReserved {
+            name: Vec<u8>,
+            did: Option<Vec<u8>>,
+        },
+        NameUnreserved {
+            name: Vec<u8>,
+        },
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Claims `name` for `did`, which signs [`Self::claim_payload`]. The caller pays the
+        /// deposit.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::WeightInfo::claim_name(name.len() as u32))]
+        pub fn claim_name(
+            origin: OriginFor<T>,
+            name: Vec<u8>,
+            did: Vec<u8>,
+            did_signature: Vec<u8>,
+        ) -> DispatchResult {
+            let who = frame_system::ensure_signed(origin)?;
+            let bounded_name = Self::bounded_name(&name)?;
+            let bounded_did = Self::bounded_did(&did)?;
+            ensure!(
+                !Names::<T>::contains_key(&bounded_name),
+                Error::<T>::NameTaken
+            );
+            if let Some(reserved_for) = ReservedNames::<T>::get(&bounded_name) {
+                ensure!(
+                    reserved_for.as_ref() == Some(&bounded_did),
+                    Error::<T>::NameReserved
+                );
+            }
+            ensure!(
+                !NameOfDid::<T>::contains_key(&bounded_did),
+                Error::<T>::DidAlreadyNamed
+            );
+
+            let payload = Self::claim_payload(&name, &did);
+            T::DidVerifier::verify_did_signature(&did, NAME_KEY_ROLE, &payload, &did_signature)?;
+
+            let deposit = Deposit::reserve::<T::Currency>(who, Self::deposit_for(&name, &did))?;
+            ReservedNames::<T>::remove(&bounded_name);
+            NameOfDid::<T>::insert(&bounded_did, &bounded_name);
+            Names::<T>::insert(
+                &bounded_name,
+                NameRecord {
+                    did: bounded_did,
+                    deposit,
+                    registered: frame_system::Pallet::<T>::block_number(),
+                },
+            );
+            Self::deposit_event(Event::NameClaimed { name, did });
+            Ok(())
+        }
+
+        /// Moves `name` to `new_did`. Both the current and the new DID sign
+        /// [`Self::transfer_payload`], so a name cannot be pushed onto a DID that does not
+        /// want it. The deposit moves to the caller.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::transfer_name())]
+        pub fn transfer_name(
+            origin: OriginFor<T>,
+            name: Vec<u8>,
+            new_did: Vec<u8>,
+            did_signature: Vec<u8>,
+            new_did_signature: Vec<u8>,
+        ) -> DispatchResult {
+            let who = frame_system::ensure_signed(origin)?;
+            let bounded_name = Self::bounded_name(&name)?;
+            let bounded_new_did = Self::bounded_did(&new_did)?;
+            let record = Names::<T>::get(&bounded_name).ok_or(Error::<T>::NameNotFound)?;
+            ensure!(record.did != bounded_new_did, Error::<T>::SameDid);
+            ensure!(
+                !NameOfDid::<T>::contains_key(&bounded_new_did),
+                Error::<T>::DidAlreadyNamed
+            );
+
+            let payload = Self::transfer_payload(&name, &new_did);
+            T::DidVerifier::verify_did_signature(
+                &record.did,
+                NAME_KEY_ROLE,
+                &payload,
+                &did_signature,
+            )?;
+            T::DidVerifier::verify_did_signature(
+                &new_did,
+                NAME_KEY_ROLE,
+                &payload,
+                &new_did_signature,
+            )?;
+
+            let amount = record.deposit.amount;
+            let deposit = Deposit::reserve::<T::Currency>(who, amount)?;
+            record.deposit.release::<T::Currency>();
+            NameOfDid::<T>::remove(&record.did);
+            NameOfDid::<T>::insert(&bounded_new_did, &bounded_name);
+            Names::<T>::insert(
+                &bounded_name,
+                NameRecord {
+                    did: bounded_new_did,
+                    deposit,
+                    registered: frame_system::Pallet::<T>::block_number(),
+                },
+            );
+            Self::deposit_event(Event::NameTransferred {
+                name,
+                from: record.did.into_inner(),
+                to: new_did,
+            });
+            Ok(())
+        }
+
+        /// Gives up `name`, signed by the DID holding it, and releases its deposit.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::release_name())]
+        pub fn release_name(
+            origin: OriginFor<T>,
+            name: Vec<u8>,
+            did_signature: Vec<u8>,
+        ) -> DispatchResult {
+            let _ = frame_system::ensure_signed(origin)?;
+            let bounded_name = Self::bounded_name(&name)?;
+            let record = Names::<T>::get(&bounded_name).ok_or(Error::<T>::NameNotFound)?;
+            let payload = Self::release_payload(&name);
+            T::DidVerifier::verify_did_signature(
+                &record.did,
+                NAME_KEY_ROLE,
+                &payload,
+                &did_signature,
+            )?;
+
+            Names::<T>::remove(&bounded_name);
+            NameOfDid::<T>::remove(&record.did);
+            record.deposit.release::<T::Currency>();
+            Self::deposit_event(Event::NameReleased {
+                name,
+                did: record.did.into_inner(),
+            });
+            Ok(())
+        }
+
+        /// Keeps `name` from being claimed by anyone but `did`, or by anyone at all when no
+        /// DID is given. Names already claimed cannot be reserved.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::reserve_name())]
+        pub fn reserve_name(
+            origin: OriginFor<T>,
+            name: Vec<u8>,
+            did: Option<Vec<u8>>,
+        ) -> DispatchResult {
+            T::ReserveOrigin::ensure_origin(origin)?;
+            let bounded_name = Self::bounded_name(&name)?;
+            let bounded_did = did.as_deref().map(Self::bounded_did).transpose()?;
+            ensure!(
+                !Names::<T>::contains_key(&bounded_name),
+                Error::<T>::NameTaken
+            );
+
+            ReservedNames::<T>::insert(&bounded_name, bounded_did);
+            Self::deposit_event(Event::NameReserved { name, did });
+            Ok(())
+        }
+
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::unreserve_name())]
+        pub fn unreserve_name(origin: OriginFor<T>, name: Vec<u8>) -> DispatchResult {
+            T::ReserveOrigin::ensure_origin(origin)?;
+            let bounded_name = Self::bounded_name(&name)?;
+            ensure!(
+                ReservedNames::<T>::take(&bounded_name).is_some(),
+                Error::<T>::NameNotReserved
+            );
+
+            Self::deposit_event(Event::NameUnreserved { name });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Checks the length and character set of `name`. Names are made of lowercase ASCII
+        /// letters and digits, optionally separated by single hyphens.
+        pub(crate) fn bounded_name(name: &[u8]) -> Result<NameOf<T>, Error<T>> {
+            ensure!(
+                name.len() >= T::MinNameLength::get() as usize,
+                Error::<T>::InvalidName
+            );
+            ensure!(
+                name.iter()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || *c == b'-'),
+                Error::<T>::InvalidName
+            );
+            ensure!(
+                name.first() != Some(&b'-')
+                    && name.last() != Some(&b'-')
+                    && !name.windows(2).any(|pair| pair == b"--"),
+                Error::<T>::InvalidName
+            );
+            name.to_vec()
+                .try_into()
+                .map_err(|_| Error::<T>::InvalidName)
+        }
+
+        fn bounded_did(did: &[u8]) -> Result<DidOf<T>, Error<T>> {
+            ensure!(
+                did.starts_with(DID_PREFIX) && !did.starts_with(NAME_PREFIX),
+                Error::<T>::InvalidDid
+            );
+            did.to_vec().try_into().map_err(|_| Error::<T>::InvalidDid)
+        }
+
+        /// Deposit required to keep `name` pointing at `did` in state.
+        fn deposit_for(name: &[u8], did: &[u8]) -> BalanceOf<T> {
+            let bytes = name.len().saturating_add(did.len()).saturated_into::<u32>();
+            T::DepositPerItem::get()
+                .saturating_add(T::DepositPerByte::get().saturating_mul(bytes.into()))
+        }
+
+        /// Payload `did` signs in `claim_name`, before the DID nonce is appended.
+        pub fn claim_payload(name: &[u8], did: &[u8]) -> Vec<u8> {
+            Self::payload(NAME_CLAIM_PREFIX, &[name, did])
+        }
+
+        /// Payload both DIDs sign in `transfer_name`, before their nonces are appended.
+        pub fn transfer_payload(name: &[u8], new_did: &[u8]) -> Vec<u8> {
+            Self::payload(NAME_TRANSFER_PREFIX, &[name, new_did])
+        }
+
+        /// Payload the DID holding a name signs in `release_name`, before the nonce.
+        pub fn release_payload(name: &[u8]) -> Vec<u8> {
+            Self::payload(NAME_RELEASE_PREFIX, &[name])
+        }
+
+        /// `prefix` and the genesis hash followed by `parts`, each preceded by its length so
+        /// that the boundary between a name and a DID cannot be shifted.
+        fn payload(prefix: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+            let genesis = frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero());
+            let mut payload = Vec::with_capacity(
+                prefix.len()
+                    + genesis.as_ref().len()
+                    + parts.iter().map(|part| part.len() + 4).sum::<usize>(),
+            );
+            payload.extend_from_slice(prefix);
+            payload.extend_from_slice(genesis.as_ref());
+            for part in parts {
+                payload.extend_from_slice(&(part.len() as u32).to_le_bytes());
+                payload.extend_from_slice(part);
+            }
+            payload
+        }
+
+        /// The DID `name` points at. The name may be given with or without the
+        /// `did:qsb:name:` prefix.
+        pub fn get_did_by_name(name: Vec<u8>) -> Option<Vec<u8>> {
+            let name = name.strip_prefix(NAME_PREFIX).unwrap_or(&name);
+            let name: NameOf<T> = name.to_vec().try_into().ok()?;
+            Names::<T>::get(name).map(|record| record.did.into_inner())
+        }
+
+        /// The name held by `did`.
+        pub fn get_name_of_did(did: Vec<u8>) -> Option<Vec<u8>> {
+            let did: DidOf<T> = did.try_into().ok()?;
+            NameOfDid::<T>::get(did).map(|name| name.into_inner())
+        }
+    }
+
+    /// Releases the name held by a deleted DID, and its deposit.
+    impl<T: Config> OnDidDeleted for Pallet<T> {
+        fn on_did_deleted(did: &[u8]) {
+            let Ok(did) = DidOf::<T>::try_from(did.to_vec()) else {
+                return;
+            };
+            let Some(name) = NameOfDid::<T>::take(&did) else {
+                return;
+            };
+            if let Some(record) = Names::<T>::take(&name) {
+                record.deposit.release::<T::Currency>();
+            }
+            Self::deposit_event(Event::NameReleased {
+                name: name.into_inner(),
+                did: did.into_inner(),
+            });
+        }
+
+        /// Bounded by the weight of `release_name`, which also checks a signature.
+        fn weight() -> Weight {
+            T::WeightInfo::release_name()
+        }
+    }
+
+    #[cfg(feature = "try-runtime")]
+    impl<T: Config> Pallet<T> {
+        /// Checks the invariants of the pallet storage:
+        ///
+        /// - every registered name is well formed and points at a `did:qsb` DID;
+        /// - [`Names`] and [`NameOfDid`] describe the same names;
+        /// - registered names are not reserved.
+        pub(crate) fn do_try_state() -> Result<(), TryRuntimeError> {
+            for (name, record) in Names::<T>::iter() {
+                ensure!(Self::bounded_name(&name).is_ok(), "malformed name");
+                ensure!(
+                    Self::bounded_did(&record.did).is_ok(),
+                    "name pointing at a malformed DID"
+                );
+                ensure!(
+                    NameOfDid::<T>::get(&record.did).as_ref() == Some(&name),
+                    "name missing from the name of its DID"
+                );
+                ensure!(
+                    !ReservedNames::<T>::contains_key(&name),
+                    "registered name still reserved"
+                );
+            }
+
+            ensure!(
+                NameOfDid::<T>::iter().all(|(did, name)| {
+                    Names::<T>::get(&name).map_or(false, |record| record.did == did)
+                }),
+                "name of a DID not registered for it"
+            );
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Test runtime of the name registry pallet.
+
+use crate as name_registry;
+use frame_support::{
+    construct_runtime,
+    traits::{ConstU32, ConstU64},
+};
+
+pub use did_test_utils::*;
+
+construct_runtime!(
+    pub struct Test {
+        System: frame_system,
+        Balances: pallet_balances,
+        Timestamp: pallet_timestamp,
+        Did: did,
+        NameRegistry: name_registry,
+    }
+);
+
+impl_identity_test_runtime!(Test, NameRegistry);
+
+impl name_registry::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type DidVerifier = Did;
+    type Currency = Balances;
+    type DepositPerItem = ConstU64<1_000>;
+    type DepositPerByte = ConstU64<10>;
+    type MinNameLength = ConstU32<3>;
+    type MaxNameLength = ConstU32<64>;
+    type MaxDidLength = ConstU32<128>;
+    type ReserveOrigin = frame_system::EnsureRoot<u64>;
+    type WeightInfo = ();
+}
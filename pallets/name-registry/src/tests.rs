@@ -0,0 +1,275 @@
+use crate::{mock::*, pallet::ReservedNames, Error};
+use frame_support::{assert_noop, assert_ok, dispatch::DispatchResult, traits::ReservableCurrency};
+use sp_core::ed25519;
+use sp_runtime::DispatchError;
+
+const NAME: &[u8] = b"alice";
+
+/// Signs `payload` for `did` with its next nonce, the way DID signatures are checked.
+fn sign_for(pair: &ed25519::Pair, did: &[u8], payload: &[u8]) -> Vec<u8> {
+    sign(pair, payload, Did::get_did(did.to_vec()).unwrap().nonce)
+}
+
+fn claim(who: u64, name: &[u8], pair: &ed25519::Pair, did: &[u8]) -> DispatchResult {
+    let signature = sign_for(pair, did, &NameRegistry::claim_payload(name, did));
+    NameRegistry::claim_name(
+        RuntimeOrigin::signed(who),
+        name.to_vec(),
+        did.to_vec(),
+        signature,
+    )
+}
+
+fn deposit_for(name: &[u8], did: &[u8]) -> u64 {
+    1_000 + 10 * (name.len() + did.len()) as u64
+}
+
+#[test]
+fn claim_name_reserves_the_deposit_of_the_caller() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let did = create_did(ALICE, &key);
+
+        assert_ok!(claim(BOB, NAME, &key, &did));
+
+        assert_eq!(
+            NameRegistry::get_did_by_name(NAME.to_vec()),
+            Some(did.clone())
+        );
+        assert_eq!(
+            NameRegistry::get_did_by_name(b"did:qsb:name:alice".to_vec()),
+            Some(did.clone())
+        );
+        assert_eq!(
+            NameRegistry::get_name_of_did(did.clone()),
+            Some(NAME.to_vec())
+        );
+        assert_eq!(Balances::reserved_balance(BOB), deposit_for(NAME, &did));
+
+        let other_key = pair(2);
+        let other_did = create_did(ALICE, &other_key);
+        assert_noop!(
+            claim(BOB, NAME, &other_key, &other_did),
+            Error::<Test>::NameTaken
+        );
+        assert_noop!(
+            claim(BOB, b"alice2", &key, &did),
+            Error::<Test>::DidAlreadyNamed
+        );
+    });
+}
+
+#[test]
+fn claim_name_checks_the_name_and_the_did() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let did = create_did(ALICE, &key);
+
+        for name in [
+            &b"ab"[..],
+            b"Abc",
+            b"a--b",
+            b"-ab",
+            b"ab-",
+            b"a.b",
+            &[b'a'; 65],
+        ] {
+            assert_noop!(claim(BOB, name, &key, &did), Error::<Test>::InvalidName);
+        }
+        assert_noop!(
+            NameRegistry::claim_name(
+                RuntimeOrigin::signed(BOB),
+                NAME.to_vec(),
+                b"did:qsb:name:bob".to_vec(),
+                vec![0; 64],
+            ),
+            Error::<Test>::InvalidDid
+        );
+    });
+}
+
+#[test]
+fn claim_name_rejects_bad_and_replayed_signatures() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let did = create_did(ALICE, &key);
+        let payload = NameRegistry::claim_payload(NAME, &did);
+
+        let wrong_key = pair(2);
+        assert_noop!(
+            NameRegistry::claim_name(
+                RuntimeOrigin::signed(BOB),
+                NAME.to_vec(),
+                did.clone(),
+                sign_for(&wrong_key, &did, &payload),
+            ),
+            did::Error::<Test>::InvalidSignature
+        );
+        let other_name = NameRegistry::claim_payload(b"bob", &did);
+        assert_noop!(
+            NameRegistry::claim_name(
+                RuntimeOrigin::signed(BOB),
+                NAME.to_vec(),
+                did.clone(),
+                sign_for(&key, &did, &other_name),
+            ),
+            did::Error::<Test>::InvalidSignature
+        );
+
+        let signature = sign_for(&key, &did, &payload);
+        assert_ok!(NameRegistry::claim_name(
+            RuntimeOrigin::signed(BOB),
+            NAME.to_vec(),
+            did.clone(),
+            signature.clone(),
+        ));
+        let release = sign_for(&key, &did, &NameRegistry::release_payload(NAME));
+        assert_ok!(NameRegistry::release_name(
+            RuntimeOrigin::signed(BOB),
+            NAME.to_vec(),
+            release
+        ));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+
+        assert_noop!(
+            NameRegistry::claim_name(RuntimeOrigin::signed(BOB), NAME.to_vec(), did, signature),
+            did::Error::<Test>::InvalidSignature
+        );
+    });
+}
+
+#[test]
+fn reserved_names_are_claimed_only_by_their_did() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let did = create_did(ALICE, &key);
+        let other_key = pair(2);
+        let other_did = create_did(ALICE, &other_key);
+
+        assert_noop!(
+            NameRegistry::reserve_name(RuntimeOrigin::signed(ALICE), NAME.to_vec(), None),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(NameRegistry::reserve_name(
+            RuntimeOrigin::root(),
+            b"admin".to_vec(),
+            None
+        ));
+        assert_noop!(
+            claim(BOB, b"admin", &key, &did),
+            Error::<Test>::NameReserved
+        );
+
+        assert_ok!(NameRegistry::reserve_name(
+            RuntimeOrigin::root(),
+            NAME.to_vec(),
+            Some(did.clone())
+        ));
+        assert_noop!(
+            claim(BOB, NAME, &other_key, &other_did),
+            Error::<Test>::NameReserved
+        );
+        assert_ok!(claim(BOB, NAME, &key, &did));
+        assert!(!ReservedNames::<Test>::contains_key(NAME.to_vec()));
+        assert_noop!(
+            NameRegistry::reserve_name(RuntimeOrigin::root(), NAME.to_vec(), None),
+            Error::<Test>::NameTaken
+        );
+
+        assert_ok!(NameRegistry::unreserve_name(
+            RuntimeOrigin::root(),
+            b"admin".to_vec()
+        ));
+        assert_noop!(
+            NameRegistry::unreserve_name(RuntimeOrigin::root(), b"admin".to_vec()),
+            Error::<Test>::NameNotReserved
+        );
+    });
+}
+
+#[test]
+fn transfer_name_needs_the_signatures_of_both_dids() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let did = create_did(ALICE, &key);
+        let new_key = pair(2);
+        let new_did = create_did(ALICE, &new_key);
+        assert_ok!(claim(BOB, NAME, &key, &did));
+
+        let payload = NameRegistry::transfer_payload(NAME, &new_did);
+        assert_noop!(
+            NameRegistry::transfer_name(
+                RuntimeOrigin::signed(CHARLIE),
+                NAME.to_vec(),
+                did.clone(),
+                sign_for(&key, &did, &payload),
+                sign_for(&key, &did, &payload),
+            ),
+            Error::<Test>::SameDid
+        );
+        assert_noop!(
+            NameRegistry::transfer_name(
+                RuntimeOrigin::signed(CHARLIE),
+                NAME.to_vec(),
+                new_did.clone(),
+                sign_for(&new_key, &did, &payload),
+                sign_for(&new_key, &new_did, &payload),
+            ),
+            did::Error::<Test>::InvalidSignature
+        );
+
+        assert_ok!(NameRegistry::transfer_name(
+            RuntimeOrigin::signed(CHARLIE),
+            NAME.to_vec(),
+            new_did.clone(),
+            sign_for(&key, &did, &payload),
+            sign_for(&new_key, &new_did, &payload),
+        ));
+        assert_eq!(
+            NameRegistry::get_did_by_name(NAME.to_vec()),
+            Some(new_did.clone())
+        );
+        assert_eq!(NameRegistry::get_name_of_did(new_did), Some(NAME.to_vec()));
+        assert_eq!(NameRegistry::get_name_of_did(did.clone()), None);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::reserved_balance(CHARLIE), deposit_for(NAME, &did));
+    });
+}
+
+#[test]
+fn deleting_a_did_releases_its_name() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let did = create_did(ALICE, &key);
+        assert_ok!(claim(BOB, NAME, &key, &did));
+
+        assert_ok!(Did::deactivate_did(
+            RuntimeOrigin::signed(ALICE),
+            did.clone()
+        ));
+        let history_len = Did::get_did(did.clone()).unwrap().history_len;
+        assert_ok!(Did::delete_did(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            history_len
+        ));
+
+        assert_eq!(NameRegistry::get_did_by_name(NAME.to_vec()), None);
+        assert_eq!(NameRegistry::get_name_of_did(did), None);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+    });
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn try_state_catches_names_missing_from_their_did() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let did = create_did(&key);
+        assert_ok!(claim(BOB, NAME, &key, &did));
+        assert_ok!(NameRegistry::do_try_state());
+
+        crate::pallet::NameOfDid::<Test>::remove(did);
+        assert!(NameRegistry::do_try_state().is_err());
+    });
+}
@@ -0,0 +1,168 @@
+//! Weights for name_registry
+//!
+//! PLACEHOLDER WEIGHTS, NOT BENCHMARK OUTPUT. No run of `benchmark pallet` produced these
+//! figures. They are rough estimates from the benchmarks in `benchmarking.rs` and the
+//! `MaxEncodedLen` of the stored records, deliberately overestimated to stay safe until
+//! real figures exist: the execution times are twice the estimate, and every call is
+//! charged one read and one write more than the storage it is known to touch. The layout
+//! follows the CLI's so that its output can replace this file as is. Generate real
+//! weights on reference hardware with:
+//!
+//! ./target/release/qsb-node benchmark pallet --chain dev --pallet name_registry --extrinsic '*' \
+//!     --steps 50 --repeat 20 --output pallets/name-registry/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for name_registry.
+pub trait WeightInfo {
+	fn claim_name(n: u32) -> Weight;
+	fn transfer_name() -> Weight;
+	fn release_name() -> Weight;
+	fn reserve_name() -> Weight;
+	fn unreserve_name() -> Weight;
+}
+
+/// Weights for name_registry using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: NameRegistry Names (r:1 w:1)
+	/// Proof: NameRegistry Names (max_values: None, max_size: Some(263), added: 2738, mode: MaxEncodedLen)
+	/// Storage: NameRegistry ReservedNames (r:1 w:1)
+	/// Proof: NameRegistry ReservedNames (max_values: None, max_size: Some(212), added: 2687, mode: MaxEncodedLen)
+	/// Storage: NameRegistry NameOfDid (r:1 w:1)
+	/// Proof: NameRegistry NameOfDid (max_values: None, max_size: Some(211), added: 2686, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92951), added: 95426, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `n` is `[3, 64]`.
+	fn claim_name(n: u32) -> Weight {
+		Weight::from_parts(148_210_000, 108_659)
+			.saturating_add(Weight::from_parts(6_824, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	/// Storage: NameRegistry Names (r:1 w:1)
+	/// Proof: NameRegistry Names (max_values: None, max_size: Some(263), added: 2738, mode: MaxEncodedLen)
+	/// Storage: NameRegistry NameOfDid (r:1 w:2)
+	/// Proof: NameRegistry NameOfDid (max_values: None, max_size: Some(211), added: 2686, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: Did DidRecords (r:2 w:2)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92951), added: 95426, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn transfer_name() -> Weight {
+		Weight::from_parts(243_476_000, 201_398)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+	}
+	/// Storage: NameRegistry Names (r:1 w:1)
+	/// Proof: NameRegistry Names (max_values: None, max_size: Some(263), added: 2738, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92951), added: 95426, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: NameRegistry NameOfDid (r:0 w:1)
+	/// Proof: NameRegistry NameOfDid (max_values: None, max_size: Some(211), added: 2686, mode: MaxEncodedLen)
+	fn release_name() -> Weight {
+		Weight::from_parts(137_808_000, 103_286)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: NameRegistry Names (r:1 w:0)
+	/// Proof: NameRegistry Names (max_values: None, max_size: Some(263), added: 2738, mode: MaxEncodedLen)
+	/// Storage: NameRegistry ReservedNames (r:0 w:1)
+	/// Proof: NameRegistry ReservedNames (max_values: None, max_size: Some(212), added: 2687, mode: MaxEncodedLen)
+	fn reserve_name() -> Weight {
+		Weight::from_parts(28_652_000, 2_738)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: NameRegistry ReservedNames (r:1 w:1)
+	/// Proof: NameRegistry ReservedNames (max_values: None, max_size: Some(212), added: 2687, mode: MaxEncodedLen)
+	fn unreserve_name() -> Weight {
+		Weight::from_parts(27_742_000, 2_687)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: NameRegistry Names (r:1 w:1)
+	/// Proof: NameRegistry Names (max_values: None, max_size: Some(263), added: 2738, mode: MaxEncodedLen)
+	/// Storage: NameRegistry ReservedNames (r:1 w:1)
+	/// Proof: NameRegistry ReservedNames (max_values: None, max_size: Some(212), added: 2687, mode: MaxEncodedLen)
+	/// Storage: NameRegistry NameOfDid (r:1 w:1)
+	/// Proof: NameRegistry NameOfDid (max_values: None, max_size: Some(211), added: 2686, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92951), added: 95426, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `n` is `[3, 64]`.
+	fn claim_name(n: u32) -> Weight {
+		Weight::from_parts(148_210_000, 108_659)
+			.saturating_add(Weight::from_parts(6_824, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	/// Storage: NameRegistry Names (r:1 w:1)
+	/// Proof: NameRegistry Names (max_values: None, max_size: Some(263), added: 2738, mode: MaxEncodedLen)
+	/// Storage: NameRegistry NameOfDid (r:1 w:2)
+	/// Proof: NameRegistry NameOfDid (max_values: None, max_size: Some(211), added: 2686, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: Did DidRecords (r:2 w:2)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92951), added: 95426, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn transfer_name() -> Weight {
+		Weight::from_parts(243_476_000, 201_398)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+	}
+	/// Storage: NameRegistry Names (r:1 w:1)
+	/// Proof: NameRegistry Names (max_values: None, max_size: Some(263), added: 2738, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: Did DidRecords (r:1 w:1)
+	/// Proof: Did DidRecords (max_values: None, max_size: Some(92951), added: 95426, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: NameRegistry NameOfDid (r:0 w:1)
+	/// Proof: NameRegistry NameOfDid (max_values: None, max_size: Some(211), added: 2686, mode: MaxEncodedLen)
+	fn release_name() -> Weight {
+		Weight::from_parts(137_808_000, 103_286)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: NameRegistry Names (r:1 w:0)
+	/// Proof: NameRegistry Names (max_values: None, max_size: Some(263), added: 2738, mode: MaxEncodedLen)
+	/// Storage: NameRegistry ReservedNames (r:0 w:1)
+	/// Proof: NameRegistry ReservedNames (max_values: None, max_size: Some(212), added: 2687, mode: MaxEncodedLen)
+	fn reserve_name() -> Weight {
+		Weight::from_parts(28_652_000, 2_738)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: NameRegistry ReservedNames (r:1 w:1)
+	/// Proof: NameRegistry ReservedNames (max_values: None, max_size: Some(212), added: 2687, mode: MaxEncodedLen)
+	fn unreserve_name() -> Weight {
+		Weight::from_parts(27_742_000, 2_687)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+}
@@ -0,0 +1,95 @@
+//! Benchmarks of the revocation list pallet.
+
+use super::*;
+use frame_benchmarking::v2::*;
+use frame_support::traits::{Currency, EnsureOrigin, Get};
+use sp_runtime::traits::Bounded;
+use sp_std::vec;
+
+fn fund<T: Config>(who: &T::AccountId) {
+    T::Currency::make_free_balance_be(who, BalanceOf::<T>::max_value() / 2u32.into());
+}
+
+fn list_nonce<T: Config>() -> Vec<u8> {
+    vec![b'n'; T::MaxListNonceLength::get() as usize]
+}
+
+/// An issuer origin whose submitter can pay deposits, along with the issuer DID.
+fn issuer<T: Config>() -> Result<(T::RuntimeOrigin, Vec<u8>), BenchmarkError> {
+    let origin =
+        T::IssuerOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+    let issuer =
+        T::IssuerOrigin::ensure_origin(origin.clone()).map_err(|_| BenchmarkError::Weightless)?;
+    fund::<T>(&issuer.submitter);
+    Ok((origin, issuer.did()))
+}
+
+/// Creates a status list of `bytes` bitmap bytes issued by the DID of `origin` and returns
+/// its id.
+fn create_list<T: Config>(origin: T::RuntimeOrigin, issuer_did: &[u8], bytes: u32) -> [u8; 32] {
+    let list_nonce = list_nonce::<T>();
+    Pallet::<T>::create_status_list(origin, list_nonce.clone(), bytes * 8)
+        .expect("a fresh status list can be created");
+    Pallet::<T>::status_list_id_from_parts(issuer_did, &list_nonce)
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    #[benchmark]
+    fn create_status_list(
+        b: Linear<1, { T::MaxBitmapBytes::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let (origin, issuer_did) = issuer::<T>()?;
+        let list_nonce = list_nonce::<T>();
+        let status_list_id = Pallet::<T>::status_list_id_from_parts(&issuer_did, &list_nonce);
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, list_nonce, b * 8);
+
+        assert_eq!(
+            StatusLists::<T>::get(status_list_id).unwrap().bitmap.len() as u32,
+            b
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_status(b: Linear<1, { T::MaxBitmapBytes::get() }>) -> Result<(), BenchmarkError> {
+        let (origin, issuer_did) = issuer::<T>()?;
+        let status_list_id = create_list::<T>(origin.clone(), &issuer_did, b);
+        let status_index = b * 8 - 1;
+
+        #[extrinsic_call]
+        _(
+            origin as T::RuntimeOrigin,
+            Pallet::<T>::status_list_string_from_id(&status_list_id),
+            status_index,
+            true,
+        );
+
+        let list = StatusLists::<T>::get(status_list_id).unwrap();
+        assert_eq!(list.bitmap[b as usize - 1], 0b1000_0000);
+        Ok(())
+    }
+
+    #[benchmark]
+    fn remove_status_list() -> Result<(), BenchmarkError> {
+        let (origin, issuer_did) = issuer::<T>()?;
+        let status_list_id =
+            create_list::<T>(origin.clone(), &issuer_did, T::MaxBitmapBytes::get());
+
+        #[extrinsic_call]
+        _(
+            origin as T::RuntimeOrigin,
+            Pallet::<T>::status_list_string_from_id(&status_list_id),
+        );
+
+        assert!(!StatusLists::<T>::contains_key(status_list_id));
+        assert!(!StatusListDeposits::<T>::contains_key(status_list_id));
+        Ok(())
+    }
+
+    impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -1,16 +1,36 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use did::{Deposit, DidOrigin};
 use frame_support::ensure;
 pub use pallet::*;
 use sp_std::vec::Vec;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod migrations;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+pub use weights::WeightInfo;
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
-    use frame_support::{dispatch::DispatchResult, pallet_prelude::*};
+    use frame_support::{
+        dispatch::DispatchResult,
+        pallet_prelude::*,
+        traits::{Currency, ReservableCurrency},
+    };
     use frame_system::pallet_prelude::{BlockNumberFor, OriginFor};
     use sp_io::hashing::blake2_256;
-    use sp_runtime::traits::Zero;
+    #[cfg(feature = "try-runtime")]
+    use sp_runtime::TryRuntimeError;
+    use sp_runtime::{
+        traits::{Saturating, Zero},
+        SaturatedConversion,
+    };
     use sp_std::vec;
 
     const STATUSLIST_PREFIX: &[u8] = b"did:qsb:statuslist:";
@@ -18,27 +38,121 @@ pub mod pallet {
     const STATUSLIST_MATERIAL_PREFIX: &[u8] = b"QSB_STATUSLIST";
     const MIN_LIST_NONCE_BYTES: usize = 16;
 
-    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
-    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
-    pub struct StatusList {
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    /// Storage version of the pallet, bumped by the migrations in [`crate::migrations`].
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+    #[cfg_attr(
+        feature = "std",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(bound = "")
+    )]
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct StatusList<T: Config> {
         pub version: u64,
-        pub issuer_did: Vec<u8>,
-        pub list_nonce: Vec<u8>,
-        pub bitmap: Vec<u8>,
+        pub issuer_did: BoundedVec<u8, T::MaxDidLength>,
+        pub list_nonce: BoundedVec<u8, T::MaxListNonceLength>,
+        pub bitmap: BoundedVec<u8, T::MaxBitmapBytes>,
     }
 
     #[pallet::pallet]
-    #[pallet::without_storage_info]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     #[pallet::config]
     pub trait Config: frame_system::Config {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        /// Origin of an issuer DID, such as [`did::EnsureDid`] with the `AssertionMethod`
+        /// role. The account that submitted the call pays the deposit of a status list.
+        type IssuerOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = DidOrigin<Self::AccountId>>;
+        /// Currency the storage deposit of a status list is reserved in.
+        type Currency: ReservableCurrency<Self::AccountId>;
+        /// Deposit reserved for every stored status list.
+        #[pallet::constant]
+        type DepositPerItem: Get<BalanceOf<Self>>;
+        /// Deposit reserved per byte of an encoded status list.
+        #[pallet::constant]
+        type DepositPerByte: Get<BalanceOf<Self>>;
+        /// Maximum length of the issuer DID of a status list.
+        #[pallet::constant]
+        type MaxDidLength: Get<u32>;
+        /// Maximum length of the nonce a status list id is derived from.
+        #[pallet::constant]
+        type MaxListNonceLength: Get<u32>;
+        /// Maximum size of a status list bitmap, in bytes.
+        #[pallet::constant]
+        type MaxBitmapBytes: Get<u32>;
+        /// Weight information for the extrinsics of this pallet.
+        type WeightInfo: WeightInfo;
     }
 
     #[pallet::storage]
     pub(super) type StatusLists<T: Config> =
-        StorageMap<_, Twox64Concat, [u8; 32], StatusList, OptionQuery>;
+        StorageMap<_, Twox64Concat, [u8; 32], StatusList<T>, OptionQuery>;
+
+    /// Storage deposit held for each status list. Entries registered before deposits were
+    /// introduced have none.
+    #[pallet::storage]
+    pub(super) type StatusListDeposits<T: Config> =
+        StorageMap<_, Twox64Concat, [u8; 32], Deposit<T::AccountId, BalanceOf<T>>, OptionQuery>;
+
+    /// Identifiers of removed status lists, kept so that a list cannot be created again under
+    /// the identifier of a removed one with every entry unrevoked.
+    #[pallet::storage]
+    pub(super) type RemovedStatusLists<T: Config> =
+        StorageMap<_, Twox64Concat, [u8; 32], (), OptionQuery>;
+
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        /// Identifier of the chain, such as the id of its chain spec, that the identifiers of
+        /// genesis status lists are derived from so that they differ from chain to chain.
+        #[serde(default)]
+        pub chain_id: Vec<u8>,
+        /// Status lists created at genesis with every entry unrevoked, as
+        /// `(issuer_did, list_nonce, list_length)`. Their identifiers are derived with
+        /// [`Pallet::genesis_status_list_id`]. Their deposit is waived: they hold none, and
+        /// none is charged when entries are revoked.
+        pub status_lists: Vec<(Vec<u8>, Vec<u8>, u32)>,
+        #[serde(skip)]
+        pub _config: PhantomData<T>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            for (issuer_did, list_nonce, list_length) in &self.status_lists {
+                Pallet::<T>::create_genesis_status_list(
+                    &self.chain_id,
+                    issuer_did,
+                    list_nonce,
+                    *list_length,
+                )
+                .expect("genesis status lists are valid");
+            }
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_n: BlockNumberFor<T>) -> Result<(), TryRuntimeError> {
+            Self::do_try_state()
+        }
+    }
 
     #[pallet::error]
     pub enum Error<T> {
@@ -48,6 +162,10 @@ pub mod pallet {
         InvalidListNonce,
         IssuerMismatch,
         StatusIndexOutOfBounds,
+        IssuerDidTooLong,
+        ListTooLong,
+        /// The status list was removed, and its identifier cannot be used again.
+        StatusListRemoved,
     }
 
     #[pallet::event]
@@ -62,43 +180,66 @@ pub mod pallet {
             status_index: u32,
             revoked: bool,
         },
+        StatusListRemoved {
+            status_list_id: Vec<u8>,
+            issuer_did: Vec<u8>,
+        },
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
+        /// Creates a status list issued by the DID of the origin, which issuers obtain by
+        /// submitting this call through `dispatch_as_did` of the DID pallet.
         #[pallet::call_index(0)]
-        #[pallet::weight({0})]
+        #[pallet::weight(T::WeightInfo::create_status_list(list_length.saturating_add(7) / 8))]
         pub fn create_status_list(
             origin: OriginFor<T>,
-            issuer_did: Vec<u8>,
             list_nonce: Vec<u8>,
             list_length: u32,
-            _did_signature: Vec<u8>,
         ) -> DispatchResult {
-            let _ = frame_system::ensure_signed(origin)?;
+            let issuer = T::IssuerOrigin::ensure_origin(origin)?;
+            let issuer_did = issuer.did();
             ensure!(
                 list_nonce.len() >= MIN_LIST_NONCE_BYTES,
                 Error::<T>::InvalidListNonce
             );
+            let bitmap_len = list_length
+                .checked_add(7)
+                .ok_or(Error::<T>::StatusIndexOutOfBounds)?
+                / 8;
+            ensure!(
+                bitmap_len <= T::MaxBitmapBytes::get(),
+                Error::<T>::ListTooLong
+            );
+            let bounded_issuer: BoundedVec<u8, T::MaxDidLength> = issuer_did
+                .clone()
+                .try_into()
+                .map_err(|_| Error::<T>::IssuerDidTooLong)?;
+            let bounded_nonce: BoundedVec<u8, T::MaxListNonceLength> = list_nonce
+                .clone()
+                .try_into()
+                .map_err(|_| Error::<T>::InvalidListNonce)?;
 
             let status_list_id = Self::status_list_id_from_parts(&issuer_did, &list_nonce);
             ensure!(
                 !StatusLists::<T>::contains_key(status_list_id),
                 Error::<T>::StatusListAlreadyExists
             );
+            ensure!(
+                !RemovedStatusLists::<T>::contains_key(status_list_id),
+                Error::<T>::StatusListRemoved
+            );
 
-            let bitmap_len = list_length
-                .checked_add(7)
-                .ok_or(Error::<T>::StatusIndexOutOfBounds)?
-                / 8;
-            let bitmap = vec![0u8; bitmap_len as usize];
             let record = StatusList {
                 version: 0,
-                issuer_did: issuer_did.clone(),
-                list_nonce,
-                bitmap,
+                issuer_did: bounded_issuer,
+                list_nonce: bounded_nonce,
+                bitmap: BoundedVec::truncate_from(vec![0u8; bitmap_len as usize]),
             };
 
+            let deposit =
+                Deposit::reserve::<T::Currency>(issuer.submitter, Self::deposit_for(&record))?;
+            StatusListDeposits::<T>::insert(status_list_id, deposit);
             StatusLists::<T>::insert(status_list_id, record);
             let status_list_id_full = Self::status_list_string_from_id(&status_list_id);
             Self::deposit_event(Event::StatusListCreated {
@@ -109,16 +250,14 @@ pub mod pallet {
         }
 
         #[pallet::call_index(1)]
-        #[pallet::weight({0})]
+        #[pallet::weight(T::WeightInfo::set_status(T::MaxBitmapBytes::get()))]
         pub fn set_status(
             origin: OriginFor<T>,
             status_list_id: Vec<u8>,
-            issuer_did: Vec<u8>,
             status_index: u32,
             revoked: bool,
-            _did_signature: Vec<u8>,
         ) -> DispatchResult {
-            let _ = frame_system::ensure_signed(origin)?;
+            let issuer_did = T::IssuerOrigin::ensure_origin(origin)?.did();
             let status_list_id = Self::decode_status_list_id(&status_list_id)?;
             let status_list_id_full = Self::status_list_string_from_id(&status_list_id);
 
@@ -158,10 +297,97 @@ pub mod pallet {
             });
             Ok(())
         }
+
+        /// Removes a status list issued by the DID of the origin, once no credential needs
+        /// its statuses anymore, and releases its deposit to the account that paid it. Status
+        /// lists created at genesis hold no deposit. The identifier stays reserved, so the
+        /// list cannot be created again.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::remove_status_list())]
+        pub fn remove_status_list(origin: OriginFor<T>, status_list_id: Vec<u8>) -> DispatchResult {
+            let issuer_did = T::IssuerOrigin::ensure_origin(origin)?.did();
+            let status_list_id = Self::decode_status_list_id(&status_list_id)?;
+            let record =
+                StatusLists::<T>::get(status_list_id).ok_or(Error::<T>::StatusListNotFound)?;
+            ensure!(record.issuer_did == issuer_did, Error::<T>::IssuerMismatch);
+
+            StatusLists::<T>::remove(status_list_id);
+            RemovedStatusLists::<T>::insert(status_list_id, ());
+            if let Some(deposit) = StatusListDeposits::<T>::take(status_list_id) {
+                deposit.release::<T::Currency>();
+            }
+
+            Self::deposit_event(Event::StatusListRemoved {
+                status_list_id: Self::status_list_string_from_id(&status_list_id),
+                issuer_did,
+            });
+            Ok(())
+        }
     }
 
     impl<T: Config> Pallet<T> {
-        fn status_list_id_from_parts(issuer_did: &[u8], list_nonce: &[u8]) -> [u8; 32] {
+        /// Deposit required to keep `record` in state.
+        fn deposit_for(record: &StatusList<T>) -> BalanceOf<T> {
+            let bytes = record.encoded_size().saturated_into::<u32>();
+            T::DepositPerItem::get()
+                .saturating_add(T::DepositPerByte::get().saturating_mul(bytes.into()))
+        }
+
+        fn create_genesis_status_list(
+            chain_id: &[u8],
+            issuer_did: &[u8],
+            list_nonce: &[u8],
+            list_length: u32,
+        ) -> DispatchResult {
+            ensure!(
+                list_nonce.len() >= MIN_LIST_NONCE_BYTES,
+                Error::<T>::InvalidListNonce
+            );
+            let bitmap_len = list_length
+                .checked_add(7)
+                .ok_or(Error::<T>::StatusIndexOutOfBounds)?
+                / 8;
+            ensure!(
+                bitmap_len <= T::MaxBitmapBytes::get(),
+                Error::<T>::ListTooLong
+            );
+            let status_list_id = Self::genesis_status_list_id(chain_id, issuer_did, list_nonce);
+            ensure!(
+                !StatusLists::<T>::contains_key(status_list_id),
+                Error::<T>::StatusListAlreadyExists
+            );
+            let record = StatusList {
+                version: 0,
+                issuer_did: issuer_did
+                    .to_vec()
+                    .try_into()
+                    .map_err(|_| Error::<T>::IssuerDidTooLong)?,
+                list_nonce: list_nonce
+                    .to_vec()
+                    .try_into()
+                    .map_err(|_| Error::<T>::InvalidListNonce)?,
+                bitmap: BoundedVec::truncate_from(vec![0u8; bitmap_len as usize]),
+            };
+            StatusLists::<T>::insert(status_list_id, record);
+            Ok(())
+        }
+
+        /// Identifier of a status list created in the genesis block of the chain `chain_id`.
+        /// The genesis hash is not known yet while the genesis state is built, so the chain is
+        /// told apart by `chain_id` instead, which is length-prefixed in the material.
+        pub fn genesis_status_list_id(
+            chain_id: &[u8],
+            issuer_did: &[u8],
+            list_nonce: &[u8],
+        ) -> [u8; 32] {
+            let mut material = STATUSLIST_MATERIAL_PREFIX.to_vec();
+            chain_id.encode_to(&mut material);
+            material.extend_from_slice(issuer_did);
+            material.extend_from_slice(list_nonce);
+            blake2_256(&material)
+        }
+
+        pub(crate) fn status_list_id_from_parts(issuer_did: &[u8], list_nonce: &[u8]) -> [u8; 32] {
             let genesis = frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero());
             let mut material = Vec::with_capacity(
                 STATUSLIST_MATERIAL_PREFIX.len()
@@ -176,7 +402,7 @@ pub mod pallet {
             blake2_256(&material)
         }
 
-        fn status_list_string_from_id(status_list_id: &[u8; 32]) -> Vec<u8> {
+        pub(crate) fn status_list_string_from_id(status_list_id: &[u8; 32]) -> Vec<u8> {
             let status_list_id_b58 = bs58::encode(status_list_id).into_string();
             let mut status_list_id_full =
                 Vec::with_capacity(STATUSLIST_PREFIX.len() + status_list_id_b58.len());
@@ -203,9 +429,36 @@ pub mod pallet {
             Ok(status_list_id)
         }
 
-        pub fn get_status_list(status_list_id: Vec<u8>) -> Result<StatusList, Error<T>> {
+        pub fn get_status_list(status_list_id: Vec<u8>) -> Result<StatusList<T>, Error<T>> {
             let status_list_id = Self::decode_status_list_id(&status_list_id)?;
             StatusLists::<T>::get(status_list_id).ok_or(Error::<T>::StatusListNotFound)
         }
     }
+
+    #[cfg(feature = "try-runtime")]
+    impl<T: Config> Pallet<T> {
+        /// Checks the invariants of the pallet storage:
+        ///
+        /// - every status list names its issuer;
+        /// - deposits only exist for stored status lists;
+        /// - removed status lists are not stored.
+        pub(crate) fn do_try_state() -> Result<(), TryRuntimeError> {
+            for record in StatusLists::<T>::iter_values() {
+                ensure!(
+                    !record.issuer_did.is_empty(),
+                    "status list without an issuer"
+                );
+            }
+
+            ensure!(
+                StatusListDeposits::<T>::iter_keys().all(StatusLists::<T>::contains_key),
+                "deposit held for a missing status list"
+            );
+            ensure!(
+                !RemovedStatusLists::<T>::iter_keys().any(StatusLists::<T>::contains_key),
+                "removed status list still stored"
+            );
+            Ok(())
+        }
+    }
 }
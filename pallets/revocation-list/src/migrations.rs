@@ -0,0 +1,109 @@
+//! Storage migrations of the revocation list pallet.
+
+use crate::{pallet::StatusLists, Config, Pallet, StatusList};
+use codec::Decode;
+#[cfg(feature = "try-runtime")]
+use codec::Encode;
+#[cfg(feature = "try-runtime")]
+use frame_support::ensure;
+use frame_support::{
+    traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+    weights::Weight,
+};
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
+use sp_std::{marker::PhantomData, vec::Vec};
+
+const LOG_TARGET: &str = "runtime::revocation-list";
+
+/// Moves status lists to bounded storage. The encoding is unchanged, so this only drops
+/// lists that exceed the configured bounds and could no longer be decoded.
+pub mod v1 {
+    use super::*;
+
+    #[derive(Decode)]
+    struct OldStatusList {
+        version: u64,
+        issuer_did: Vec<u8>,
+        list_nonce: Vec<u8>,
+        bitmap: Vec<u8>,
+    }
+
+    pub struct MigrateToV1<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain = Pallet::<T>::on_chain_storage_version();
+            if on_chain != 0 {
+                log::info!(
+                    target: LOG_TARGET,
+                    "skipping v1 migration, on-chain storage version is {:?}",
+                    on_chain
+                );
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut translated = 0u64;
+            StatusLists::<T>::translate::<OldStatusList, _>(|status_list_id, old| {
+                translated += 1;
+                let list = migrate_list::<T>(old);
+                if list.is_none() {
+                    log::warn!(
+                        target: LOG_TARGET,
+                        "dropping status list {:?} which exceeds the configured bounds",
+                        status_list_id
+                    );
+                }
+                list
+            });
+
+            StorageVersion::new(1).put::<Pallet<T>>();
+            log::info!(target: LOG_TARGET, "migrated {} status lists to v1", translated);
+            T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+        }
+
+        /// Records the on-chain storage version and the number of stored status lists.
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            let on_chain = Pallet::<T>::on_chain_storage_version();
+            let stored = StatusLists::<T>::iter_keys().count() as u64;
+            Ok((on_chain, stored).encode())
+        }
+
+        /// Checks that the storage version was bumped and that every remaining status list
+        /// decodes. Only the migration itself may drop status lists, those exceeding the bounds.
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let (on_chain, stored): (StorageVersion, u64) =
+                Decode::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+            ensure!(
+                Pallet::<T>::on_chain_storage_version() == 1,
+                "storage version not bumped to 1"
+            );
+            let keys = StatusLists::<T>::iter_keys().count() as u64;
+            let decoded = StatusLists::<T>::iter_values().count() as u64;
+            ensure!(
+                decoded == keys,
+                "undecodable status lists after the migration"
+            );
+            if on_chain == 0 {
+                ensure!(keys <= stored, "status lists added by the migration");
+            } else {
+                ensure!(
+                    keys == stored,
+                    "status lists dropped by a skipped migration"
+                );
+            }
+            Pallet::<T>::do_try_state()
+        }
+    }
+
+    fn migrate_list<T: Config>(old: OldStatusList) -> Option<StatusList<T>> {
+        Some(StatusList {
+            version: old.version,
+            issuer_did: old.issuer_did.try_into().ok()?,
+            list_nonce: old.list_nonce.try_into().ok()?,
+            bitmap: old.bitmap.try_into().ok()?,
+        })
+    }
+}
@@ -0,0 +1,37 @@
+//! Test runtime of the revocation list pallet.
+
+use crate as revocation_list;
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{ConstU32, ConstU64},
+};
+
+pub use did_test_utils::*;
+
+parameter_types! {
+    pub const IssuerRole: did::KeyRole = did::KeyRole::AssertionMethod;
+}
+
+construct_runtime!(
+    pub struct Test {
+        System: frame_system,
+        Balances: pallet_balances,
+        Timestamp: pallet_timestamp,
+        Did: did,
+        RevocationList: revocation_list,
+    }
+);
+
+impl_identity_test_runtime!(Test, ());
+
+impl revocation_list::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type IssuerOrigin = did::EnsureDid<Test, IssuerRole>;
+    type Currency = Balances;
+    type DepositPerItem = ConstU64<1_000>;
+    type DepositPerByte = ConstU64<10>;
+    type MaxDidLength = ConstU32<128>;
+    type MaxListNonceLength = ConstU32<64>;
+    type MaxBitmapBytes = ConstU32<{ 16 * 1024 }>;
+    type WeightInfo = ();
+}
@@ -0,0 +1,204 @@
+use crate::{
+    migrations::v1::MigrateToV1,
+    mock::*,
+    pallet::{RemovedStatusLists, StatusListDeposits, StatusLists},
+    Error,
+};
+use did::{DidOrigin, KeyRole};
+use frame_support::{
+    assert_noop, assert_ok,
+    storage::unhashed,
+    traits::{GetStorageVersion, OnRuntimeUpgrade, ReservableCurrency, StorageVersion},
+};
+
+const LIST_NONCE: &[u8] = b"0123456789abcdef";
+
+fn issuer(did_id: [u8; 32]) -> RuntimeOrigin {
+    DidOrigin {
+        did_id,
+        network: None,
+        role: KeyRole::AssertionMethod,
+        submitter: ALICE,
+    }
+    .into()
+}
+
+fn issuer_did(did_id: [u8; 32]) -> Vec<u8> {
+    DidOrigin {
+        did_id,
+        network: None,
+        role: KeyRole::AssertionMethod,
+        submitter: ALICE,
+    }
+    .did()
+}
+
+/// Creates a list of `list_length` entries issued by `did_id` and returns its id.
+fn create_list(did_id: [u8; 32], list_length: u32) -> Vec<u8> {
+    assert_ok!(RevocationList::create_status_list(
+        issuer(did_id),
+        LIST_NONCE.to_vec(),
+        list_length
+    ));
+    let id = RevocationList::status_list_id_from_parts(&issuer_did(did_id), LIST_NONCE);
+    RevocationList::status_list_string_from_id(&id)
+}
+
+#[test]
+fn create_status_list_reserves_the_deposit_of_the_submitter() {
+    new_test_ext().execute_with(|| {
+        let id = create_list([1; 32], 100);
+
+        let list = RevocationList::get_status_list(id).unwrap();
+        assert_eq!(list.bitmap.len(), 13);
+        assert!(list.bitmap.iter().all(|byte| *byte == 0));
+        assert_eq!(list.issuer_did.to_vec(), issuer_did([1; 32]));
+        let list_id = RevocationList::status_list_id_from_parts(&issuer_did([1; 32]), LIST_NONCE);
+        let deposit = StatusListDeposits::<Test>::get(list_id).unwrap();
+        assert_eq!(deposit.owner, ALICE);
+        assert_eq!(Balances::reserved_balance(ALICE), deposit.amount);
+
+        assert_noop!(
+            RevocationList::create_status_list(issuer([1; 32]), LIST_NONCE.to_vec(), 100),
+            Error::<Test>::StatusListAlreadyExists
+        );
+    });
+}
+
+#[test]
+fn removing_a_status_list_releases_its_deposit() {
+    new_test_ext().execute_with(|| {
+        let id = create_list([1; 32], 100);
+        let list_id = RevocationList::status_list_id_from_parts(&issuer_did([1; 32]), LIST_NONCE);
+
+        assert_noop!(
+            RevocationList::remove_status_list(issuer([2; 32]), id.clone()),
+            Error::<Test>::IssuerMismatch
+        );
+        assert_ok!(RevocationList::remove_status_list(
+            issuer([1; 32]),
+            id.clone()
+        ));
+        assert!(!StatusLists::<Test>::contains_key(list_id));
+        assert!(!StatusListDeposits::<Test>::contains_key(list_id));
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+
+        // The list cannot come back with every entry unrevoked.
+        assert!(RemovedStatusLists::<Test>::contains_key(list_id));
+        assert_noop!(
+            RevocationList::create_status_list(issuer([1; 32]), LIST_NONCE.to_vec(), 100),
+            Error::<Test>::StatusListRemoved
+        );
+        assert_noop!(
+            RevocationList::set_status(issuer([1; 32]), id, 3, true),
+            Error::<Test>::StatusListNotFound
+        );
+    });
+}
+
+#[test]
+fn create_status_list_checks_its_nonce_and_length() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            RevocationList::create_status_list(issuer([1; 32]), b"short".to_vec(), 100),
+            Error::<Test>::InvalidListNonce
+        );
+        assert_noop!(
+            RevocationList::create_status_list(
+                issuer([1; 32]),
+                LIST_NONCE.to_vec(),
+                16 * 1024 * 8 + 1
+            ),
+            Error::<Test>::ListTooLong
+        );
+        assert_ok!(RevocationList::create_status_list(
+            issuer([1; 32]),
+            LIST_NONCE.to_vec(),
+            16 * 1024 * 8
+        ));
+    });
+}
+
+#[test]
+fn only_the_issuer_sets_statuses() {
+    new_test_ext().execute_with(|| {
+        let id = create_list([1; 32], 16);
+
+        assert_noop!(
+            RevocationList::set_status(issuer([2; 32]), id.clone(), 3, true),
+            Error::<Test>::IssuerMismatch
+        );
+        assert_noop!(
+            RevocationList::set_status(issuer([1; 32]), id.clone(), 16, true),
+            Error::<Test>::StatusIndexOutOfBounds
+        );
+
+        assert_ok!(RevocationList::set_status(
+            issuer([1; 32]),
+            id.clone(),
+            3,
+            true
+        ));
+        assert_ok!(RevocationList::set_status(
+            issuer([1; 32]),
+            id.clone(),
+            9,
+            true
+        ));
+        let list = RevocationList::get_status_list(id.clone()).unwrap();
+        assert_eq!(list.bitmap.to_vec(), vec![0b0000_1000, 0b0000_0010]);
+        assert_eq!(list.version, 2);
+
+        assert_ok!(RevocationList::set_status(
+            issuer([1; 32]),
+            id.clone(),
+            3,
+            false
+        ));
+        let list = RevocationList::get_status_list(id).unwrap();
+        assert_eq!(list.bitmap.to_vec(), vec![0, 0b0000_0010]);
+    });
+}
+
+#[test]
+fn migration_to_v1_drops_lists_beyond_the_bounds() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<RevocationList>();
+        // Lists in the original layout, encoded like the old unbounded list.
+        let old = |bitmap: Vec<u8>| {
+            (
+                0u64,
+                b"did:qsb:issuer".to_vec(),
+                LIST_NONCE.to_vec(),
+                bitmap,
+            )
+        };
+        unhashed::put(
+            &StatusLists::<Test>::hashed_key_for([1; 32]),
+            &old(vec![0; 2]),
+        );
+        unhashed::put(
+            &StatusLists::<Test>::hashed_key_for([2; 32]),
+            &old(vec![0; 16 * 1024 + 1]),
+        );
+
+        MigrateToV1::<Test>::on_runtime_upgrade();
+
+        assert_eq!(RevocationList::on_chain_storage_version(), 1);
+        assert_eq!(StatusLists::<Test>::get([1; 32]).unwrap().bitmap.len(), 2);
+        assert!(!StatusLists::<Test>::contains_key([2; 32]));
+    });
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn try_state_catches_deposits_of_missing_lists() {
+    new_test_ext().execute_with(|| {
+        create_list([1; 32], 16);
+        assert_ok!(RevocationList::do_try_state());
+
+        let list_id = RevocationList::status_list_id_from_parts(&issuer_did([1; 32]), LIST_NONCE);
+        StatusLists::<Test>::remove(list_id);
+        assert!(RevocationList::do_try_state().is_err());
+    });
+}
@@ -0,0 +1,88 @@
+//! Benchmarks of the schema pallet.
+
+use super::*;
+use frame_benchmarking::v2::*;
+use frame_support::traits::{Currency, EnsureOrigin, Get};
+use sp_runtime::traits::Bounded;
+use sp_std::vec;
+
+/// Largest schema document measured by `register_schema`.
+const MAX_SCHEMA_BYTES: u32 = 64 * 1024;
+
+fn fund<T: Config>(who: &T::AccountId) {
+    T::Currency::make_free_balance_be(who, BalanceOf::<T>::max_value() / 2u32.into());
+}
+
+fn schema_uri<T: Config>() -> Vec<u8> {
+    vec![b'u'; T::MaxUriLength::get() as usize]
+}
+
+/// An issuer origin whose submitter can pay deposits.
+fn issuer<T: Config>() -> Result<T::RuntimeOrigin, BenchmarkError> {
+    let origin =
+        T::IssuerOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+    let issuer =
+        T::IssuerOrigin::ensure_origin(origin.clone()).map_err(|_| BenchmarkError::Weightless)?;
+    fund::<T>(&issuer.submitter);
+    Ok(origin)
+}
+
+/// Registers a schema issued by the DID of `origin` and returns its id.
+fn register<T: Config>(origin: T::RuntimeOrigin) -> [u8; 32] {
+    let schema_json = vec![b's'; MAX_SCHEMA_BYTES as usize];
+    let schema_id = Pallet::<T>::schema_id_from_schema(&schema_json);
+    Pallet::<T>::register_schema(origin, schema_json, schema_uri::<T>())
+        .expect("a fresh schema can be registered");
+    schema_id
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    #[benchmark]
+    fn register_schema(l: Linear<0, MAX_SCHEMA_BYTES>) -> Result<(), BenchmarkError> {
+        let origin = issuer::<T>()?;
+        let schema_json = vec![b's'; l as usize];
+        let schema_id = Pallet::<T>::schema_id_from_schema(&schema_json);
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, schema_json, schema_uri::<T>());
+
+        assert!(Schemas::<T>::contains_key(schema_id));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn deprecate_schema() -> Result<(), BenchmarkError> {
+        let origin = issuer::<T>()?;
+        let schema_id = register::<T>(origin.clone());
+
+        #[extrinsic_call]
+        _(
+            origin as T::RuntimeOrigin,
+            Pallet::<T>::schema_string_from_schema_id(&schema_id),
+        );
+
+        assert!(Schemas::<T>::get(schema_id).unwrap().deprecated);
+        Ok(())
+    }
+
+    #[benchmark]
+    fn remove_schema() -> Result<(), BenchmarkError> {
+        let origin = issuer::<T>()?;
+        let schema_id = register::<T>(origin.clone());
+        let schema_string = Pallet::<T>::schema_string_from_schema_id(&schema_id);
+        Pallet::<T>::deprecate_schema(origin.clone(), schema_string.clone())
+            .expect("the issuer can deprecate its schema");
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, schema_string);
+
+        assert!(!Schemas::<T>::contains_key(schema_id));
+        assert!(!SchemaDeposits::<T>::contains_key(schema_id));
+        Ok(())
+    }
+
+    impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
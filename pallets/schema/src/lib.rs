@@ -1,41 +1,151 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use did::{Deposit, DidOrigin};
 use frame_support::ensure;
 pub use pallet::*;
 use sp_std::vec::Vec;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod migrations;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+pub use weights::WeightInfo;
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
-    use frame_support::{dispatch::DispatchResult, pallet_prelude::*};
+    use frame_support::{
+        dispatch::DispatchResult,
+        pallet_prelude::*,
+        traits::{Currency, ReservableCurrency},
+    };
     use frame_system::pallet_prelude::{BlockNumberFor, OriginFor};
     use sp_io::hashing::blake2_256;
-    use sp_runtime::traits::Zero;
+    #[cfg(feature = "try-runtime")]
+    use sp_runtime::TryRuntimeError;
+    use sp_runtime::{
+        traits::{Saturating, Zero},
+        SaturatedConversion,
+    };
     const SCHEMA_PREFIX: &[u8] = b"did:qsb:schema:";
     const SCHEMA_MATERIAL_PREFIX: &[u8] = b"QSB_SCHEMA";
 
-    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
-    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
-    pub struct SchemaRecord {
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    /// Storage version of the pallet, bumped by the migrations in [`crate::migrations`].
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+    #[cfg_attr(
+        feature = "std",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(bound = "")
+    )]
+    #[derive(
+        CloneNoBound,
+        Encode,
+        Decode,
+        EqNoBound,
+        PartialEqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct SchemaRecord<T: Config> {
         pub version: u64,
         pub deprecated: bool,
-        pub issuer_did: Vec<u8>,
+        pub issuer_did: BoundedVec<u8, T::MaxDidLength>,
         pub schema_hash: [u8; 32],
-        pub schema_uri: Vec<u8>,
+        pub schema_uri: BoundedVec<u8, T::MaxUriLength>,
     }
 
     #[pallet::pallet]
-    #[pallet::without_storage_info]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     #[pallet::config]
     pub trait Config: frame_system::Config {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        /// Origin of an issuer DID, such as [`did::EnsureDid`] with the `AssertionMethod`
+        /// role. The account that submitted the call pays the deposit of a schema.
+        type IssuerOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = DidOrigin<Self::AccountId>>;
+        /// Currency the storage deposit of a schema is reserved in.
+        type Currency: ReservableCurrency<Self::AccountId>;
+        /// Deposit reserved for every stored schema.
+        #[pallet::constant]
+        type DepositPerItem: Get<BalanceOf<Self>>;
+        /// Deposit reserved per byte of an encoded schema.
+        #[pallet::constant]
+        type DepositPerByte: Get<BalanceOf<Self>>;
+        /// Maximum length of the issuer DID of a schema.
+        #[pallet::constant]
+        type MaxDidLength: Get<u32>;
+        /// Maximum length of the URI a schema is published at.
+        #[pallet::constant]
+        type MaxUriLength: Get<u32>;
+        /// Weight information for the extrinsics of this pallet.
+        type WeightInfo: WeightInfo;
     }
 
     #[pallet::storage]
     pub(super) type Schemas<T: Config> =
-        StorageMap<_, Twox64Concat, [u8; 32], SchemaRecord, OptionQuery>;
+        StorageMap<_, Twox64Concat, [u8; 32], SchemaRecord<T>, OptionQuery>;
+
+    /// Storage deposit held for each schema. Entries registered before deposits were
+    /// introduced have none.
+    #[pallet::storage]
+    pub(super) type SchemaDeposits<T: Config> =
+        StorageMap<_, Twox64Concat, [u8; 32], Deposit<T::AccountId, BalanceOf<T>>, OptionQuery>;
+
+    /// Identifiers of removed schemas, kept so that no other issuer can register a schema
+    /// under the identifier that credentials issued against the removed one still name.
+    #[pallet::storage]
+    pub(super) type RemovedSchemas<T: Config> =
+        StorageMap<_, Twox64Concat, [u8; 32], (), OptionQuery>;
+
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        /// Identifier of the chain, such as the id of its chain spec, that the identifiers of
+        /// genesis schemas are derived from so that they differ from chain to chain.
+        #[serde(default)]
+        pub chain_id: Vec<u8>,
+        /// Schemas registered at genesis, as `(schema_json, schema_uri, issuer_did)`. Their
+        /// identifiers are derived with [`Pallet::genesis_schema_id`]. Their deposit is waived:
+        /// they hold none, and none is charged when they change.
+        pub schemas: Vec<(Vec<u8>, Vec<u8>, Vec<u8>)>,
+        #[serde(skip)]
+        pub _config: PhantomData<T>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            for (schema_json, schema_uri, issuer_did) in &self.schemas {
+                Pallet::<T>::register_genesis_schema(
+                    &self.chain_id,
+                    schema_json,
+                    schema_uri,
+                    issuer_did,
+                )
+                .expect("genesis schemas are valid");
+            }
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_n: BlockNumberFor<T>) -> Result<(), TryRuntimeError> {
+            Self::do_try_state()
+        }
+    }
 
     #[pallet::error]
     pub enum Error<T> {
@@ -44,6 +154,12 @@ pub mod pallet {
         SchemaDeprecated,
         InvalidSchemaId,
         IssuerMismatch,
+        IssuerDidTooLong,
+        SchemaUriTooLong,
+        /// Only deprecated schemas can be removed.
+        SchemaNotDeprecated,
+        /// The schema was removed, and its identifier cannot be registered again.
+        SchemaRemoved,
     }
 
     #[pallet::event]
@@ -57,35 +173,56 @@ pub mod pallet {
             schema_id: Vec<u8>,
             issuer_did: Vec<u8>,
         },
+        SchemaRemoved {
+            schema_id: Vec<u8>,
+            issuer_did: Vec<u8>,
+        },
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
+        /// Registers a schema issued by the DID of the origin, which issuers obtain by
+        /// submitting this call through `dispatch_as_did` of the DID pallet.
         #[pallet::call_index(0)]
-        #[pallet::weight({0})]
+        #[pallet::weight(T::WeightInfo::register_schema(schema_json.len() as u32))]
         pub fn register_schema(
             origin: OriginFor<T>,
             schema_json: Vec<u8>,
             schema_uri: Vec<u8>,
-            issuer_did: Vec<u8>,
-            _did_signature: Vec<u8>,
         ) -> DispatchResult {
-            let _ = frame_system::ensure_signed(origin)?;
+            let issuer = T::IssuerOrigin::ensure_origin(origin)?;
+            let issuer_did = issuer.did();
             let schema_id = Self::schema_id_from_schema(&schema_json);
             ensure!(
                 !Schemas::<T>::contains_key(schema_id),
                 Error::<T>::SchemaAlreadyExists
             );
+            ensure!(
+                !RemovedSchemas::<T>::contains_key(schema_id),
+                Error::<T>::SchemaRemoved
+            );
 
             let schema_hash = blake2_256(&schema_json);
+            let bounded_issuer: BoundedVec<u8, T::MaxDidLength> = issuer_did
+                .clone()
+                .try_into()
+                .map_err(|_| Error::<T>::IssuerDidTooLong)?;
+            let bounded_uri: BoundedVec<u8, T::MaxUriLength> = schema_uri
+                .clone()
+                .try_into()
+                .map_err(|_| Error::<T>::SchemaUriTooLong)?;
+
             let record = SchemaRecord {
                 version: 0,
                 deprecated: false,
-                issuer_did: issuer_did.clone(),
+                issuer_did: bounded_issuer,
                 schema_hash,
-                schema_uri,
+                schema_uri: bounded_uri,
             };
 
+            let deposit =
+                Deposit::reserve::<T::Currency>(issuer.submitter, Self::deposit_for(&record))?;
+            SchemaDeposits::<T>::insert(schema_id, deposit);
             Schemas::<T>::insert(schema_id, record);
             let schema_id_full = Self::schema_string_from_schema_id(&schema_id);
             Self::deposit_event(Event::SchemaRegistered {
@@ -96,14 +233,9 @@ pub mod pallet {
         }
 
         #[pallet::call_index(1)]
-        #[pallet::weight({0})]
-        pub fn deprecate_schema(
-            origin: OriginFor<T>,
-            schema_id: Vec<u8>,
-            issuer_did: Vec<u8>,
-            _did_signature: Vec<u8>,
-        ) -> DispatchResult {
-            let _ = frame_system::ensure_signed(origin)?;
+        #[pallet::weight(T::WeightInfo::deprecate_schema())]
+        pub fn deprecate_schema(origin: OriginFor<T>, schema_id: Vec<u8>) -> DispatchResult {
+            let issuer_did = T::IssuerOrigin::ensure_origin(origin)?.did();
             let schema_id = Self::decode_schema_id(&schema_id)?;
             let schema_id_full = Self::schema_string_from_schema_id(&schema_id);
 
@@ -122,10 +254,80 @@ pub mod pallet {
             });
             Ok(())
         }
+
+        /// Removes a deprecated schema issued by the DID of the origin and releases its
+        /// deposit to the account that paid it. Schemas registered at genesis hold no deposit.
+        /// The identifier stays reserved, so the schema cannot be registered again.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::remove_schema())]
+        pub fn remove_schema(origin: OriginFor<T>, schema_id: Vec<u8>) -> DispatchResult {
+            let issuer_did = T::IssuerOrigin::ensure_origin(origin)?.did();
+            let schema_id = Self::decode_schema_id(&schema_id)?;
+            let record = Schemas::<T>::get(schema_id).ok_or(Error::<T>::SchemaNotFound)?;
+            ensure!(record.issuer_did == issuer_did, Error::<T>::IssuerMismatch);
+            ensure!(record.deprecated, Error::<T>::SchemaNotDeprecated);
+
+            Schemas::<T>::remove(schema_id);
+            RemovedSchemas::<T>::insert(schema_id, ());
+            if let Some(deposit) = SchemaDeposits::<T>::take(schema_id) {
+                deposit.release::<T::Currency>();
+            }
+
+            Self::deposit_event(Event::SchemaRemoved {
+                schema_id: Self::schema_string_from_schema_id(&schema_id),
+                issuer_did,
+            });
+            Ok(())
+        }
     }
 
     impl<T: Config> Pallet<T> {
-        fn schema_id_from_schema(schema_json: &[u8]) -> [u8; 32] {
+        /// Deposit required to keep `record` in state.
+        fn deposit_for(record: &SchemaRecord<T>) -> BalanceOf<T> {
+            let bytes = record.encoded_size().saturated_into::<u32>();
+            T::DepositPerItem::get()
+                .saturating_add(T::DepositPerByte::get().saturating_mul(bytes.into()))
+        }
+
+        fn register_genesis_schema(
+            chain_id: &[u8],
+            schema_json: &[u8],
+            schema_uri: &[u8],
+            issuer_did: &[u8],
+        ) -> DispatchResult {
+            let schema_id = Self::genesis_schema_id(chain_id, schema_json);
+            ensure!(
+                !Schemas::<T>::contains_key(schema_id),
+                Error::<T>::SchemaAlreadyExists
+            );
+            let record = SchemaRecord {
+                version: 0,
+                deprecated: false,
+                issuer_did: issuer_did
+                    .to_vec()
+                    .try_into()
+                    .map_err(|_| Error::<T>::IssuerDidTooLong)?,
+                schema_hash: blake2_256(schema_json),
+                schema_uri: schema_uri
+                    .to_vec()
+                    .try_into()
+                    .map_err(|_| Error::<T>::SchemaUriTooLong)?,
+            };
+            Schemas::<T>::insert(schema_id, record);
+            Ok(())
+        }
+
+        /// Identifier of a schema registered in the genesis block of the chain `chain_id`. The
+        /// genesis hash is not known yet while the genesis state is built, so the chain is
+        /// told apart by `chain_id` instead, which is length-prefixed in the material.
+        pub fn genesis_schema_id(chain_id: &[u8], schema_json: &[u8]) -> [u8; 32] {
+            let mut material = SCHEMA_MATERIAL_PREFIX.to_vec();
+            chain_id.encode_to(&mut material);
+            material.extend_from_slice(schema_json);
+            blake2_256(&material)
+        }
+
+        pub(crate) fn schema_id_from_schema(schema_json: &[u8]) -> [u8; 32] {
             let genesis = frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero());
             let mut material = Vec::with_capacity(
                 SCHEMA_MATERIAL_PREFIX.len() + genesis.as_ref().len() + schema_json.len(),
@@ -136,7 +338,7 @@ pub mod pallet {
             blake2_256(&material)
         }
 
-        fn schema_string_from_schema_id(schema_id: &[u8; 32]) -> Vec<u8> {
+        pub(crate) fn schema_string_from_schema_id(schema_id: &[u8; 32]) -> Vec<u8> {
             let schema_id_b58 = bs58::encode(schema_id).into_string();
             let mut schema_id_full = Vec::with_capacity(SCHEMA_PREFIX.len() + schema_id_b58.len());
             schema_id_full.extend_from_slice(SCHEMA_PREFIX);
@@ -160,9 +362,38 @@ pub mod pallet {
             Ok(schema_id)
         }
 
-        pub fn get_schema(schema_id: Vec<u8>) -> Result<SchemaRecord, Error<T>> {
+        pub fn get_schema(schema_id: Vec<u8>) -> Result<SchemaRecord<T>, Error<T>> {
             let schema_id = Self::decode_schema_id(&schema_id)?;
             Schemas::<T>::get(schema_id).ok_or(Error::<T>::SchemaNotFound)
         }
     }
+
+    #[cfg(feature = "try-runtime")]
+    impl<T: Config> Pallet<T> {
+        /// Checks the invariants of the pallet storage:
+        ///
+        /// - every schema names its issuer;
+        /// - a deprecated schema has been updated at least once;
+        /// - deposits only exist for stored schemas;
+        /// - removed schemas are not stored.
+        pub(crate) fn do_try_state() -> Result<(), TryRuntimeError> {
+            for record in Schemas::<T>::iter_values() {
+                ensure!(!record.issuer_did.is_empty(), "schema without an issuer");
+                ensure!(
+                    !record.deprecated || record.version > 0,
+                    "deprecated schema at its initial version"
+                );
+            }
+
+            ensure!(
+                SchemaDeposits::<T>::iter_keys().all(Schemas::<T>::contains_key),
+                "deposit held for a missing schema"
+            );
+            ensure!(
+                !RemovedSchemas::<T>::iter_keys().any(Schemas::<T>::contains_key),
+                "removed schema still stored"
+            );
+            Ok(())
+        }
+    }
 }
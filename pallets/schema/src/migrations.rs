@@ -0,0 +1,105 @@
+//! Storage migrations of the schema pallet.
+
+use crate::{pallet::Schemas, Config, Pallet, SchemaRecord};
+use codec::Decode;
+#[cfg(feature = "try-runtime")]
+use codec::Encode;
+#[cfg(feature = "try-runtime")]
+use frame_support::ensure;
+use frame_support::{
+    traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+    weights::Weight,
+};
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
+use sp_std::{marker::PhantomData, vec::Vec};
+
+const LOG_TARGET: &str = "runtime::schema";
+
+/// Moves schema records to bounded storage. The encoding is unchanged, so this only drops
+/// records that exceed the configured bounds and could no longer be decoded.
+pub mod v1 {
+    use super::*;
+
+    #[derive(Decode)]
+    struct OldSchemaRecord {
+        version: u64,
+        deprecated: bool,
+        issuer_did: Vec<u8>,
+        schema_hash: [u8; 32],
+        schema_uri: Vec<u8>,
+    }
+
+    pub struct MigrateToV1<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain = Pallet::<T>::on_chain_storage_version();
+            if on_chain != 0 {
+                log::info!(
+                    target: LOG_TARGET,
+                    "skipping v1 migration, on-chain storage version is {:?}",
+                    on_chain
+                );
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut translated = 0u64;
+            Schemas::<T>::translate::<OldSchemaRecord, _>(|schema_id, old| {
+                translated += 1;
+                let record = migrate_record::<T>(old);
+                if record.is_none() {
+                    log::warn!(
+                        target: LOG_TARGET,
+                        "dropping schema {:?} which exceeds the configured bounds",
+                        schema_id
+                    );
+                }
+                record
+            });
+
+            StorageVersion::new(1).put::<Pallet<T>>();
+            log::info!(target: LOG_TARGET, "migrated {} schemas to v1", translated);
+            T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+        }
+
+        /// Records the on-chain storage version and the number of stored schemas.
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            let on_chain = Pallet::<T>::on_chain_storage_version();
+            let stored = Schemas::<T>::iter_keys().count() as u64;
+            Ok((on_chain, stored).encode())
+        }
+
+        /// Checks that the storage version was bumped and that every remaining schema
+        /// decodes. Only the migration itself may drop schemas, those exceeding the bounds.
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let (on_chain, stored): (StorageVersion, u64) =
+                Decode::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+            ensure!(
+                Pallet::<T>::on_chain_storage_version() == 1,
+                "storage version not bumped to 1"
+            );
+            let keys = Schemas::<T>::iter_keys().count() as u64;
+            let decoded = Schemas::<T>::iter_values().count() as u64;
+            ensure!(decoded == keys, "undecodable schemas after the migration");
+            if on_chain == 0 {
+                ensure!(keys <= stored, "schemas added by the migration");
+            } else {
+                ensure!(keys == stored, "schemas dropped by a skipped migration");
+            }
+            Pallet::<T>::do_try_state()
+        }
+    }
+
+    fn migrate_record<T: Config>(old: OldSchemaRecord) -> Option<SchemaRecord<T>> {
+        Some(SchemaRecord {
+            version: old.version,
+            deprecated: old.deprecated,
+            issuer_did: old.issuer_did.try_into().ok()?,
+            schema_hash: old.schema_hash,
+            schema_uri: old.schema_uri.try_into().ok()?,
+        })
+    }
+}
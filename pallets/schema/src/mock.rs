@@ -0,0 +1,36 @@
+//! Test runtime of the schema pallet.
+
+use crate as schema;
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{ConstU32, ConstU64},
+};
+
+pub use did_test_utils::*;
+
+parameter_types! {
+    pub const IssuerRole: did::KeyRole = did::KeyRole::AssertionMethod;
+}
+
+construct_runtime!(
+    pub struct Test {
+        System: frame_system,
+        Balances: pallet_balances,
+        Timestamp: pallet_timestamp,
+        Did: did,
+        Schema: schema,
+    }
+);
+
+impl_identity_test_runtime!(Test, ());
+
+impl schema::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type IssuerOrigin = did::EnsureDid<Test, IssuerRole>;
+    type Currency = Balances;
+    type DepositPerItem = ConstU64<1_000>;
+    type DepositPerByte = ConstU64<10>;
+    type MaxDidLength = ConstU32<128>;
+    type MaxUriLength = ConstU32<256>;
+    type WeightInfo = ();
+}
@@ -0,0 +1,258 @@
+use crate::{
+    migrations::v1::MigrateToV1,
+    mock::*,
+    pallet::{RemovedSchemas, SchemaDeposits, Schemas},
+    Error,
+};
+use did::{DidOrigin, KeyRole};
+use frame_support::{
+    assert_noop, assert_ok,
+    storage::unhashed,
+    traits::{GetStorageVersion, OnRuntimeUpgrade, ReservableCurrency, StorageVersion},
+};
+use sp_runtime::DispatchError;
+
+const SCHEMA: &[u8] = br#"{"type":"object"}"#;
+const SCHEMA_URI: &[u8] = b"https://example.com/schema.json";
+
+/// Origin of the DID `did_id`, as `dispatch_as_did` gives it, submitted by `submitter`.
+fn did_origin(did_id: [u8; 32], role: KeyRole, submitter: u64) -> RuntimeOrigin {
+    DidOrigin {
+        did_id,
+        network: None,
+        role,
+        submitter,
+    }
+    .into()
+}
+
+fn issuer(did_id: [u8; 32]) -> RuntimeOrigin {
+    did_origin(did_id, KeyRole::AssertionMethod, ALICE)
+}
+
+fn issuer_did(did_id: [u8; 32]) -> Vec<u8> {
+    DidOrigin {
+        did_id,
+        network: None,
+        role: KeyRole::AssertionMethod,
+        submitter: ALICE,
+    }
+    .did()
+}
+
+fn schema_id() -> [u8; 32] {
+    Schema::schema_id_from_schema(SCHEMA)
+}
+
+#[test]
+fn register_schema_reserves_the_deposit_of_the_submitter() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Schema::register_schema(
+            issuer([1; 32]),
+            SCHEMA.to_vec(),
+            SCHEMA_URI.to_vec()
+        ));
+
+        let record = Schemas::<Test>::get(schema_id()).unwrap();
+        assert_eq!(record.issuer_did.to_vec(), issuer_did([1; 32]));
+        assert_eq!(record.schema_hash, sp_io::hashing::blake2_256(SCHEMA));
+        let deposit = SchemaDeposits::<Test>::get(schema_id()).unwrap();
+        assert_eq!(deposit.owner, ALICE);
+        assert_eq!(Balances::reserved_balance(ALICE), deposit.amount);
+
+        assert_noop!(
+            Schema::register_schema(issuer([2; 32]), SCHEMA.to_vec(), SCHEMA_URI.to_vec()),
+            Error::<Test>::SchemaAlreadyExists
+        );
+    });
+}
+
+#[test]
+fn removing_a_deprecated_schema_releases_its_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Schema::register_schema(
+            did_origin([1; 32], KeyRole::AssertionMethod, BOB),
+            SCHEMA.to_vec(),
+            SCHEMA_URI.to_vec()
+        ));
+        let id = Schema::schema_string_from_schema_id(&schema_id());
+
+        assert_noop!(
+            Schema::remove_schema(issuer([1; 32]), id.clone()),
+            Error::<Test>::SchemaNotDeprecated
+        );
+        assert_ok!(Schema::deprecate_schema(issuer([1; 32]), id.clone()));
+        assert_noop!(
+            Schema::remove_schema(issuer([2; 32]), id.clone()),
+            Error::<Test>::IssuerMismatch
+        );
+
+        // Any account may submit the call for the issuer; the deposit goes back to its payer.
+        assert_ok!(Schema::remove_schema(issuer([1; 32]), id.clone()));
+        assert!(!Schemas::<Test>::contains_key(schema_id()));
+        assert!(!SchemaDeposits::<Test>::contains_key(schema_id()));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+
+        assert!(RemovedSchemas::<Test>::contains_key(schema_id()));
+        assert_noop!(
+            Schema::remove_schema(issuer([1; 32]), id),
+            Error::<Test>::SchemaNotFound
+        );
+        assert_noop!(
+            Schema::register_schema(issuer([2; 32]), SCHEMA.to_vec(), SCHEMA_URI.to_vec()),
+            Error::<Test>::SchemaRemoved
+        );
+    });
+}
+
+#[test]
+fn schemas_stay_within_their_bounds() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Schema::register_schema(issuer([1; 32]), SCHEMA.to_vec(), vec![b'u'; 257]),
+            Error::<Test>::SchemaUriTooLong
+        );
+        assert!(!Schemas::<Test>::contains_key(schema_id()));
+    });
+}
+
+#[test]
+fn schemas_are_issued_by_assertion_method_keys() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Schema::register_schema(
+                RuntimeOrigin::signed(ALICE),
+                SCHEMA.to_vec(),
+                SCHEMA_URI.to_vec()
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Schema::register_schema(
+                did_origin([1; 32], KeyRole::Authentication, ALICE),
+                SCHEMA.to_vec(),
+                SCHEMA_URI.to_vec(),
+            ),
+            DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn only_the_issuer_deprecates_a_schema() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Schema::register_schema(
+            issuer([1; 32]),
+            SCHEMA.to_vec(),
+            SCHEMA_URI.to_vec()
+        ));
+        let id = Schema::schema_string_from_schema_id(&schema_id());
+
+        assert_noop!(
+            Schema::deprecate_schema(issuer([2; 32]), id.clone()),
+            Error::<Test>::IssuerMismatch
+        );
+        assert_ok!(Schema::deprecate_schema(issuer([1; 32]), id.clone()));
+        let record = Schemas::<Test>::get(schema_id()).unwrap();
+        assert!(record.deprecated);
+        assert_eq!(record.version, 1);
+        assert_noop!(
+            Schema::deprecate_schema(issuer([1; 32]), id),
+            Error::<Test>::SchemaDeprecated
+        );
+    });
+}
+
+#[test]
+fn issuers_register_schemas_through_dispatch_as_did() {
+    new_test_ext().execute_with(|| {
+        let key = pair(1);
+        let did = create_did(ALICE, &key);
+        assert_ok!(Did::update_roles(
+            RuntimeOrigin::signed(ALICE),
+            did.clone(),
+            b"#key-1".to_vec(),
+            vec![KeyRole::AssertionMethod, KeyRole::CapabilityInvocation],
+        ));
+
+        let call = RuntimeCall::Schema(crate::Call::register_schema {
+            schema_json: SCHEMA.to_vec(),
+            schema_uri: SCHEMA_URI.to_vec(),
+        });
+        let did_id: [u8; 32] = bs58::decode(&did[b"did:qsb:".len()..])
+            .into_vec()
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let payload = Did::dispatch_payload(&did_id, &KeyRole::AssertionMethod, &BOB, &call);
+        let signature = sign(&key, &payload, 1);
+        assert_ok!(Did::dispatch_as_did(
+            RuntimeOrigin::signed(BOB),
+            did.clone(),
+            KeyRole::AssertionMethod,
+            Box::new(call.clone()),
+            signature.clone(),
+        ));
+
+        assert_eq!(
+            Schemas::<Test>::get(schema_id())
+                .unwrap()
+                .issuer_did
+                .to_vec(),
+            did
+        );
+        assert_eq!(SchemaDeposits::<Test>::get(schema_id()).unwrap().owner, BOB);
+        assert_noop!(
+            Did::dispatch_as_did(
+                RuntimeOrigin::signed(BOB),
+                did,
+                KeyRole::AssertionMethod,
+                Box::new(call),
+                signature,
+            ),
+            did::Error::<Test>::InvalidSignature
+        );
+    });
+}
+
+#[test]
+fn migration_to_v1_drops_schemas_beyond_the_bounds() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<Schema>();
+        // Records in the original layout, encoded like the old unbounded record.
+        let old = |issuer_did: Vec<u8>| (0u64, false, issuer_did, [0u8; 32], SCHEMA_URI.to_vec());
+        unhashed::put(
+            &Schemas::<Test>::hashed_key_for([1; 32]),
+            &old(b"did:qsb:issuer".to_vec()),
+        );
+        unhashed::put(
+            &Schemas::<Test>::hashed_key_for([2; 32]),
+            &old(vec![b'd'; 129]),
+        );
+
+        MigrateToV1::<Test>::on_runtime_upgrade();
+
+        assert_eq!(Schema::on_chain_storage_version(), 1);
+        assert_eq!(
+            Schemas::<Test>::get([1; 32]).unwrap().issuer_did.to_vec(),
+            b"did:qsb:issuer".to_vec()
+        );
+        assert!(!Schemas::<Test>::contains_key([2; 32]));
+    });
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn try_state_catches_deposits_of_missing_schemas() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Schema::register_schema(
+            issuer([1; 32]),
+            SCHEMA.to_vec(),
+            SCHEMA_URI.to_vec()
+        ));
+        assert_ok!(Schema::do_try_state());
+
+        Schemas::<Test>::remove(schema_id());
+        assert!(Schema::do_try_state().is_err());
+    });
+}
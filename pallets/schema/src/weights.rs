@@ -0,0 +1,106 @@
+//! Weights for schema
+//!
+//! PLACEHOLDER WEIGHTS, NOT BENCHMARK OUTPUT. No run of `benchmark pallet` produced these
+//! figures. They are rough estimates from the benchmarks in `benchmarking.rs` and the
+//! `MaxEncodedLen` of the stored records, deliberately overestimated to stay safe until
+//! real figures exist: the execution times are twice the estimate, and every call is
+//! charged one read and one write more than the storage it is known to touch. The layout
+//! follows the CLI's so that its output can replace this file as is. Generate real
+//! weights on reference hardware with:
+//!
+//! ./target/release/qsb-node benchmark pallet --chain dev --pallet schema --extrinsic '*' \
+//!     --steps 50 --repeat 20 --output pallets/schema/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for schema.
+pub trait WeightInfo {
+	fn register_schema(l: u32) -> Weight;
+	fn deprecate_schema() -> Weight;
+	fn remove_schema() -> Weight;
+}
+
+/// Weights for schema using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: Schema Schemas (r:1 w:1)
+	/// Proof: Schema Schemas (max_values: None, max_size: Some(469), added: 2944, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Schema SchemaDeposits (r:0 w:1)
+	/// Proof: Schema SchemaDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// The range of component `l` is `[0, 65536]`.
+	fn register_schema(l: u32) -> Weight {
+		Weight::from_parts(60_236_000, 8_066)
+			.saturating_add(Weight::from_parts(5_230, 0).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: Schema Schemas (r:1 w:1)
+	/// Proof: Schema Schemas (max_values: None, max_size: Some(469), added: 2944, mode: MaxEncodedLen)
+	fn deprecate_schema() -> Weight {
+		Weight::from_parts(37_808_000, 2_944)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Schema Schemas (r:1 w:1)
+	/// Proof: Schema Schemas (max_values: None, max_size: Some(469), added: 2944, mode: MaxEncodedLen)
+	/// Storage: Schema SchemaDeposits (r:1 w:1)
+	/// Proof: Schema SchemaDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Schema RemovedSchemas (r:0 w:1)
+	/// Proof: Schema RemovedSchemas (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	fn remove_schema() -> Weight {
+		Weight::from_parts(64_000_000, 8_110)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: Schema Schemas (r:1 w:1)
+	/// Proof: Schema Schemas (max_values: None, max_size: Some(469), added: 2944, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Schema SchemaDeposits (r:0 w:1)
+	/// Proof: Schema SchemaDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// The range of component `l` is `[0, 65536]`.
+	fn register_schema(l: u32) -> Weight {
+		Weight::from_parts(60_236_000, 8_066)
+			.saturating_add(Weight::from_parts(5_230, 0).saturating_mul(l.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: Schema Schemas (r:1 w:1)
+	/// Proof: Schema Schemas (max_values: None, max_size: Some(469), added: 2944, mode: MaxEncodedLen)
+	fn deprecate_schema() -> Weight {
+		Weight::from_parts(37_808_000, 2_944)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: Schema Schemas (r:1 w:1)
+	/// Proof: Schema Schemas (max_values: None, max_size: Some(469), added: 2944, mode: MaxEncodedLen)
+	/// Storage: Schema SchemaDeposits (r:1 w:1)
+	/// Proof: Schema SchemaDeposits (max_values: None, max_size: Some(88), added: 2563, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Schema RemovedSchemas (r:0 w:1)
+	/// Proof: Schema RemovedSchemas (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	fn remove_schema() -> Weight {
+		Weight::from_parts(64_000_000, 8_110)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+}